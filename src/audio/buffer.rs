@@ -36,16 +36,29 @@ use std::sync::{
 };
 
 /// A 2D point representing an XY sample
-/// Left channel = X, Right channel = Y
+/// Left channel = X, Right channel = Y, optional third channel = Z (beam)
 #[derive(Clone, Copy, Debug, Default)]
 pub struct XYSample {
     pub x: f32,
     pub y: f32,
+    /// Beam intensity (1.0 = lit, 0.0 = blanked / pen up)
+    pub z: f32,
 }
 
 impl XYSample {
+    /// Create a lit sample
     pub fn new(x: f32, y: f32) -> Self {
-        Self { x, y }
+        Self { x, y, z: 1.0 }
+    }
+
+    /// Create a sample with an explicit beam intensity
+    pub fn with_z(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Whether the beam is off for this sample
+    pub fn is_blanked(&self) -> bool {
+        self.z <= 0.0
     }
 }
 
@@ -4,27 +4,193 @@
 //! abstracting the cpal setup and stream management.
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{FromSample, Sample};
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use cpal::{
+    BufferSize, FromSample, Sample, SizedSample, StreamConfig, SupportedBufferSize,
+    SupportedStreamConfig,
+};
+use std::cell::OnceCell;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
+use thiserror::Error;
 
 use super::buffer::{SampleBuffer, XYSample};
-use crate::effects::{EffectChain, LfoScale, LfoWaveform, Rotate};
-use crate::shapes::Shape;
+use super::monitor::{self, mono_sum, MonitorTap, TAP_CAPACITY};
+use crate::effects::{
+    clamp_to_unit_circle, AtomicRng, BoundsLimiter, EffectChain, Jitter, LfoScale, LfoWaveform,
+    OutOfBoundsMode, Ripple, Rotate, Scale, SequencerEffect, SequencerTarget, StepSequencer, Tile,
+};
+use crate::loader::LatestWorker;
+use crate::shapes::{Path, Shape};
 
 /// Audio engine configuration
+#[derive(Clone)]
 pub struct AudioConfig {
     /// How many times per second to trace the shape (Hz)
     pub frequency: f32,
+    /// Master speed: scales the trace rate and the effect clock together
+    ///
+    /// Below 1 the whole picture slows down, e.g. for a ritardando; the
+    /// trace rate still stays within the supported frequency range.
+    pub time_scale: f32,
     /// Output volume (0.0 to 1.0)
     pub volume: f32,
+    /// Stream buffer size in frames (None = device default)
+    ///
+    /// Smaller buffers lower latency for live control at the cost of
+    /// a higher risk of underruns.
+    pub buffer_frames: Option<u32>,
+    /// Scale each shape so its peak coordinate fills the screen
+    pub auto_fit: bool,
+    /// Blank (Z = 0) jumps between consecutive samples longer than this (0 = off)
+    pub blank_distance: f32,
+    /// Radius around the origin the beam is hurried through (0 = off)
+    ///
+    /// Figures that keep passing through the center burn a bright dot
+    /// there on a real tube. Samples inside the radius are dropped, so the
+    /// beam crosses the zone in a single step.
+    pub center_dead_zone: f32,
+    /// Start of the traced parameter range (0.0 to 1.0)
+    pub start_t: f32,
+    /// End of the traced parameter range (0.0 to 1.0)
+    ///
+    /// Together with `start_t` this loops a sub-range of the shape, e.g.
+    /// half a circle. An end before the start traces the range backwards.
+    pub end_t: f32,
+    /// Constant rotation of the output signal (degrees)
+    ///
+    /// Unlike the animated Rotate effect this is a fixed mastering-style
+    /// matrix, for matching the orientation of a particular scope.
+    pub output_rotation: f32,
+    /// Stereo width: gain on the difference (side) signal, 1.0 = unchanged
+    pub stereo_width: f32,
+    /// Most points per trace, whatever the importer produced (0 = no limit)
+    ///
+    /// Larger traces are uniformly decimated, which raises the trace rate
+    /// so dense shapes don't flicker.
+    pub max_total_points: usize,
+    /// Repeat the start point at the end of closed shapes
+    ///
+    /// Sampling stops just short of `t = 1`, so at low sample counts the
+    /// last segment of a closed figure falls short of the start.
+    pub close_gap: bool,
+    /// Fraction of each trace spent sweeping back from the end to the start
+    /// of open shapes (0 = instant jump)
+    ///
+    /// Gives a visible, evenly swept flyback line instead of a jump.
+    pub retrace_fraction: f32,
+    /// Extra samples held at each sharp corner (0 = off)
+    ///
+    /// The beam lingers on the corners, drawing the points that give a
+    /// figure its character brighter than the edges between them. The
+    /// held samples come out of the trace's budget, so the trace rate
+    /// doesn't change.
+    pub corner_dwell: usize,
+    /// Passes over a closed shape per trace (1 = a single pass)
+    ///
+    /// Layering the figure brightens it, and with `per_rev_rotation` it
+    /// builds spirograph-like rosettes. The passes share one trace's
+    /// samples, so the trace rate doesn't change.
+    pub revolutions: usize,
+    /// Rotation added on each pass after the first (degrees)
+    pub per_rev_rotation: f32,
+    /// How many samples ahead of X the Y channel reads (negative = behind)
+    ///
+    /// A small lag between the channels skews the trace into a pseudo-3D
+    /// ribbon, a staple of scope art.
+    pub channel_phase_offset: i32,
+    /// Output channel carrying X (0-based)
+    ///
+    /// Multi-channel interfaces may want X/Y on e.g. outputs 3/4. Channels
+    /// outside the device's layout fall back to the first two. Takes
+    /// effect when the stream is (re)started.
+    pub x_channel: usize,
+    /// Output channel carrying Y (0-based)
+    pub y_channel: usize,
+    /// Output channel carrying Z blanking (0-based, None = off)
+    ///
+    /// Z is a DC level (1 = lit), so it's only written to a channel chosen
+    /// here; every channel not routed stays silent. Takes effect when the
+    /// stream is (re)started.
+    pub z_channel: Option<usize>,
+    /// Second device the output is mirrored to, summed to mono (None = off)
+    ///
+    /// For listening on headphones or speakers while the scope is driven
+    /// from another interface. Takes effect when the stream is (re)started.
+    pub monitor_device: Option<String>,
+    /// Sample format to ask the output device for
+    ///
+    /// Takes effect when the stream is (re)started.
+    pub output_format: OutputFormat,
+    /// Add triangular dither when the output format is an integer one
+    ///
+    /// Takes effect when the stream is (re)started.
+    pub dither: bool,
+}
+
+/// Sample format preference for the output stream
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum OutputFormat {
+    /// Whatever the device defaults to
+    #[default]
+    Auto,
+    /// 32-bit float
+    Float,
+    /// 16-bit integer
+    Int16,
+}
+
+impl OutputFormat {
+    /// Get all formats
+    pub fn all() -> &'static [OutputFormat] {
+        &[OutputFormat::Auto, OutputFormat::Float, OutputFormat::Int16]
+    }
+
+    /// Get the name of this format
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Auto => "Device default",
+            OutputFormat::Float => "32-bit float",
+            OutputFormat::Int16 => "16-bit integer",
+        }
+    }
+
+    /// The cpal format asked for, if any
+    fn sample_format(&self) -> Option<cpal::SampleFormat> {
+        match self {
+            OutputFormat::Auto => None,
+            OutputFormat::Float => Some(cpal::SampleFormat::F32),
+            OutputFormat::Int16 => Some(cpal::SampleFormat::I16),
+        }
+    }
 }
 
 impl Default for AudioConfig {
     fn default() -> Self {
         Self {
             frequency: 80.0, // 80 Hz = 80 traces per second
+            time_scale: 1.0,
             volume: 0.8,
+            buffer_frames: None,
+            auto_fit: false,
+            blank_distance: 0.0,
+            center_dead_zone: 0.0,
+            start_t: 0.0,
+            end_t: 1.0,
+            output_rotation: 0.0,
+            stereo_width: 1.0,
+            max_total_points: 0,
+            close_gap: false,
+            retrace_fraction: 0.0,
+            corner_dwell: 0,
+            revolutions: 1,
+            per_rev_rotation: 0.0,
+            channel_phase_offset: 0,
+            x_channel: 0,
+            y_channel: 1,
+            z_channel: None,
+            monitor_device: None,
+            output_format: OutputFormat::Auto,
+            dither: true,
         }
     }
 }
@@ -38,6 +204,10 @@ struct ShapeData {
     samples: Vec<XYSample>,
     /// Name of the current shape
     name: String,
+    /// Offset of the Y channel's read index from X's, in samples
+    y_offset: i32,
+    /// Previous trace's output, for trace overlap
+    history: TraceHistory,
 }
 
 impl Default for ShapeData {
@@ -45,12 +215,14 @@ impl Default for ShapeData {
         Self {
             samples: Vec::new(),
             name: "None".to_string(),
+            y_offset: 0,
+            history: TraceHistory::default(),
         }
     }
 }
 
 /// Effect parameters shared with audio thread
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct EffectParams {
     /// Rotation speed in radians per second
     pub rotation_speed: f32,
@@ -66,6 +238,65 @@ pub struct EffectParams {
     pub scale_lfo_enabled: bool,
     /// Scale LFO waveform shape
     pub scale_lfo_waveform: LfoWaveform,
+    /// Cycles of the scale LFO spread along the path (0 = whole shape pulses)
+    pub scale_lfo_spatial: f32,
+    /// Whether the step sequencer is enabled
+    pub sequencer_enabled: bool,
+    /// Step values (0.0 to 1.0)
+    pub sequencer_steps: Vec<f32>,
+    /// Sequencer tempo in BPM
+    pub sequencer_bpm: f32,
+    /// Sequencer steps per beat
+    pub sequencer_rate: f32,
+    /// Sequencer output at a step value of 0
+    pub sequencer_min: f32,
+    /// Sequencer output at a step value of 1
+    pub sequencer_max: f32,
+    /// Parameter the sequencer drives
+    pub sequencer_target: SequencerTarget,
+    /// Whether tiling is enabled
+    pub tile_enabled: bool,
+    /// Tiles across
+    pub tile_x: u32,
+    /// Tiles down
+    pub tile_y: u32,
+    /// Mirror alternate tiles
+    pub tile_mirror: bool,
+    /// Extra uniform scale from spring smoothing and the visualizer (1.0 = at rest)
+    pub spring_scale: f32,
+    /// Extra fixed rotation driven by the visualizer (radians)
+    pub reactive_angle: f32,
+    /// Whether radial ripple modulation is enabled
+    pub ripple_enabled: bool,
+    /// Ripples per revolution
+    pub ripple_freq: f32,
+    /// Ripple depth as a fraction of the radius
+    pub ripple_depth: f32,
+    /// Whether random per-sample jitter is enabled
+    pub jitter_enabled: bool,
+    /// Largest jitter displacement on each axis
+    pub jitter_amount: f32,
+    /// How points pushed past ±1 by the effects are handled
+    pub out_of_bounds: OutOfBoundsMode,
+    /// Finally pull anything outside the unit circle back onto it
+    pub radial_limit: bool,
+    /// Effect to audition on its own, ignoring the others' enable flags
+    pub solo: Option<EffectSlot>,
+    /// Rate of the effect clock relative to real time (1.0 = real time)
+    pub time_scale: f32,
+    /// Scale on the Z (brightness) output, e.g. for idle dimming (1.0 = full)
+    pub brightness: f32,
+}
+
+/// A user-facing effect in the chain, for soloing
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum EffectSlot {
+    Jitter,
+    Ripple,
+    Rotation,
+    ScaleLfo,
+    Sequencer,
+    Tile,
 }
 
 impl Default for EffectParams {
@@ -78,49 +309,329 @@ impl Default for EffectParams {
             scale_lfo_max: 1.2,
             scale_lfo_enabled: false,
             scale_lfo_waveform: LfoWaveform::Sine,
+            scale_lfo_spatial: 0.0,
+            sequencer_enabled: false,
+            sequencer_steps: vec![1.0, 0.5, 0.75, 0.5],
+            sequencer_bpm: 120.0,
+            sequencer_rate: 1.0,
+            sequencer_min: 0.5,
+            sequencer_max: 1.0,
+            sequencer_target: SequencerTarget::Size,
+            tile_enabled: false,
+            tile_x: 2,
+            tile_y: 2,
+            tile_mirror: true,
+            spring_scale: 1.0,
+            reactive_angle: 0.0,
+            ripple_enabled: false,
+            ripple_freq: 8.0,
+            ripple_depth: 0.1,
+            jitter_enabled: false,
+            jitter_amount: 0.01,
+            out_of_bounds: OutOfBoundsMode::Clamp,
+            radial_limit: false,
+            solo: None,
+            time_scale: 1.0,
+            brightness: 1.0,
         }
     }
 }
 
 impl EffectParams {
+    /// Whether an effect belongs in the chain: the soloed one if any,
+    /// otherwise every enabled one
+    fn includes(&self, slot: EffectSlot, enabled: bool) -> bool {
+        match self.solo {
+            Some(solo) => solo == slot,
+            None => enabled,
+        }
+    }
+
+    /// Whether the effects keep the figure moving on their own
+    pub fn is_animated(&self) -> bool {
+        let rotating = self.includes(
+            EffectSlot::Rotation,
+            self.rotation_enabled && self.rotation_speed != 0.0,
+        );
+        let sequencing = self.includes(
+            EffectSlot::Sequencer,
+            self.sequencer_enabled && !self.sequencer_steps.is_empty(),
+        );
+        let pulsing = self.includes(EffectSlot::ScaleLfo, self.scale_lfo_enabled);
+        let jittering = self.includes(
+            EffectSlot::Jitter,
+            self.jitter_enabled && self.jitter_amount != 0.0,
+        );
+        jittering || self.time_scale > 0.0 && (rotating || sequencing || pulsing)
+    }
+
     /// Build an EffectChain from the current parameters
-    fn build_chain(&self) -> EffectChain {
+    ///
+    /// Jitter draws from `jitter_rng`, so its noise carries on from one
+    /// chain to the next instead of repeating.
+    fn build_chain(&self, jitter_rng: &Arc<AtomicRng>) -> EffectChain {
         let mut chain = EffectChain::new();
 
-        if self.rotation_enabled && self.rotation_speed != 0.0 {
+        if self.solo.is_none() && self.spring_scale != 1.0 {
+            chain.add(Scale::uniform(self.spring_scale));
+        }
+
+        if self.solo.is_none() && self.reactive_angle != 0.0 {
+            chain.add(Rotate::new(self.reactive_angle));
+        }
+
+        if self.includes(
+            EffectSlot::Ripple,
+            self.ripple_enabled && self.ripple_depth != 0.0,
+        ) {
+            chain.add(Ripple::new(self.ripple_freq, self.ripple_depth));
+        }
+
+        if self.includes(
+            EffectSlot::Jitter,
+            self.jitter_enabled && self.jitter_amount != 0.0,
+        ) {
+            chain.add(Jitter::new(self.jitter_amount, Arc::clone(jitter_rng)));
+        }
+
+        if self.includes(
+            EffectSlot::Rotation,
+            self.rotation_enabled && self.rotation_speed != 0.0,
+        ) {
             chain.add(Rotate::animated(self.rotation_speed));
         }
 
-        if self.scale_lfo_enabled {
+        if self.includes(EffectSlot::ScaleLfo, self.scale_lfo_enabled) {
             chain.add(
                 LfoScale::new(self.scale_lfo_freq, self.scale_lfo_min, self.scale_lfo_max)
-                    .waveform(self.scale_lfo_waveform),
+                    .waveform(self.scale_lfo_waveform)
+                    .spatial(self.scale_lfo_spatial),
             );
         }
 
+        if self.includes(
+            EffectSlot::Sequencer,
+            self.sequencer_enabled && !self.sequencer_steps.is_empty(),
+        ) {
+            chain.add(SequencerEffect::new(
+                StepSequencer::new(
+                    self.sequencer_steps.clone(),
+                    self.sequencer_bpm,
+                    self.sequencer_rate,
+                    self.sequencer_min,
+                    self.sequencer_max,
+                ),
+                self.sequencer_target,
+            ));
+        }
+
+        if self.includes(EffectSlot::Tile, self.tile_enabled) {
+            chain.add(Tile::new(self.tile_x, self.tile_y, self.tile_mirror));
+        }
+
         chain
     }
 }
 
+/// Slowest supported trace rate (Hz)
+pub const MIN_FREQUENCY: f32 = 1.0;
+
+/// Fastest supported trace rate (Hz)
+pub const MAX_FREQUENCY: f32 = 1000.0;
+
+/// Floor on samples per trace, so very fast rates still draw something
+const MIN_SAMPLES_PER_SHAPE: usize = 10;
+
+/// Ceiling on samples per trace (one second at 96kHz)
+const MAX_SAMPLES_PER_SHAPE: usize = 96_000;
+
+/// Upper bound on per-shape sample hints (keeps trace rate above ~5Hz at 48kHz)
+const MAX_SUGGESTED_SAMPLES: usize = 8192;
+
+/// Peak level auto-fit scales shapes to (leaves a small margin at the edges)
+const AUTO_FIT_PEAK: f32 = 0.95;
+
+/// Largest trace checked for self-intersections (the sweep can go quadratic)
+const MAX_CROSSING_CHECK_POINTS: usize = 4096;
+
+/// Shapes whose samples all fit in a box this small are treated as a single dot
+const DEGENERATE_EXTENT: f32 = 1e-4;
+
+/// Turn between consecutive segments, in radians, above which a sample is a corner
+///
+/// Well below 45° so a right angle falling between two samples, which shows
+/// up as two 45° turns, still counts.
+const CORNER_ANGLE: f32 = 0.5;
+
 /// How often to push samples to the visualization buffer
 /// (every Nth sample to reduce lock contention)
 const VIZ_DECIMATION: usize = 8;
 
+/// Handles shared between the engine and the audio callback
+///
+/// Cloning only bumps reference counts, so each stream callback gets
+/// its own cheap copy.
+#[derive(Clone)]
+struct StreamShared {
+    is_playing: Arc<AtomicBool>,
+    shape_data: Arc<RwLock<ShapeData>>,
+    sample_index: Arc<AtomicUsize>,
+    buffer: SampleBuffer,
+    effect_params: Arc<RwLock<EffectParams>>,
+    effect_chain: Arc<RwLock<Arc<EffectChain>>>,
+    effect_clock: Arc<AtomicU64>,
+    invert_y: Arc<AtomicBool>,
+    max_slew: Arc<AtomicU32>,
+    trace_overlap: Arc<AtomicU32>,
+    /// Set when a buffer had to clamp the output to ±1
+    clipped: Arc<AtomicBool>,
+    x_channel: usize,
+    y_channel: usize,
+    z_channel: Option<usize>,
+    /// Mono copy of the output for the monitor stream, if one is open
+    monitor: Option<Arc<MonitorTap>>,
+    /// Dither integer output formats
+    dither: bool,
+}
+
+/// Per-stream output state carried between callbacks
+#[derive(Default)]
+struct StreamState {
+    slew: SlewLimiter,
+    bounds: BoundsLimiter,
+    dither: Dither,
+    /// Effect chain from the last callback, reused while a swap holds the lock
+    chain: Option<Arc<EffectChain>>,
+}
+
+/// Triangular-PDF dither ahead of integer conversion
+///
+/// A slow figure moves by less than one integer step per sample, so plain
+/// rounding turns its curves into staircases that show on the scope. The
+/// sum of two uniform values half a step wide decorrelates the rounding
+/// error from the signal, trading the steps for a faint, even hiss.
+struct Dither {
+    rng: AtomicRng,
+    /// One quantization step of the output format (0 = off)
+    step: f32,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl Dither {
+    fn new(step: f32) -> Self {
+        Self {
+            rng: AtomicRng::new(0),
+            step,
+        }
+    }
+
+    /// Add up to one step of noise, either way, to `value`
+    fn apply(&self, value: f32) -> f32 {
+        if self.step == 0.0 {
+            return value;
+        }
+        value + (self.rng.next_bipolar() + self.rng.next_bipolar()) * 0.5 * self.step
+    }
+}
+
+/// Size of one quantization step (in the [-1, 1] range) for a sample
+/// format, or 0 for float formats
+fn quantization_step(format: cpal::SampleFormat) -> f32 {
+    let bits = match format {
+        cpal::SampleFormat::I8 | cpal::SampleFormat::U8 => 8,
+        cpal::SampleFormat::I16 | cpal::SampleFormat::U16 => 16,
+        cpal::SampleFormat::I32 | cpal::SampleFormat::U32 => 32,
+        _ => return 0.0,
+    };
+    2.0 / 2f32.powi(bits)
+}
+
+/// Marks a history slot no trace has filled yet
+const EMPTY_SLOT: u64 = u64::MAX;
+
+/// Blends each trace with the previous one, sample for sample
+///
+/// Drawing every trace over the last makes animated figures denser, like a
+/// signal-domain afterglow. Holds one trace of effected output, X and Y
+/// packed as f32 bits per slot. The slots are sized on the UI thread
+/// whenever the trace length changes, so the callback never allocates.
+#[derive(Default)]
+struct TraceHistory {
+    slots: Box<[AtomicU64]>,
+}
+
+impl TraceHistory {
+    fn new(len: usize) -> Self {
+        Self {
+            slots: (0..len).map(|_| AtomicU64::new(EMPTY_SLOT)).collect(),
+        }
+    }
+
+    /// Forget the previous trace
+    fn clear(&self) {
+        for slot in self.slots.iter() {
+            slot.store(EMPTY_SLOT, Ordering::Relaxed);
+        }
+    }
+
+    /// Mix `amount` of the previous trace's sample at `idx` into this one
+    fn blend(&self, idx: usize, x: f32, y: f32, amount: f32) -> (f32, f32) {
+        let Some(slot) = self.slots.get(idx) else {
+            return (x, y);
+        };
+        let packed = ((x.to_bits() as u64) << 32) | y.to_bits() as u64;
+        match slot.swap(packed, Ordering::Relaxed) {
+            EMPTY_SLOT => (x, y),
+            previous => {
+                let px = f32::from_bits((previous >> 32) as u32);
+                let py = f32::from_bits(previous as u32);
+                (x + (px - x) * amount, y + (py - y) * amount)
+            }
+        }
+    }
+}
+
+/// Caps how far X and Y may move between consecutive output samples
+///
+/// Galvanometers and some scopes can't follow instantaneous jumps; limiting
+/// the per-sample step trades sharp corners for a signal they can track.
+/// Lives in the stream callback so it carries over between buffers.
+#[derive(Default)]
+struct SlewLimiter {
+    /// Last emitted position
+    last: Option<(f32, f32)>,
+}
+
+impl SlewLimiter {
+    /// Limit the step from the previous output (`max_step <= 0` = off)
+    fn limit(&mut self, x: f32, y: f32, max_step: f32) -> (f32, f32) {
+        let out = match self.last {
+            Some((lx, ly)) if max_step > 0.0 => (
+                lx + (x - lx).clamp(-max_step, max_step),
+                ly + (y - ly).clamp(-max_step, max_step),
+            ),
+            _ => (x, y),
+        };
+        self.last = Some(out);
+        out
+    }
+}
+
 /// Write audio samples for any sample format
-#[allow(clippy::too_many_arguments)]
 fn write_audio_samples<T: Sample + FromSample<f32>>(
     data: &mut [T],
     channels: usize,
-    is_playing: &AtomicBool,
-    shape_data: &RwLock<ShapeData>,
-    sample_index: &AtomicUsize,
-    buffer: &SampleBuffer,
-    effect_params: &RwLock<EffectParams>,
-    total_samples: &AtomicU64,
+    shared: &StreamShared,
+    state: &mut StreamState,
     sample_rate: f32,
 ) {
     // Check if we should output audio
-    if !is_playing.load(Ordering::Relaxed) {
+    if !shared.is_playing.load(Ordering::Relaxed) {
         // Output silence
         for sample in data.iter_mut() {
             *sample = T::EQUILIBRIUM;
@@ -129,7 +640,7 @@ fn write_audio_samples<T: Sample + FromSample<f32>>(
     }
 
     // Try to read shape data (non-blocking for audio thread)
-    let shape_guard = match shape_data.try_read() {
+    let shape_guard = match shared.shape_data.try_read() {
         Ok(guard) => guard,
         Err(_) => {
             // Couldn't get lock - output silence
@@ -151,53 +662,447 @@ fn write_audio_samples<T: Sample + FromSample<f32>>(
     let num_shape_samples = shape_guard.samples.len();
 
     // Get current index and calculate new index after this buffer
-    let start_idx = sample_index.load(Ordering::Relaxed);
-    let start_total = total_samples.load(Ordering::Relaxed);
+    let start_idx = shared.sample_index.load(Ordering::Relaxed);
+    let start_clock = f64::from_bits(shared.effect_clock.load(Ordering::Relaxed));
     let num_frames = data.len() / channels;
 
-    // Try to get effect chain (use empty chain if locked)
-    let chain = effect_params
+    // The chain is built on the UI thread; taking a reference to it here
+    // keeps the callback free of allocation. While a swap holds the lock,
+    // keep playing the chain from the last callback. The UI side keeps
+    // retired chains alive, so letting go of ours never frees one here.
+    if let Ok(current) = shared.effect_chain.try_read() {
+        if !state
+            .chain
+            .as_ref()
+            .is_some_and(|c| Arc::ptr_eq(c, &current))
+        {
+            state.chain = Some(Arc::clone(&current));
+        }
+    }
+    let chain = state.chain.clone();
+    let (out_of_bounds, radial_limit, time_scale, brightness) = shared
+        .effect_params
         .try_read()
-        .map(|e| e.build_chain())
-        .unwrap_or_default();
+        .map(|e| {
+            let time_scale = e.time_scale.max(0.0);
+            let brightness = e.brightness.clamp(0.0, 1.0);
+            (e.out_of_bounds, e.radial_limit, time_scale, brightness)
+        })
+        .unwrap_or((OutOfBoundsMode::default(), false, 1.0, 1.0));
+
+    // Output polarity is applied once here, after all effects
+    let y_sign = if shared.invert_y.load(Ordering::Relaxed) {
+        -1.0
+    } else {
+        1.0
+    };
+    let max_slew = f32::from_bits(shared.max_slew.load(Ordering::Relaxed));
+    let overlap = f32::from_bits(shared.trace_overlap.load(Ordering::Relaxed));
+    let mut clipped = false;
 
     // Generate audio samples
     for (frame_num, frame) in data.chunks_mut(channels).enumerate() {
         // Calculate wrapped index for this frame
         let idx = (start_idx + frame_num) % num_shape_samples;
         let xy = shape_guard.samples[idx];
+        let y_idx = phase_offset_index(idx, shape_guard.y_offset, num_shape_samples);
+        let y = shape_guard.samples[y_idx].y;
+        // With a phase offset X and Y come from different points, so the
+        // beam is blanked if either of them is
+        let z = xy.z.min(shape_guard.samples[y_idx].z);
 
         // Calculate time for effects
-        let current_sample = start_total + frame_num as u64;
-        let time = current_sample as f32 / sample_rate;
+        let time = advance_clock(start_clock, frame_num, sample_rate, time_scale) as f32;
 
-        // Apply effects
-        let (ex, ey) = chain.apply(xy.x, xy.y, time);
+        // Apply effects, telling them how far along the trace this sample is
+        let t = idx as f32 / num_shape_samples as f32;
+        let (ex, ey) = match &chain {
+            Some(chain) => chain.apply_at(xy.x, y, time, t),
+            None => (xy.x, y),
+        };
+        let (ex, ey) = if overlap > 0.0 {
+            shape_guard.history.blend(idx, ex, ey, overlap)
+        } else {
+            (ex, ey)
+        };
+        // The radial limit goes first: a per-axis clamp would square off
+        // the point and lose the angle it keeps
+        let (ex, ey) = if radial_limit {
+            let clamped = clamp_to_unit_circle(ex, ey);
+            clipped |= clamped != (ex, ey);
+            clamped
+        } else {
+            (ex, ey)
+        };
+        let (ex, ey) = state.bounds.apply(out_of_bounds, ex, ey);
+        if idx == num_shape_samples - 1 {
+            state.bounds.end_trace();
+        }
+        let (ex, ey) = state.slew.limit(ex, ey * y_sign, max_slew);
 
-        // Output to audio channels (Left = X, Right = Y)
+        // Output to audio channels (X and Y on the routed channels, Z
+        // blanking only where routed, the rest silent)
         if channels >= 2 {
-            frame[0] = T::from_sample(ex);
-            frame[1] = T::from_sample(ey);
-            // Fill any extra channels with silence
-            for ch in frame.iter_mut().skip(2) {
+            let (x_channel, y_channel) =
+                route_channels(shared.x_channel, shared.y_channel, channels);
+            for ch in frame.iter_mut() {
                 *ch = T::EQUILIBRIUM;
             }
+            frame[x_channel] = T::from_sample(state.dither.apply(ex));
+            frame[y_channel] = T::from_sample(state.dither.apply(ey));
+            if let Some(z_channel) = shared.z_channel {
+                if z_channel < channels && z_channel != x_channel && z_channel != y_channel {
+                    frame[z_channel] = T::from_sample(z * brightness);
+                }
+            }
         } else {
-            frame[0] = T::from_sample((ex + ey) / 2.0); // Mono mix
+            frame[0] = T::from_sample(state.dither.apply(mono_sum(ex, ey)));
+        }
+        if let Some(tap) = &shared.monitor {
+            tap.push(mono_sum(ex, ey));
         }
 
         // Push effected samples to visualization buffer
         if (start_idx + frame_num).is_multiple_of(VIZ_DECIMATION) {
-            buffer.push(XYSample::new(ex, ey));
+            shared.buffer.push(XYSample::with_z(ex, ey, z));
         }
     }
 
+    if state.bounds.take_clipped() || clipped {
+        shared.clipped.store(true, Ordering::Relaxed);
+    }
+
     // Update sample index with wrap-around to prevent overflow
     let new_idx = (start_idx + num_frames) % num_shape_samples;
-    sample_index.store(new_idx, Ordering::Relaxed);
+    shared.sample_index.store(new_idx, Ordering::Relaxed);
+
+    // Advance the effect clock past this buffer
+    let end_clock = advance_clock(start_clock, num_frames, sample_rate, time_scale);
+    shared
+        .effect_clock
+        .store(end_clock.to_bits(), Ordering::Relaxed);
+}
+
+/// Effect clock `frames` samples after `start`, running at `time_scale`
+fn advance_clock(start: f64, frames: usize, sample_rate: f32, time_scale: f32) -> f64 {
+    start + frames as f64 / sample_rate as f64 * time_scale as f64
+}
+
+/// Clamp the X/Y routing to the device's channels
+///
+/// Each axis keeps its channel if the device has it; otherwise X falls back to
+/// the first channel and Y to the first one X isn't using, so the two never
+/// land on the same channel.
+fn route_channels(x_channel: usize, y_channel: usize, channels: usize) -> (usize, usize) {
+    let x = if x_channel < channels { x_channel } else { 0 };
+    let y = if y_channel < channels && y_channel != x {
+        y_channel
+    } else if x == 0 {
+        1
+    } else {
+        0
+    };
+    (x, y)
+}
+
+/// Index `offset` samples away from `idx`, wrapping around the trace
+fn phase_offset_index(idx: usize, offset: i32, len: usize) -> usize {
+    (idx as i64 + offset as i64).rem_euclid(len as i64) as usize
+}
+
+/// Number of samples in one trace at the given rate, kept within sane bounds
+fn samples_per_trace(sample_rate: f32, frequency: f32) -> usize {
+    let frequency = frequency.clamp(MIN_FREQUENCY, MAX_FREQUENCY);
+    ((sample_rate / frequency) as usize).clamp(MIN_SAMPLES_PER_SHAPE, MAX_SAMPLES_PER_SHAPE)
+}
+
+/// Uniformly pick `budget` samples, always keeping the first and last
+fn decimate(samples: &[XYSample], budget: usize) -> Vec<XYSample> {
+    let n = samples.len();
+    if n <= budget {
+        return samples.to_vec();
+    }
+    if budget < 2 {
+        return samples[..budget].to_vec();
+    }
+    (0..budget)
+        .map(|i| samples[i * (n - 1) / (budget - 1)])
+        .collect()
+}
+
+/// Output matrix: scale the side (X - Y) signal by `width`, then rotate by `degrees`
+fn output_matrix(degrees: f32, width: f32) -> [[f32; 2]; 2] {
+    // Mid/side width: mid = (x + y) / 2 is kept, side = (x - y) / 2 is scaled
+    let keep = (1.0 + width) / 2.0;
+    let cross = (1.0 - width) / 2.0;
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    [
+        [cos * keep - sin * cross, cos * cross - sin * keep],
+        [sin * keep + cos * cross, sin * cross + cos * keep],
+    ]
+}
+
+/// Gain that brings the largest absolute coordinate up (or down) to `AUTO_FIT_PEAK`
+fn auto_fit_gain(samples: &[XYSample]) -> f32 {
+    let peak = samples
+        .iter()
+        .map(|s| s.x.abs().max(s.y.abs()))
+        .fold(0.0f32, f32::max);
+
+    if peak > f32::EPSILON {
+        AUTO_FIT_PEAK / peak
+    } else {
+        1.0
+    }
+}
+
+/// Replace points with a NaN or infinite coordinate by the last finite
+/// point before them (or the origin), returning how many were replaced
+fn replace_non_finite(samples: &mut [XYSample]) -> usize {
+    let mut last_valid = XYSample::new(0.0, 0.0);
+    let mut replaced = 0;
+    for s in samples.iter_mut() {
+        if s.x.is_finite() && s.y.is_finite() {
+            last_valid = *s;
+        } else {
+            *s = last_valid;
+            replaced += 1;
+        }
+    }
+    replaced
+}
+
+/// Whether every sample lies within `DEGENERATE_EXTENT` of the others
+fn is_degenerate(samples: &[XYSample]) -> bool {
+    let Some(first) = samples.first() else {
+        return true;
+    };
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (first.x, first.x, first.y, first.y);
+    for s in samples {
+        min_x = min_x.min(s.x);
+        max_x = max_x.max(s.x);
+        min_y = min_y.min(s.y);
+        max_y = max_y.max(s.y);
+    }
+    (max_x - min_x).max(max_y - min_y) < DEGENERATE_EXTENT
+}
+
+/// Whether the trace turns sharply at sample `i`
+///
+/// With `closed`, the corner at the wrap from the last sample to the first
+/// is found too.
+fn is_corner(samples: &[XYSample], i: usize, closed: bool) -> bool {
+    let n = samples.len();
+    let (prev, next) = if closed {
+        (samples[(i + n - 1) % n], samples[(i + 1) % n])
+    } else if i > 0 && i + 1 < n {
+        (samples[i - 1], samples[i + 1])
+    } else {
+        return false;
+    };
+    let sample = samples[i];
+    let (ax, ay) = (sample.x - prev.x, sample.y - prev.y);
+    let (bx, by) = (next.x - sample.x, next.y - sample.y);
+    let lengths = (ax * ax + ay * ay).sqrt() * (bx * bx + by * by).sqrt();
+    if lengths <= 0.0 {
+        return false;
+    }
+    let turn = ((ax * bx + ay * by) / lengths).clamp(-1.0, 1.0).acos();
+    turn > CORNER_ANGLE
+}
+
+/// Repeat each sample where the trace turns sharply `dwell` extra times
+fn add_corner_dwell(samples: &[XYSample], dwell: usize, closed: bool) -> Vec<XYSample> {
+    let mut out = Vec::with_capacity(samples.len());
+    for (i, &sample) in samples.iter().enumerate() {
+        out.push(sample);
+        if is_corner(samples, i, closed) {
+            out.extend(std::iter::repeat_n(sample, dwell));
+        }
+    }
+    out
+}
+
+/// Sample `count` points evenly across `span` of the shape from `start_t`
+fn sample_span<S: Shape + ?Sized>(
+    shape: &S,
+    start_t: f32,
+    span: f32,
+    count: usize,
+) -> Vec<XYSample> {
+    (0..count)
+        .map(|i| {
+            let t = start_t + span * i as f32 / count as f32;
+            let (x, y) = shape.sample(t);
+            let z = if shape.is_blanked(t) { 0.0 } else { 1.0 };
+            XYSample::with_z(x, y, z)
+        })
+        .collect()
+}
+
+/// Repeat a closed trace `revolutions` times, each pass rotated a further
+/// `degrees` about the origin
+fn add_revolutions(samples: &[XYSample], revolutions: usize, degrees: f32) -> Vec<XYSample> {
+    let mut out = Vec::with_capacity(samples.len() * revolutions);
+    for pass in 0..revolutions {
+        let (sin, cos) = (degrees * pass as f32).to_radians().sin_cos();
+        out.extend(
+            samples
+                .iter()
+                .map(|s| XYSample::with_z(s.x * cos - s.y * sin, s.x * sin + s.y * cos, s.z)),
+        );
+    }
+    out
+}
+
+/// Blank every sample reached by a jump longer than `blank_distance`
+///
+/// Importers don't have to mark gaps between contours or mesh edges
+/// themselves: any transition this long is treated as a pen-up move.
+/// This is the signal-side counterpart to the display's long-line culling.
+/// The wrap from the last sample back to the first is checked too.
+fn mark_pen_up(samples: &mut [XYSample], blank_distance: f32) {
+    let n = samples.len();
+    if n < 2 {
+        return;
+    }
+
+    let limit_sq = blank_distance * blank_distance;
+    let jumps: Vec<bool> = (0..n)
+        .map(|i| {
+            let prev = samples[(i + n - 1) % n];
+            let cur = samples[i];
+            let dx = cur.x - prev.x;
+            let dy = cur.y - prev.y;
+            dx * dx + dy * dy > limit_sq
+        })
+        .collect();
+
+    for (sample, jump) in samples.iter_mut().zip(jumps) {
+        if jump {
+            sample.z = 0.0;
+        }
+    }
+}
+
+/// Drop the samples closer than `radius` to the origin
+///
+/// A shape lying entirely inside the zone is kept as it is, rather than
+/// leaving nothing to draw.
+fn skip_dead_zone(samples: &[XYSample], radius: f32) -> Vec<XYSample> {
+    let outside: Vec<XYSample> = samples
+        .iter()
+        .filter(|s| s.x.hypot(s.y) >= radius)
+        .copied()
+        .collect();
+    if outside.is_empty() {
+        samples.to_vec()
+    } else {
+        outside
+    }
+}
 
-    // Update total sample counter for time tracking
-    total_samples.fetch_add(num_frames as u64, Ordering::Relaxed);
+/// Build an output stream for a concrete sample type
+fn build_output_stream<T: SizedSample + FromSample<f32>>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    shared: StreamShared,
+    sample_rate: f32,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    let channels = config.channels as usize;
+    let step = if shared.dither {
+        quantization_step(T::FORMAT)
+    } else {
+        0.0
+    };
+    let mut state = StreamState {
+        dither: Dither::new(step),
+        ..Default::default()
+    };
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            write_audio_samples(data, channels, &shared, &mut state, sample_rate);
+        },
+        |err| log::error!("Audio stream error: {}", err),
+        None,
+    )
+}
+
+/// Build a stream config from the device default and the requested buffer size
+///
+/// A fixed size is clamped into the range the device reports. If the device
+/// can't report a range, the request is passed through as-is and `start`
+/// falls back to the default size should the stream fail to build.
+fn stream_config(supported: &SupportedStreamConfig, buffer_frames: Option<u32>) -> StreamConfig {
+    let mut config = supported.config();
+    config.buffer_size = match (buffer_frames, supported.buffer_size()) {
+        (None, _) => BufferSize::Default,
+        (Some(frames), SupportedBufferSize::Range { min, max }) => {
+            BufferSize::Fixed(frames.clamp(*min, *max))
+        }
+        (Some(frames), SupportedBufferSize::Unknown) => BufferSize::Fixed(frames),
+    };
+    config
+}
+
+/// Device config in the preferred sample format, falling back to the default
+///
+/// The default config's sample rate is kept; a device without the format
+/// at that rate runs in its default format.
+fn output_config(
+    device: &cpal::Device,
+    preference: OutputFormat,
+) -> Result<SupportedStreamConfig, cpal::DefaultStreamConfigError> {
+    let default = device.default_output_config()?;
+    let Some(format) = preference.sample_format() else {
+        return Ok(default);
+    };
+    if default.sample_format() == format {
+        return Ok(default);
+    }
+    let rate = default.sample_rate();
+    let matching = device
+        .supported_output_configs()
+        .ok()
+        .and_then(|mut ranges| {
+            ranges.find(|range| {
+                range.sample_format() == format
+                    && range.channels() == default.channels()
+                    && (range.min_sample_rate()..=range.max_sample_rate()).contains(&rate)
+            })
+        });
+    match matching {
+        Some(range) => Ok(range.with_sample_rate(rate)),
+        None => {
+            log::warn!(
+                "Output device has no {} format, using {:?}",
+                preference.name(),
+                default.sample_format()
+            );
+            Ok(default)
+        }
+    }
+}
+
+/// Open and start the stream mirroring the output to a monitor device
+fn start_monitor(
+    name: &str,
+    sample_rate: u32,
+    tap: Arc<MonitorTap>,
+) -> Result<cpal::Stream, String> {
+    let device = monitor::find_output_device(name).ok_or("device not found")?;
+    let supported = monitor::monitor_config(&device, sample_rate).map_err(|e| e.to_string())?;
+    let config = supported.config();
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => monitor::build_monitor_stream::<f32>(&device, &config, tap),
+        cpal::SampleFormat::I16 => monitor::build_monitor_stream::<i16>(&device, &config, tap),
+        cpal::SampleFormat::U16 => monitor::build_monitor_stream::<u16>(&device, &config, tap),
+        format => return Err(format!("unsupported sample format {:?}", format)),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    Ok(stream)
 }
 
 /// High-level audio output engine
@@ -211,6 +1116,9 @@ pub struct AudioEngine {
     /// The audio output stream (kept alive to continue playback)
     stream: Option<cpal::Stream>,
 
+    /// Stream mirroring the output to the monitor device, if enabled
+    monitor_stream: Option<cpal::Stream>,
+
     /// Buffer for sharing samples with the UI
     buffer: SampleBuffer,
 
@@ -235,84 +1143,604 @@ pub struct AudioEngine {
     /// Effect parameters shared with audio thread
     effect_params: Arc<RwLock<EffectParams>>,
 
-    /// Total samples played (for time tracking in effects)
-    total_samples: Arc<AtomicU64>,
-}
+    /// Effect chain built from `effect_params`, shared with the audio thread
+    effect_chain: Arc<RwLock<Arc<EffectChain>>>,
 
-impl AudioEngine {
-    /// Create a new audio engine
-    ///
-    /// # Arguments
-    /// * `buffer` - Shared sample buffer for visualization
-    pub fn new(buffer: SampleBuffer) -> Self {
-        Self {
-            is_playing: Arc::new(AtomicBool::new(false)),
-            stream: None,
-            buffer,
-            config: AudioConfig::default(),
-            shape_data: Arc::new(RwLock::new(ShapeData::default())),
-            sample_index: Arc::new(AtomicUsize::new(0)),
-            status: "Ready".to_string(),
-            sample_rate: 48000.0,
-            samples_per_shape: 600, // 48000 / 80 = 600 samples per shape at 80Hz
-            effect_params: Arc::new(RwLock::new(EffectParams::default())),
-            total_samples: Arc::new(AtomicU64::new(0)),
-        }
-    }
+    /// Chains swapped out but possibly still held by the audio thread,
+    /// dropped here once nothing else refers to them
+    retired_chains: Vec<Arc<EffectChain>>,
 
-    /// Update effect parameters
-    pub fn set_effects(&self, params: EffectParams) {
-        if let Ok(mut effects) = self.effect_params.write() {
-            *effects = params;
-        }
-    }
+    /// Noise source for jitter, kept across chain rebuilds
+    jitter_rng: Arc<AtomicRng>,
 
-    /// Check if audio is currently playing
-    pub fn is_playing(&self) -> bool {
-        self.is_playing.load(Ordering::Relaxed)
-    }
+    /// Seconds of effect time played, as f64 bits; runs at the time scale
+    effect_clock: Arc<AtomicU64>,
 
-    /// Get the current shape name
-    pub fn current_shape_name(&self) -> String {
-        self.shape_data.read().unwrap().name.clone()
-    }
+    /// Whether to invert the Y output (for scopes with opposite polarity)
+    invert_y: Arc<AtomicBool>,
 
-    /// Set the shape to render
-    ///
-    /// This pre-samples the shape and stores it for the audio thread.
-    /// The number of samples is based on sample_rate / frequency.
-    pub fn set_shape<S: Shape>(&mut self, shape: &S) {
-        // Calculate samples per shape based on frequency
-        self.samples_per_shape = (self.sample_rate / self.config.frequency) as usize;
-        self.samples_per_shape = self.samples_per_shape.max(10); // Minimum 10 samples
-
-        // Sample the shape
-        let mut samples = Vec::with_capacity(self.samples_per_shape);
-        for i in 0..self.samples_per_shape {
-            let t = i as f32 / self.samples_per_shape as f32;
-            let (x, y) = shape.sample(t);
-            samples.push(XYSample::new(
-                x * self.config.volume,
-                y * self.config.volume,
-            ));
-        }
+    /// Maximum per-sample X/Y step, as f32 bits (0 = unlimited)
+    max_slew: Arc<AtomicU32>,
 
-        // Update shared shape data
-        if let Ok(mut data) = self.shape_data.write() {
-            data.samples = samples;
-            data.name = shape.name().to_string();
-        }
+    /// Blend of the previous trace into the current one, as f32 bits (0 = off)
+    trace_overlap: Arc<AtomicU32>,
+
+    /// Set by the audio thread when the output was clamped to ±1
+    clipped: Arc<AtomicBool>,
+
+    /// Warning about the last shape set (e.g. degenerate geometry)
+    shape_warning: Option<String>,
+
+    /// Point count before decimation, if the last shape exceeded the budget
+    decimated_from: Option<usize>,
+
+    /// Sampled geometry of the current shape, before the output stage
+    geometry: Vec<XYSample>,
+
+    /// Name of the shape `geometry` was sampled from
+    geometry_name: String,
+
+    /// Auto-fit times volume, as last applied by `refresh_output`
+    output_gain: f32,
+
+    /// Largest |x| or |y| the output stage produced, as last applied by
+    /// `refresh_output`
+    output_peak: f32,
+
+    /// Bounding box of the current shape as (min_x, min_y, max_x, max_y)
+    shape_bounds: Option<(f32, f32, f32, f32)>,
+
+    /// Whether the current trace joins its end back to its start
+    geometry_closed: bool,
+
+    /// Times the current trace crosses itself, if it was small enough to
+    /// check; counted on first request, since the sweep is costly
+    crossings: OnceCell<Option<usize>>,
+
+    /// Number of times a shape has been sampled
+    resample_count: usize,
+
+    /// Worker thread that samples heavy shapes off the UI thread
+    resampler: LatestWorker<SampledShape>,
+}
+
+/// Reasons `AudioEngine::try_set_shape` refuses a shape
+#[derive(Error, Debug, PartialEq)]
+pub enum AudioError {
+    #[error("Shape \"{0}\" has no points")]
+    Empty(String),
+
+    #[error("Shape \"{name}\" produced {count} non-finite points")]
+    NonFinite { name: String, count: usize },
+
+    #[error("Shape \"{0}\" has no extent - all its points coincide")]
+    Degenerate(String),
+
+    #[error("Resampling {0} failed unexpectedly")]
+    ResampleFailed(String),
+}
+
+/// Geometry sampled from a shape, ready for `AudioEngine::apply_sampled`
+pub struct SampledShape {
+    samples: Vec<XYSample>,
+    samples_per_shape: usize,
+    decimated_from: Option<usize>,
+    warning: Option<String>,
+    empty: bool,
+    non_finite: usize,
+    degenerate: bool,
+    closed: bool,
+    name: String,
+    bounds: (f32, f32, f32, f32),
+}
+
+impl SampledShape {
+    /// Sampled points, before the output stage
+    pub fn samples(&self) -> &[XYSample] {
+        &self.samples
+    }
+
+    /// Check the geometry is fit to play
+    ///
+    /// An empty shape is also degenerate, and replaced non-finite points
+    /// can make one so; the first cause found is reported.
+    pub fn check(&self) -> Result<(), AudioError> {
+        if self.empty {
+            Err(AudioError::Empty(self.name.clone()))
+        } else if self.non_finite > 0 {
+            Err(AudioError::NonFinite {
+                name: self.name.clone(),
+                count: self.non_finite,
+            })
+        } else if self.degenerate {
+            Err(AudioError::Degenerate(self.name.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Sample `shape` into geometry using the loop region, retrace, point
+/// budget and pen-up settings of `config`
+///
+/// This is the slow part of setting a shape; it touches no engine state,
+/// so it can run on a worker thread.
+pub fn sample_shape<S: Shape + ?Sized>(
+    shape: &S,
+    config: &AudioConfig,
+    sample_rate: f32,
+) -> SampledShape {
+    // Calculate samples per shape based on frequency and master speed
+    let mut samples_per_shape =
+        samples_per_trace(sample_rate, config.frequency * config.time_scale);
+
+    // Dense shapes may ask for more points than the frequency allows
+    if let Some(hint) = shape.suggested_samples() {
+        samples_per_shape = samples_per_shape.max(hint.min(MAX_SUGGESTED_SAMPLES));
+    }
+
+    // Traces that don't end where they start may give part of the
+    // cycle to a swept return
+    let start_t = config.start_t.clamp(0.0, 1.0);
+    let span = config.end_t.clamp(0.0, 1.0) - start_t;
+    let full_loop = shape.is_closed() && span.abs() >= 1.0;
+    let open = !full_loop;
+
+    // Several revolutions share one trace's worth of samples
+    if full_loop && config.revolutions > 1 {
+        samples_per_shape = (samples_per_shape / config.revolutions).max(1);
+    }
+
+    let retrace = if open {
+        let fraction = config.retrace_fraction.clamp(0.0, 0.9);
+        ((samples_per_shape as f32 * fraction).round() as usize)
+            .min(samples_per_shape.saturating_sub(2))
+    } else {
+        0
+    };
+    let drawn = samples_per_shape - retrace;
+
+    // Sample the shape across the loop region
+    let mut samples = sample_span(shape, start_t, span, drawn);
+
+    // Corner dwell comes out of the same budget: resample with fewer
+    // points to leave room for the held samples
+    if config.corner_dwell > 0 {
+        let corners = (0..drawn)
+            .filter(|&i| is_corner(&samples, i, full_loop))
+            .count();
+        let held = corners * config.corner_dwell;
+        if held > 0 && held < drawn / 2 {
+            samples = sample_span(shape, start_t, span, drawn - held);
+        }
+    }
+
+    // A NaN or infinity would reach the DAC as a pop or silence
+    let replaced = replace_non_finite(&mut samples);
+    if replaced > 0 {
+        log::warn!(
+            "Shape {} produced {} non-finite points; replaced them",
+            shape.name(),
+            replaced
+        );
+    }
+
+    // Linger on sharp corners so they draw brighter
+    if config.corner_dwell > 0 {
+        samples = add_corner_dwell(&samples, config.corner_dwell, full_loop);
+    }
+    let drawn = samples.len();
+
+    // Sweep linearly back towards the start; the next trace lands on it
+    if retrace > 0 {
+        let (first, last) = (samples[0], samples[drawn - 1]);
+        for k in 1..=retrace {
+            let f = k as f32 / (retrace + 1) as f32;
+            samples.push(XYSample::new(
+                last.x + (first.x - last.x) * f,
+                last.y + (first.y - last.y) * f,
+            ));
+        }
+    }
+    samples_per_shape = samples.len();
+
+    // Finish closed loops with an explicit closing segment (full traces only)
+    if config.close_gap && full_loop {
+        samples.push(samples[0]);
+        samples_per_shape = samples.len();
+    }
+
+    // Trace full closed loops several times, turning a little each pass
+    if config.revolutions > 1 && full_loop {
+        samples = add_revolutions(&samples, config.revolutions, config.per_rev_rotation);
+        samples_per_shape = samples.len();
+    }
+
+    // Keep dense shapes within the point budget
+    let budget = config.max_total_points;
+    let decimated_from = if budget > 0 && samples.len() > budget {
+        let original = samples.len();
+        samples = decimate(&samples, budget);
+        samples_per_shape = samples.len();
+        Some(original)
+    } else {
+        None
+    };
+
+    // Hurry through the center, in shape units like the pen-up distance
+    if config.center_dead_zone > 0.0 {
+        samples = skip_dead_zone(&samples, config.center_dead_zone);
+        samples_per_shape = samples.len();
+    }
+
+    // A shape that collapses to one spot would just park the beam
+    let degenerate = is_degenerate(&samples);
+    let warning = if degenerate {
+        log::warn!("Shape {} is degenerate (all points coincide)", shape.name());
+        Some(format!(
+            "Shape \"{}\" has no extent - the beam will sit on one spot",
+            shape.name()
+        ))
+    } else {
+        shape.warning()
+    };
+
+    // Blank long jumps before any scaling, so the threshold is in shape units
+    if config.blank_distance > 0.0 {
+        mark_pen_up(&mut samples, config.blank_distance);
+    }
+
+    SampledShape {
+        samples,
+        samples_per_shape,
+        decimated_from,
+        warning,
+        empty: !shape.has_points(),
+        non_finite: replaced,
+        degenerate,
+        closed: shape.is_closed(),
+        name: shape.name().to_string(),
+        bounds: shape.bounds(),
+    }
+}
+
+impl AudioEngine {
+    /// Create a new audio engine
+    ///
+    /// # Arguments
+    /// * `buffer` - Shared sample buffer for visualization
+    pub fn new(buffer: SampleBuffer) -> Self {
+        Self {
+            is_playing: Arc::new(AtomicBool::new(false)),
+            stream: None,
+            monitor_stream: None,
+            buffer,
+            config: AudioConfig::default(),
+            shape_data: Arc::new(RwLock::new(ShapeData::default())),
+            sample_index: Arc::new(AtomicUsize::new(0)),
+            status: "Ready".to_string(),
+            sample_rate: 48000.0,
+            samples_per_shape: 600, // 48000 / 80 = 600 samples per shape at 80Hz
+            effect_params: Arc::new(RwLock::new(EffectParams::default())),
+            effect_chain: Arc::default(),
+            retired_chains: Vec::new(),
+            jitter_rng: Arc::new(AtomicRng::new(0)),
+            effect_clock: Arc::new(AtomicU64::new(0)),
+            invert_y: Arc::new(AtomicBool::new(false)),
+            max_slew: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            trace_overlap: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            clipped: Arc::new(AtomicBool::new(false)),
+            shape_warning: None,
+            decimated_from: None,
+            geometry: Vec::new(),
+            geometry_name: String::new(),
+            output_gain: 1.0,
+            output_peak: 0.0,
+            shape_bounds: None,
+            geometry_closed: false,
+            crossings: OnceCell::new(),
+            resample_count: 0,
+            resampler: LatestWorker::new("resampler"),
+        }
+    }
+
+    /// Update effect parameters
+    ///
+    /// The chain is built here, off the audio thread, and swapped in whole,
+    /// only when the parameters changed. The old chain is kept until the
+    /// audio thread has let go of it, so it's never freed in a callback.
+    pub fn set_effects(&mut self, params: EffectParams) {
+        // Only this side hands out chains, so one nobody else holds is done
+        self.retired_chains.retain(|c| Arc::strong_count(c) > 1);
+
+        if self.effect_params.read().is_ok_and(|p| *p == params) {
+            return;
+        }
+        let chain = Arc::new(params.build_chain(&self.jitter_rng));
+        if let Ok(mut current) = self.effect_chain.write() {
+            self.retired_chains
+                .push(std::mem::replace(&mut *current, chain));
+        }
+        if let Ok(mut effects) = self.effect_params.write() {
+            *effects = params;
+        }
+    }
+
+    /// Set the global Y output polarity
+    ///
+    /// Applied once at the output stage, so both the audio device and the
+    /// on-screen display see the same (possibly inverted) signal.
+    pub fn set_invert_y(&self, invert: bool) {
+        self.invert_y.store(invert, Ordering::Relaxed);
+    }
+
+    /// Whether the Y output is inverted
+    pub fn invert_y(&self) -> bool {
+        self.invert_y.load(Ordering::Relaxed)
+    }
+
+    /// Set the slew-rate limit: the largest X/Y change per output sample (0 = off)
+    pub fn set_max_slew(&self, max_step: f32) {
+        self.max_slew
+            .store(max_step.max(0.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Current slew-rate limit (0 = off)
+    pub fn max_slew(&self) -> f32 {
+        f32::from_bits(self.max_slew.load(Ordering::Relaxed))
+    }
+
+    /// Set how much of the previous trace is blended into each sample (0 to 1)
+    ///
+    /// Unlike the display's persistence this changes the signal itself, so
+    /// a real scope shows the overlapped traces too.
+    pub fn set_trace_overlap(&self, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        let previous = self.trace_overlap.swap(amount.to_bits(), Ordering::Relaxed);
+
+        // History isn't kept while overlap is off, so don't blend a stale one
+        if f32::from_bits(previous) == 0.0 && amount > 0.0 {
+            if let Ok(data) = self.shape_data.read() {
+                data.history.clear();
+            }
+        }
+    }
+
+    /// Current trace overlap (0 = off)
+    pub fn trace_overlap(&self) -> f32 {
+        f32::from_bits(self.trace_overlap.load(Ordering::Relaxed))
+    }
+
+    /// Whether the output was clamped since the last call, resetting the flag
+    pub fn take_clipped(&self) -> bool {
+        self.clipped.swap(false, Ordering::Relaxed)
+    }
+
+    /// Check if audio is currently playing
+    pub fn is_playing(&self) -> bool {
+        self.is_playing.load(Ordering::Relaxed)
+    }
+
+    /// Warning about the last shape set, if any
+    pub fn shape_warning(&self) -> Option<&str> {
+        self.shape_warning.as_deref()
+    }
+
+    /// Points per trace of the current shape, after any decimation
+    pub fn samples_per_shape(&self) -> usize {
+        self.samples_per_shape
+    }
+
+    /// Point count before decimation, if the point budget was exceeded
+    pub fn decimated_from(&self) -> Option<usize> {
+        self.decimated_from
+    }
+
+    /// Bounding box of the current shape, in shape units before the output stage
+    pub fn shape_bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        self.shape_bounds
+    }
+
+    /// Largest |x| or |y| of the current shape after the output stage
+    /// (width, rotation, auto-fit and volume); past 1.0 it clips
+    pub fn output_peak(&self) -> f32 {
+        self.output_peak
+    }
+
+    /// Self-crossings of the current trace, if it was small enough to check
+    ///
+    /// Counted the first time it's asked for after the shape changes, so
+    /// shapes re-set every frame only pay for it while it's on display.
+    pub fn crossing_count(&self) -> Option<usize> {
+        *self.crossings.get_or_init(|| {
+            (self.geometry.len() <= MAX_CROSSING_CHECK_POINTS).then(|| {
+                let points = self.geometry.iter().map(|s| (s.x, s.y)).collect();
+                Path::with_options(points, self.geometry_closed, String::new())
+                    .self_intersections()
+                    .len()
+            })
+        })
+    }
+
+    /// Mean position of the current shape's lit samples
+    pub fn shape_centroid(&self) -> Option<(f32, f32)> {
+        let lit: Vec<_> = self.geometry.iter().filter(|s| s.z > 0.0).collect();
+        if lit.is_empty() {
+            return None;
+        }
+        let n = lit.len() as f32;
+        let (sx, sy) = lit
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), s| (sx + s.x, sy + s.y));
+        Some((sx / n, sy / n))
+    }
+
+    /// Get the current shape name
+    pub fn current_shape_name(&self) -> String {
+        self.shape_data.read().unwrap().name.clone()
+    }
+
+    /// Set the shape to render
+    ///
+    /// This pre-samples the shape and stores it for the audio thread.
+    /// The number of samples is based on sample_rate / frequency, raised
+    /// to the shape's `suggested_samples` hint (capped) for dense geometry.
+    ///
+    /// Problem geometry is still set, with any non-finite points replaced
+    /// and a degenerate shape reported through `shape_warning`; use
+    /// `try_set_shape` to refuse it instead.
+    pub fn set_shape<S: Shape>(&mut self, shape: &S) {
+        // A shape set directly supersedes any resample still in flight
+        self.resampler.cancel();
+        let sampled = sample_shape(shape, &self.config, self.sample_rate);
+        self.apply_sampled(sampled);
+    }
+
+    /// Set the shape to render, unless it is empty, degenerate or has
+    /// non-finite points
+    ///
+    /// On error the current shape keeps playing.
+    pub fn try_set_shape<S: Shape>(&mut self, shape: &S) -> Result<(), AudioError> {
+        self.resampler.cancel();
+        let sampled = sample_shape(shape, &self.config, self.sample_rate);
+        sampled.check()?;
+        self.apply_sampled(sampled);
+        Ok(())
+    }
+
+    /// Build and sample a shape on the worker thread with the current settings
+    ///
+    /// `build` runs on the worker too, so costly construction (mesh
+    /// subdivision, say) stays off the caller's thread. A request that
+    /// hasn't started yet is replaced; `poll_resample` applies the result.
+    pub fn resample_in_background<S: Shape>(
+        &mut self,
+        label: impl Into<String>,
+        build: impl FnOnce() -> S + Send + 'static,
+    ) {
+        let config = self.config.clone();
+        let sample_rate = self.sample_rate;
+        self.resampler
+            .submit(label, move || sample_shape(&build(), &config, sample_rate));
+    }
+
+    /// Apply the newest background resample if it has finished
+    ///
+    /// Returns the request's label and whether its shape was applied once
+    /// it has finished. Shapes `try_set_shape` would refuse are refused
+    /// here too, and the current shape keeps playing.
+    pub fn poll_resample(&mut self) -> Option<(String, Result<(), AudioError>)> {
+        let label = self.resampler.pending().unwrap_or_default().to_string();
+        let result = match self.resampler.poll()? {
+            Some(sampled) => sampled.check().map(|()| self.apply_sampled(sampled)),
+            None => Err(AudioError::ResampleFailed(label.clone())),
+        };
+        Some((label, result))
+    }
+
+    /// Label of the background resample in flight, if any
+    pub fn pending_resample(&self) -> Option<&str> {
+        self.resampler.pending()
+    }
+
+    /// Make already sampled geometry the current shape
+    pub fn apply_sampled(&mut self, sampled: SampledShape) {
+        self.samples_per_shape = sampled.samples_per_shape;
+        self.decimated_from = sampled.decimated_from;
+        self.shape_warning = sampled.warning;
+        self.geometry_closed = sampled.closed;
+        self.crossings = OnceCell::new();
+        self.geometry = sampled.samples;
+        self.geometry_name = sampled.name;
+        self.shape_bounds = Some(sampled.bounds);
+        self.resample_count += 1;
+        self.refresh_output();
+    }
+
+    /// Re-apply the output stage (auto-fit, volume, width, rotation) to the
+    /// cached geometry, without re-sampling the shape
+    ///
+    /// Use this when only those settings changed; anything that affects
+    /// sampling (frequency, loop region, pen-up distance...) needs `set_shape`.
+    pub fn refresh_output(&mut self) {
+        if self.geometry.is_empty() {
+            return;
+        }
+
+        // Apply width and rotation as one output matrix, then fit what
+        // comes out of it to the screen (a rotated or widened figure
+        // peaks higher than the raw geometry) and apply the master volume
+        let [[a, b], [c, d]] = output_matrix(self.config.output_rotation, self.config.stereo_width);
+        let mut samples: Vec<XYSample> = self
+            .geometry
+            .iter()
+            .map(|s| XYSample::with_z(a * s.x + b * s.y, c * s.x + d * s.y, s.z))
+            .collect();
+        let fit = if self.config.auto_fit {
+            auto_fit_gain(&samples)
+        } else {
+            1.0
+        };
+        let gain = fit * self.config.volume;
+        self.output_gain = gain;
+        for s in samples.iter_mut() {
+            s.x *= gain;
+            s.y *= gain;
+        }
+        self.output_peak = samples
+            .iter()
+            .fold(0.0, |peak: f32, s| peak.max(s.x.abs()).max(s.y.abs()));
+
+        // Update shared shape data
+        let same_shape = if let Ok(mut data) = self.shape_data.write() {
+            let same_length = data.samples.len() == samples.len();
+            if !same_length {
+                data.history = TraceHistory::new(samples.len());
+            }
+            let same_shape = same_length && data.name == self.geometry_name;
+            data.samples = samples;
+            data.name.clone_from(&self.geometry_name);
+            data.y_offset = self.config.channel_phase_offset;
+            same_shape
+        } else {
+            false
+        };
+
+        // Animated shapes are re-set every frame with the same name and
+        // sample count; keep the beam where it is so the trace doesn't
+        // restart mid-way. A different shape starts from its beginning.
+        if same_shape {
+            log::debug!("Shape updated: {}", self.geometry_name);
+            return;
+        }
 
         // Reset sample index
         self.sample_index.store(0, Ordering::Relaxed);
 
         log::info!(
             "Shape set: {} ({} samples)",
-            shape.name(),
+            self.geometry_name,
             self.samples_per_shape
         );
     }
 
+    /// The linear part of the output stage as a 2x2 matrix
+    ///
+    /// Width, rotation, auto-fit and volume as last applied by
+    /// `refresh_output`, then the Y inversion. Per-sample effects are not
+    /// included, since they change along the trace.
+    pub fn output_transform(&self) -> [[f32; 2]; 2] {
+        let [[a, b], [c, d]] = output_matrix(self.config.output_rotation, self.config.stereo_width);
+        let gain = self.output_gain;
+        let y_gain = if self.invert_y() { -gain } else { gain };
+        [[a * gain, b * gain], [c * y_gain, d * y_gain]]
+    }
+
+    /// How many times a shape has been sampled (for diagnostics)
+    pub fn resample_count(&self) -> usize {
+        self.resample_count
+    }
+
     /// Start audio playback
     pub fn start(&mut self) {
         if self.stream.is_some() {
@@ -346,8 +1774,8 @@ impl AudioEngine {
         let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
         log::info!("Using output device: {}", device_name);
 
-        // Get the default output configuration
-        let config = match device.default_output_config() {
+        // Get the output configuration, in the preferred format if possible
+        let config = match output_config(&device, self.config.output_format) {
             Ok(c) => c,
             Err(e) => {
                 self.status = format!("Error getting config: {}", e);
@@ -359,107 +1787,52 @@ impl AudioEngine {
         log::info!("Audio config: {:?}", config);
 
         self.sample_rate = config.sample_rate().0 as f32;
-        let channels = config.channels() as usize;
-
-        // Clone values needed in the audio callback
-        let is_playing = Arc::clone(&self.is_playing);
-        let shape_data = Arc::clone(&self.shape_data);
-        let sample_index = Arc::clone(&self.sample_index);
-        let effect_params = Arc::clone(&self.effect_params);
-        let total_samples = Arc::clone(&self.total_samples);
-        let buffer = self.buffer.clone_ref();
         let sample_rate = self.sample_rate;
+        let mut shared = self.stream_shared();
+        if self.config.monitor_device.is_some() {
+            shared.monitor = Some(Arc::new(MonitorTap::new(TAP_CAPACITY)));
+        }
+        let monitor_tap = shared.monitor.clone();
 
         // Build the output stream based on sample format
         let sample_format = config.sample_format();
         log::info!("Sample format: {:?}", sample_format);
 
-        let stream_result = match sample_format {
+        if !matches!(
+            sample_format,
+            cpal::SampleFormat::F32 | cpal::SampleFormat::I16 | cpal::SampleFormat::U16
+        ) {
+            self.status = format!("Unsupported sample format: {:?}", sample_format);
+            log::error!("Unsupported sample format: {:?}", sample_format);
+            return;
+        }
+
+        let build = |stream_config: &StreamConfig| match sample_format {
             cpal::SampleFormat::F32 => {
-                let is_playing = Arc::clone(&is_playing);
-                let shape_data = Arc::clone(&shape_data);
-                let sample_index = Arc::clone(&sample_index);
-                let effect_params = Arc::clone(&effect_params);
-                let total_samples = Arc::clone(&total_samples);
-                let buffer = buffer.clone_ref();
-                device.build_output_stream(
-                    &config.into(),
-                    move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                        write_audio_samples(
-                            data,
-                            channels,
-                            &is_playing,
-                            &shape_data,
-                            &sample_index,
-                            &buffer,
-                            &effect_params,
-                            &total_samples,
-                            sample_rate,
-                        );
-                    },
-                    |err| log::error!("Audio stream error: {}", err),
-                    None,
-                )
+                build_output_stream::<f32>(&device, stream_config, shared.clone(), sample_rate)
             }
             cpal::SampleFormat::I16 => {
-                let is_playing = Arc::clone(&is_playing);
-                let shape_data = Arc::clone(&shape_data);
-                let sample_index = Arc::clone(&sample_index);
-                let effect_params = Arc::clone(&effect_params);
-                let total_samples = Arc::clone(&total_samples);
-                let buffer = buffer.clone_ref();
-                device.build_output_stream(
-                    &config.into(),
-                    move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
-                        write_audio_samples(
-                            data,
-                            channels,
-                            &is_playing,
-                            &shape_data,
-                            &sample_index,
-                            &buffer,
-                            &effect_params,
-                            &total_samples,
-                            sample_rate,
-                        );
-                    },
-                    |err| log::error!("Audio stream error: {}", err),
-                    None,
-                )
+                build_output_stream::<i16>(&device, stream_config, shared.clone(), sample_rate)
             }
             cpal::SampleFormat::U16 => {
-                let is_playing = Arc::clone(&is_playing);
-                let shape_data = Arc::clone(&shape_data);
-                let sample_index = Arc::clone(&sample_index);
-                let effect_params = Arc::clone(&effect_params);
-                let total_samples = Arc::clone(&total_samples);
-                let buffer = buffer.clone_ref();
-                device.build_output_stream(
-                    &config.into(),
-                    move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
-                        write_audio_samples(
-                            data,
-                            channels,
-                            &is_playing,
-                            &shape_data,
-                            &sample_index,
-                            &buffer,
-                            &effect_params,
-                            &total_samples,
-                            sample_rate,
-                        );
-                    },
-                    |err| log::error!("Audio stream error: {}", err),
-                    None,
-                )
-            }
-            format => {
-                self.status = format!("Unsupported sample format: {:?}", format);
-                log::error!("Unsupported sample format: {:?}", format);
-                return;
+                build_output_stream::<u16>(&device, stream_config, shared.clone(), sample_rate)
             }
+            _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
         };
 
+        let mut stream_config = stream_config(&config, self.config.buffer_frames);
+        let mut stream_result = build(&stream_config);
+
+        // Fall back to the device default if the fixed size was rejected
+        if stream_result.is_err() && stream_config.buffer_size != BufferSize::Default {
+            log::warn!(
+                "Buffer size {:?} not supported, falling back to default",
+                stream_config.buffer_size
+            );
+            stream_config.buffer_size = BufferSize::Default;
+            stream_result = build(&stream_config);
+        }
+
         match stream_result {
             Ok(s) => {
                 if let Err(e) = s.play() {
@@ -471,12 +1844,32 @@ impl AudioEngine {
                 let shape_name = self.shape_data.read().unwrap().name.clone();
                 self.is_playing.store(true, Ordering::Relaxed);
                 self.stream = Some(s);
+                let latency = match stream_config.buffer_size {
+                    BufferSize::Fixed(frames) => {
+                        format!("{:.1}ms latency", frames as f32 / sample_rate * 1000.0)
+                    }
+                    BufferSize::Default => "default latency".to_string(),
+                };
                 self.status = format!(
-                    "Playing: {} at {}Hz, {:.0}% volume",
+                    "Playing: {} at {}Hz, {:.0}% volume, {}",
                     shape_name,
                     self.config.frequency,
-                    self.config.volume * 100.0
+                    self.config.volume * 100.0,
+                    latency
                 );
+                if let (Some(name), Some(tap)) = (self.config.monitor_device.clone(), monitor_tap) {
+                    match start_monitor(&name, sample_rate as u32, tap) {
+                        Ok(stream) => {
+                            self.monitor_stream = Some(stream);
+                            self.status.push_str(&format!(", monitoring on {}", name));
+                        }
+                        Err(e) => {
+                            self.status
+                                .push_str(&format!(", monitor unavailable: {}", e));
+                            log::error!("Failed to start monitor stream: {}", e);
+                        }
+                    }
+                }
                 log::info!("Audio started successfully");
             }
             Err(e) => {
@@ -486,14 +1879,58 @@ impl AudioEngine {
         }
     }
 
+    /// Handles for a stream callback
+    fn stream_shared(&self) -> StreamShared {
+        StreamShared {
+            is_playing: Arc::clone(&self.is_playing),
+            shape_data: Arc::clone(&self.shape_data),
+            sample_index: Arc::clone(&self.sample_index),
+            buffer: self.buffer.clone_ref(),
+            effect_params: Arc::clone(&self.effect_params),
+            effect_chain: Arc::clone(&self.effect_chain),
+            effect_clock: Arc::clone(&self.effect_clock),
+            invert_y: Arc::clone(&self.invert_y),
+            max_slew: Arc::clone(&self.max_slew),
+            trace_overlap: Arc::clone(&self.trace_overlap),
+            clipped: Arc::clone(&self.clipped),
+            x_channel: self.config.x_channel,
+            y_channel: self.config.y_channel,
+            z_channel: self.config.z_channel,
+            monitor: None,
+            dither: self.config.dither,
+        }
+    }
+
+    /// Silence the output at once and drop every effect
+    ///
+    /// Stops the stream, clears the effect parameters and rewinds the trace
+    /// and the effect clock, so playback restarts from a known state.
+    pub fn panic(&mut self) {
+        self.stop();
+        self.set_effects(EffectParams::default());
+        self.sample_index.store(0, Ordering::Relaxed);
+        self.effect_clock.store(0.0f64.to_bits(), Ordering::Relaxed);
+        self.status = "Panic: output muted".to_string();
+        log::warn!("Panic: output muted and effects cleared");
+    }
+
     /// Stop audio playback
     pub fn stop(&mut self) {
         self.is_playing.store(false, Ordering::Relaxed);
         self.stream = None;
+        self.monitor_stream = None;
         self.status = "Stopped".to_string();
         log::info!("Audio stopped");
     }
 
+    /// Restart playback so stream-level config (e.g. buffer size) takes effect
+    pub fn restart(&mut self) {
+        if self.is_playing() {
+            self.stop();
+            self.start();
+        }
+    }
+
     /// Toggle playback state
     pub fn toggle(&mut self) {
         if self.is_playing() {
@@ -508,3 +1945,876 @@ impl AudioEngine {
         self.set_shape(shape);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Line, Path, Rectangle};
+
+    /// Render one buffer of a shape through the output stage
+    fn render(shape_data: &Arc<RwLock<ShapeData>>, invert_y: bool) -> Vec<f32> {
+        render_limited(shape_data, invert_y, 0.0)
+    }
+
+    /// Stream state for rendering a shape in tests
+    fn test_shared(
+        shape_data: &Arc<RwLock<ShapeData>>,
+        invert_y: bool,
+        max_slew: f32,
+    ) -> StreamShared {
+        StreamShared {
+            is_playing: Arc::new(AtomicBool::new(true)),
+            shape_data: Arc::clone(shape_data),
+            sample_index: Arc::new(AtomicUsize::new(0)),
+            buffer: SampleBuffer::new(16),
+            effect_params: Arc::new(RwLock::new(EffectParams::default())),
+            effect_chain: Arc::default(),
+            effect_clock: Arc::new(AtomicU64::new(0)),
+            invert_y: Arc::new(AtomicBool::new(invert_y)),
+            max_slew: Arc::new(AtomicU32::new(max_slew.to_bits())),
+            trace_overlap: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            clipped: Arc::new(AtomicBool::new(false)),
+            x_channel: 0,
+            y_channel: 1,
+            z_channel: None,
+            monitor: None,
+            dither: false,
+        }
+    }
+
+    #[test]
+    fn test_dither_adds_bounded_zero_mean_noise() {
+        let step = quantization_step(cpal::SampleFormat::I16);
+        assert_eq!(step, 1.0 / 32768.0);
+        assert_eq!(quantization_step(cpal::SampleFormat::F32), 0.0);
+
+        let dither = Dither::new(step);
+        let n = 100_000;
+        let mut sum = 0.0f64;
+        let mut moved = 0;
+        for _ in 0..n {
+            let noise = dither.apply(0.25) - 0.25;
+            assert!(noise.abs() <= step * 1.001, "{} > {}", noise, step);
+            sum += noise as f64;
+            moved += (noise != 0.0) as usize;
+        }
+        assert!((sum / n as f64).abs() < step as f64 * 0.01);
+        assert!(moved > n / 2);
+
+        // Off for float output
+        assert_eq!(Dither::new(0.0).apply(0.25), 0.25);
+    }
+
+    #[test]
+    fn test_channel_routing() {
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples: vec![XYSample::with_z(0.25, -0.5, 1.0)],
+            name: "Dot".to_string(),
+            y_offset: 0,
+            ..Default::default()
+        }));
+        let mut shared = test_shared(&shape_data, false, 0.0);
+        shared.x_channel = 2;
+        shared.y_channel = 3;
+
+        let mut data = vec![9.0f32; 8];
+        write_audio_samples(&mut data, 4, &shared, &mut StreamState::default(), 48000.0);
+        for frame in data.chunks(4) {
+            assert_eq!(frame, [0.0, 0.0, 0.25, -0.5]);
+        }
+
+        // Z only goes out when routed
+        shared.z_channel = Some(1);
+        let mut data = vec![9.0f32; 8];
+        write_audio_samples(&mut data, 4, &shared, &mut StreamState::default(), 48000.0);
+        for frame in data.chunks(4) {
+            assert_eq!(frame, [0.0, 1.0, 0.25, -0.5]);
+        }
+
+        // Idle dimming scales the routed Z
+        shared.effect_params.write().unwrap().brightness = 0.25;
+        let mut data = vec![9.0f32; 4];
+        write_audio_samples(&mut data, 4, &shared, &mut StreamState::default(), 48000.0);
+        assert_eq!(data, [0.0, 0.25, 0.25, -0.5]);
+        shared.effect_params.write().unwrap().brightness = 1.0;
+        shared.z_channel = None;
+
+        // Channels the device doesn't have fall back to the first two
+        let mut data = vec![9.0f32; 4];
+        write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+        assert_eq!(data, [0.25, -0.5, 0.25, -0.5]);
+
+        // An axis the device has keeps its channel when only the other is missing
+        shared.x_channel = 1;
+        let mut data = vec![9.0f32; 3];
+        write_audio_samples(&mut data, 3, &shared, &mut StreamState::default(), 48000.0);
+        assert_eq!(data, [-0.5, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn test_route_channels() {
+        assert_eq!(route_channels(2, 3, 4), (2, 3));
+        assert_eq!(route_channels(2, 3, 3), (2, 0));
+        assert_eq!(route_channels(3, 2, 3), (0, 2));
+        assert_eq!(route_channels(5, 6, 2), (0, 1));
+        // X and Y never share a channel
+        assert_eq!(route_channels(1, 1, 2), (1, 0));
+        assert_eq!(route_channels(3, 0, 2), (0, 1));
+    }
+
+    #[test]
+    fn test_moving_effects_count_as_animated() {
+        let mut params = EffectParams::default();
+        assert!(!params.is_animated());
+
+        // A ripple on its own stands still
+        params.ripple_enabled = true;
+        assert!(!params.is_animated());
+
+        params.rotation_enabled = true;
+        params.rotation_speed = 1.0;
+        assert!(params.is_animated());
+
+        // A stopped clock freezes everything
+        params.time_scale = 0.0;
+        assert!(!params.is_animated());
+    }
+
+    #[test]
+    fn test_out_of_range_point_sets_clip_flag() {
+        let dot = |x: f32| {
+            Arc::new(RwLock::new(ShapeData {
+                samples: vec![XYSample::new(x, 0.0)],
+                name: "Dot".to_string(),
+                y_offset: 0,
+                ..Default::default()
+            }))
+        };
+        let render = |shared: &StreamShared| {
+            let mut data = vec![0.0f32; 8];
+            write_audio_samples(&mut data, 2, shared, &mut StreamState::default(), 48000.0);
+        };
+
+        let shared = test_shared(&dot(0.5), false, 0.0);
+        render(&shared);
+        assert!(!shared.clipped.load(Ordering::Relaxed));
+
+        let shared = test_shared(&dot(1.5), false, 0.0);
+        render(&shared);
+        assert!(shared.clipped.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_half_time_scale_halves_effect_time() {
+        let clock_after_buffer = |time_scale: f32| {
+            let shape_data = Arc::new(RwLock::new(ShapeData {
+                samples: vec![XYSample::new(0.5, 0.0)],
+                name: "Dot".to_string(),
+                y_offset: 0,
+                ..Default::default()
+            }));
+            let shared = test_shared(&shape_data, false, 0.0);
+            shared.effect_params.write().unwrap().time_scale = time_scale;
+
+            // 480 frames: 10 ms at 48 kHz
+            let mut data = vec![0.0f32; 960];
+            write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+            f64::from_bits(shared.effect_clock.load(Ordering::Relaxed))
+        };
+
+        let real_time = clock_after_buffer(1.0);
+        assert!((real_time - 0.01).abs() < 1e-9);
+        assert!((clock_after_buffer(0.5) - real_time / 2.0).abs() < 1e-9);
+
+        // The trace slows down with it
+        let config = AudioConfig {
+            time_scale: 0.5,
+            ..Default::default()
+        };
+        let slow = sample_shape(&Circle::new(0.5), &config, 48000.0);
+        let normal = sample_shape(&Circle::new(0.5), &AudioConfig::default(), 48000.0);
+        assert_eq!(slow.samples.len(), 2 * normal.samples.len());
+    }
+
+    #[test]
+    fn test_radial_limit_keeps_angle_of_off_axis_point() {
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples: vec![XYSample::new(1.5, 0.5)],
+            name: "Dot".to_string(),
+            ..Default::default()
+        }));
+        let shared = test_shared(&shape_data, false, 0.0);
+        shared.effect_params.write().unwrap().radial_limit = true;
+
+        let mut data = vec![0.0f32; 4];
+        write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+        let (x, y) = (data[0], data[1]);
+        assert!((x.hypot(y) - 1.0).abs() < 1e-5);
+        assert!(
+            (y / x - 0.5 / 1.5).abs() < 1e-5,
+            "angle lost: ({}, {})",
+            x,
+            y
+        );
+        assert!(shared.clipped.load(Ordering::Relaxed));
+    }
+
+    /// Render one buffer with a slew-rate limit
+    fn render_limited(
+        shape_data: &Arc<RwLock<ShapeData>>,
+        invert_y: bool,
+        max_slew: f32,
+    ) -> Vec<f32> {
+        let shared = test_shared(shape_data, invert_y, max_slew);
+        let mut data = vec![0.0f32; 16];
+        write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+        data
+    }
+
+    #[test]
+    fn test_effect_swap_never_frees_on_audio_thread() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Circle::new(0.8));
+        engine.is_playing.store(true, Ordering::Relaxed);
+        let rotating = EffectParams {
+            rotation_enabled: true,
+            rotation_speed: 1.0,
+            ..Default::default()
+        };
+        engine.set_effects(rotating.clone());
+        let shared = engine.stream_shared();
+        let mut state = StreamState::default();
+        let mut data = vec![0.0f32; 16];
+        write_audio_samples(&mut data, 2, &shared, &mut state, 48000.0);
+        let first = Arc::clone(state.chain.as_ref().unwrap());
+
+        // Unchanged parameters keep the chain
+        engine.set_effects(rotating.clone());
+        assert!(Arc::ptr_eq(&engine.effect_chain.read().unwrap(), &first));
+        drop(first);
+
+        // Swapped mid-callback: the callback's copy is not the last one
+        engine.set_effects(EffectParams {
+            tile_enabled: true,
+            ..rotating
+        });
+        assert!(Arc::strong_count(state.chain.as_ref().unwrap()) > 1);
+
+        // While a swap holds the lock, the last chain keeps playing
+        {
+            let _swapping = engine.effect_chain.write().unwrap();
+            write_audio_samples(&mut data, 2, &shared, &mut state, 48000.0);
+            assert_eq!(state.chain.as_ref().unwrap().len(), 1);
+        }
+
+        // Once the callback moves on, the UI side drops the retired chain
+        write_audio_samples(&mut data, 2, &shared, &mut state, 48000.0);
+        assert_eq!(state.chain.as_ref().unwrap().len(), 2);
+        engine.set_effects(EffectParams::default());
+        assert_eq!(engine.retired_chains.len(), 1);
+    }
+
+    #[test]
+    fn test_solo_builds_single_effect_chain() {
+        let mut params = EffectParams {
+            rotation_enabled: true,
+            rotation_speed: 1.0,
+            scale_lfo_enabled: true,
+            tile_enabled: true,
+            spring_scale: 1.2,
+            ..Default::default()
+        };
+        let rng = Arc::new(AtomicRng::new(1));
+        assert_eq!(params.build_chain(&rng).len(), 4);
+
+        params.solo = Some(EffectSlot::Tile);
+        let chain = params.build_chain(&rng);
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain.iter().next().unwrap().name(), "Tile");
+
+        // Soloing jitter brings it in even while it's switched off
+        params.solo = Some(EffectSlot::Jitter);
+        let chain = params.build_chain(&rng);
+        assert_eq!(chain.iter().next().unwrap().name(), "Jitter");
+    }
+
+    #[test]
+    fn test_channel_phase_offset_shifts_y_index() {
+        let samples = (0..8)
+            .map(|i| XYSample::new(i as f32 * 0.1, i as f32 * 0.1))
+            .collect();
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples,
+            name: "Ramp".to_string(),
+            y_offset: 3,
+            ..Default::default()
+        }));
+        let shared = test_shared(&shape_data, false, 0.0);
+
+        let mut data = vec![0.0f32; 16];
+        write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+        for (i, frame) in data.chunks(2).enumerate() {
+            let expected_y = ((i + 3) % 8) as f32 * 0.1;
+            assert!((frame[0] - i as f32 * 0.1).abs() < 1e-6);
+            assert!((frame[1] - expected_y).abs() < 1e-6);
+        }
+
+        assert_eq!(phase_offset_index(1, -3, 8), 6);
+    }
+
+    #[test]
+    fn test_channel_phase_offset_blanks_either_index() {
+        let samples = (0..8)
+            .map(|i| XYSample::with_z(0.0, 0.0, if i == 5 { 0.0 } else { 1.0 }))
+            .collect();
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples,
+            name: "Gap".to_string(),
+            y_offset: 3,
+            ..Default::default()
+        }));
+        let mut shared = test_shared(&shape_data, false, 0.0);
+        shared.z_channel = Some(2);
+
+        // Blanked where X reads point 5 (frame 5) and where Y does (frame 2)
+        let mut data = vec![0.0f32; 24];
+        write_audio_samples(&mut data, 3, &shared, &mut StreamState::default(), 48000.0);
+        let z: Vec<f32> = data.chunks(3).map(|frame| frame[2]).collect();
+        assert_eq!(z, [1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_new_shape_restarts_the_trace() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Circle::new(0.5));
+        let length = engine.geometry.len();
+        engine.sample_index.store(5, Ordering::Relaxed);
+
+        // The same shape animating keeps its place
+        engine.set_shape(&Circle::new(0.6));
+        assert_eq!(engine.sample_index.load(Ordering::Relaxed), 5);
+
+        // A different one of the same length starts over
+        engine.set_shape(&Rectangle::square(0.5));
+        assert_eq!(engine.geometry.len(), length);
+        assert_eq!(engine.sample_index.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_background_resample_matches_synchronous() {
+        let xyz = |s: &[XYSample]| s.iter().map(|p| (p.x, p.y, p.z)).collect::<Vec<_>>();
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Rectangle::new(1.0, 0.5));
+        let expected = xyz(&engine.geometry);
+
+        engine.resample_in_background("rectangle", || Rectangle::new(1.0, 0.5));
+        assert_eq!(engine.pending_resample(), Some("rectangle"));
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let wait = |engine: &mut AudioEngine| loop {
+            if let Some(result) = engine.poll_resample() {
+                return result;
+            }
+            assert!(std::time::Instant::now() < deadline, "resample timed out");
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        };
+        let (label, result) = wait(&mut engine);
+        assert_eq!(label, "rectangle");
+        assert!(result.is_ok());
+        assert_eq!(engine.pending_resample(), None);
+        assert_eq!(xyz(&engine.geometry), expected);
+        assert_eq!(engine.resample_count(), 2);
+
+        // An empty shape is refused and the rectangle keeps playing
+        engine.resample_in_background("empty", || Path::new(Vec::new()));
+        let (_, result) = wait(&mut engine);
+        assert!(matches!(result, Err(AudioError::Empty(_))));
+        assert_eq!(xyz(&engine.geometry), expected);
+        assert_eq!(engine.resample_count(), 2);
+    }
+
+    #[test]
+    fn test_panic_silences_and_clears_effects() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Circle::new(0.8));
+        engine.is_playing.store(true, Ordering::Relaxed);
+        engine.set_effects(EffectParams {
+            rotation_enabled: true,
+            rotation_speed: 1.0,
+            tile_enabled: true,
+            ..Default::default()
+        });
+        engine.sample_index.store(7, Ordering::Relaxed);
+
+        engine.panic();
+
+        assert!(!engine.is_playing());
+        assert_eq!(engine.sample_index.load(Ordering::Relaxed), 0);
+        assert!(engine.effect_chain.read().unwrap().is_empty());
+
+        let mut data = vec![0.5f32; 16];
+        let shared = engine.stream_shared();
+        write_audio_samples(&mut data, 2, &shared, &mut StreamState::default(), 48000.0);
+        assert!(data.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_trace_overlap_blends_previous_cycle() {
+        let trace = |x: f32| (0..4).map(|i| XYSample::new(x, i as f32 * 0.1)).collect();
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples: trace(0.2),
+            name: "A".to_string(),
+            y_offset: 0,
+            history: TraceHistory::new(4),
+        }));
+        let shared = test_shared(&shape_data, false, 0.0);
+        shared
+            .trace_overlap
+            .store(0.5f32.to_bits(), Ordering::Relaxed);
+        let mut state = StreamState::default();
+
+        // First cycle has nothing to blend with
+        let mut first = vec![0.0f32; 8];
+        write_audio_samples(&mut first, 2, &shared, &mut state, 48000.0);
+        assert!((first[0] - 0.2).abs() < 1e-6);
+
+        // Second cycle mixes in the first, point for point
+        shape_data.write().unwrap().samples = trace(0.6);
+        let mut second = vec![0.0f32; 8];
+        write_audio_samples(&mut second, 2, &shared, &mut state, 48000.0);
+        for frame in second.chunks(2) {
+            assert!((frame[0] - 0.4).abs() < 1e-6);
+        }
+        assert!((second[3] - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_slew_limit_caps_large_jumps() {
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples: vec![XYSample::new(-0.8, 0.5), XYSample::new(0.8, -0.5)],
+            name: "Jump".to_string(),
+            y_offset: 0,
+            ..Default::default()
+        }));
+
+        let out = render_limited(&shape_data, false, 0.1);
+        for pair in out.chunks(2).collect::<Vec<_>>().windows(2) {
+            assert!((pair[1][0] - pair[0][0]).abs() <= 0.1 + 1e-6);
+            assert!((pair[1][1] - pair[0][1]).abs() <= 0.1 + 1e-6);
+        }
+        // The first jump is 1.6 wide, so it must have been limited
+        assert!((out[2] - out[0] - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_invert_y_negates_once() {
+        let circle = Circle::new(0.5);
+        let samples = (0..8)
+            .map(|i| {
+                let (x, y) = circle.sample(i as f32 / 8.0);
+                XYSample::new(x, y)
+            })
+            .collect();
+        let shape_data = Arc::new(RwLock::new(ShapeData {
+            samples,
+            name: "Circle".to_string(),
+            y_offset: 0,
+            ..Default::default()
+        }));
+
+        let normal = render(&shape_data, false);
+        let inverted = render(&shape_data, true);
+
+        for (n, i) in normal.chunks(2).zip(inverted.chunks(2)) {
+            assert_eq!(n[0], i[0]); // X untouched
+            assert_eq!(n[1], -i[1]); // Y negated exactly once
+        }
+    }
+
+    #[test]
+    fn test_stream_config_buffer_size() {
+        let supported = SupportedStreamConfig::new(
+            2,
+            cpal::SampleRate(48000),
+            SupportedBufferSize::Range { min: 64, max: 4096 },
+            cpal::SampleFormat::F32,
+        );
+
+        let config = stream_config(&supported, Some(256));
+        assert_eq!(config.buffer_size, BufferSize::Fixed(256));
+
+        // Out-of-range requests are clamped to what the device supports
+        let config = stream_config(&supported, Some(16));
+        assert_eq!(config.buffer_size, BufferSize::Fixed(64));
+
+        let config = stream_config(&supported, None);
+        assert_eq!(config.buffer_size, BufferSize::Default);
+    }
+
+    #[test]
+    fn test_decimate_keeps_endpoints() {
+        let samples: Vec<XYSample> = (0..20_000)
+            .map(|i| XYSample::new(i as f32, -(i as f32)))
+            .collect();
+        let decimated = decimate(&samples, 5000);
+
+        assert_eq!(decimated.len(), 5000);
+        assert_eq!(decimated[0].x, samples[0].x);
+        assert_eq!(decimated[4999].x, samples[19_999].x);
+        assert!(decimated.windows(2).all(|w| w[1].x > w[0].x));
+    }
+
+    #[test]
+    fn test_point_budget_in_set_shape() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.frequency = 2.0; // 24000 points per trace at 48kHz
+        engine.config.max_total_points = 5000;
+        engine.set_shape(&Circle::new(0.5));
+
+        assert_eq!(engine.samples_per_shape(), 5000);
+        assert_eq!(engine.decimated_from(), Some(24_000));
+        assert_eq!(engine.shape_data.read().unwrap().samples.len(), 5000);
+    }
+
+    #[test]
+    fn test_output_rotation_90_degrees() {
+        let [[a, b], [c, d]] = output_matrix(90.0, 1.0);
+        // (1, 0) -> (0, 1)
+        assert!(a.abs() < 1e-6);
+        assert!((c - 1.0).abs() < 1e-6);
+        // (0, 1) -> (-1, 0)
+        assert!((b + 1.0).abs() < 1e-6);
+        assert!(d.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zero_width_collapses_to_mid() {
+        let [[a, b], [c, d]] = output_matrix(0.0, 0.0);
+        let (x, y) = (0.8, 0.2);
+        let (ox, oy) = (a * x + b * y, c * x + d * y);
+        assert!((ox - 0.5).abs() < 1e-6);
+        assert!((oy - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_auto_fit_scales_up_small_shape() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.volume = 1.0;
+        engine.config.auto_fit = true;
+        engine.set_shape(&Circle::new(0.3));
+
+        let data = engine.shape_data.read().unwrap();
+        let peak = data
+            .samples
+            .iter()
+            .map(|s| s.x.abs().max(s.y.abs()))
+            .fold(0.0f32, f32::max);
+        assert!((peak - AUTO_FIT_PEAK).abs() < 0.01);
+        drop(data);
+
+        // Fitted after rotation and width, so neither pushes it past the peak
+        engine.config.output_rotation = 45.0;
+        engine.config.stereo_width = 1.5;
+        engine.set_shape(&Rectangle::new(0.6, 0.6));
+        let data = engine.shape_data.read().unwrap();
+        let peak = data
+            .samples
+            .iter()
+            .map(|s| s.x.abs().max(s.y.abs()))
+            .fold(0.0f32, f32::max);
+        assert!((peak - AUTO_FIT_PEAK).abs() < 0.01, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_output_transform_matches_output_stage() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.volume = 0.7;
+        engine.config.auto_fit = true;
+        engine.config.output_rotation = 30.0;
+        engine.config.stereo_width = 1.5;
+        engine.set_invert_y(true);
+        engine.set_shape(&Rectangle::new(0.6, 0.4));
+
+        let [[a, b], [c, d]] = engine.output_transform();
+        let data = engine.shape_data.read().unwrap();
+        for (raw, out) in engine.geometry.iter().zip(&data.samples) {
+            // The shared samples are inverted in the callback, not here
+            let (x, y) = (a * raw.x + b * raw.y, c * raw.x + d * raw.y);
+            assert!((x - out.x).abs() < 1e-5);
+            assert!((y + out.y).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_samples_per_trace_extremes() {
+        for rate in [44100.0, 48000.0, 96000.0] {
+            let slow = samples_per_trace(rate, MIN_FREQUENCY);
+            let fast = samples_per_trace(rate, MAX_FREQUENCY);
+            assert!(slow > fast);
+            assert!((MIN_SAMPLES_PER_SHAPE..=MAX_SAMPLES_PER_SHAPE).contains(&slow));
+            assert!((MIN_SAMPLES_PER_SHAPE..=MAX_SAMPLES_PER_SHAPE).contains(&fast));
+        }
+
+        // Out-of-range and nonsense rates still give a usable count
+        assert_eq!(samples_per_trace(48000.0, 0.0), 48000);
+        assert!(samples_per_trace(48000.0, 1e9) >= MIN_SAMPLES_PER_SHAPE);
+    }
+
+    #[test]
+    fn test_loop_region_starts_at_start_t() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.volume = 1.0;
+        engine.config.start_t = 0.25;
+        engine.config.end_t = 0.75;
+
+        let circle = Circle::new(0.5);
+        engine.set_shape(&circle);
+
+        let data = engine.shape_data.read().unwrap();
+        let (x, y) = circle.sample(0.25);
+        assert!((data.samples[0].x - x).abs() < 1e-6);
+        assert!((data.samples[0].y - y).abs() < 1e-6);
+
+        // Only the left half of the circle is traced
+        assert!(data.samples.iter().all(|s| s.x <= 1e-6));
+    }
+
+    #[test]
+    fn test_volume_change_reuses_geometry() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.volume = 1.0;
+        engine.set_shape(&Circle::new(0.5));
+        assert_eq!(engine.resample_count(), 1);
+        let before = engine.shape_data.read().unwrap().samples[0];
+
+        engine.config.volume = 0.5;
+        engine.refresh_output();
+        assert_eq!(engine.resample_count(), 1);
+        let after = engine.shape_data.read().unwrap().samples[0];
+        assert!((after.x - before.x * 0.5).abs() < 1e-6);
+        assert!((after.y - before.y * 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_output_peak_includes_volume() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.volume = 1.0;
+        engine.set_shape(&Rectangle::square(2.4));
+        assert!((engine.output_peak() - 1.2).abs() < 1e-5);
+
+        // The shape overhangs ±1, but not once it's turned down
+        engine.config.volume = 0.5;
+        engine.refresh_output();
+        assert!((engine.output_peak() - 0.6).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_close_gap_joins_square() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.frequency = MAX_FREQUENCY; // 48 points per trace
+        let square = Rectangle::square(1.0);
+
+        engine.set_shape(&square);
+        {
+            let data = engine.shape_data.read().unwrap();
+            let (first, last) = (data.samples[0], data.samples[data.samples.len() - 1]);
+            assert!((first.x - last.x).abs() + (first.y - last.y).abs() > 1e-3);
+        }
+
+        engine.config.close_gap = true;
+        engine.set_shape(&square);
+        let data = engine.shape_data.read().unwrap();
+        let (first, last) = (data.samples[0], data.samples[data.samples.len() - 1]);
+        assert_eq!((first.x, first.y), (last.x, last.y));
+    }
+
+    #[test]
+    fn test_corner_dwell_clusters_at_square_corners() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Rectangle::square(1.0));
+        let plain = engine.geometry.len();
+
+        engine.config.corner_dwell = 4;
+        engine.set_shape(&Rectangle::square(1.0));
+        // The held samples fit in the same trace, keeping its rate
+        assert_eq!(engine.geometry.len(), plain);
+        assert_eq!(engine.samples_per_shape(), engine.geometry.len());
+
+        // Every repeated sample sits on a corner, and every corner has some
+        let mut corners = [0usize; 4];
+        for pair in engine.geometry.windows(2) {
+            if (pair[0].x, pair[0].y) != (pair[1].x, pair[1].y) {
+                continue;
+            }
+            let (x, y) = (pair[1].x, pair[1].y);
+            assert!(
+                (x.abs() - 0.5).abs() < 0.02 && (y.abs() - 0.5).abs() < 0.02,
+                "dwell at ({}, {}) is not on a corner",
+                x,
+                y
+            );
+            corners[usize::from(x > 0.0) + 2 * usize::from(y > 0.0)] += 1;
+        }
+        assert!(corners.iter().all(|&c| c >= 4), "{:?}", corners);
+    }
+
+    #[test]
+    fn test_revolutions_trace_rotated_passes() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Rectangle::square(1.0));
+        let single = engine.geometry.clone();
+
+        engine.config.revolutions = 2;
+        engine.config.per_rev_rotation = 90.0;
+        engine.set_shape(&Rectangle::square(1.0));
+        // Both passes fit in the samples of a single one
+        assert_eq!(engine.geometry.len(), single.len());
+        assert_eq!(engine.samples_per_shape(), engine.geometry.len());
+
+        let (first, second) = engine.geometry.split_at(single.len() / 2);
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(second) {
+            // Rotated a quarter turn: (x, y) -> (-y, x)
+            assert!((b.x + a.y).abs() < 1e-5 && (b.y - a.x).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_retrace_sweeps_linearly_to_start() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.config.frequency = MAX_FREQUENCY; // 48 points per trace
+        engine.config.retrace_fraction = 0.25;
+        engine.set_shape(&Line::new(-1.0, 0.0, 1.0, 0.0));
+
+        let data = engine.shape_data.read().unwrap();
+        assert_eq!(data.samples.len(), 48);
+
+        // 36 drawn points, then 12 evenly spaced steps back towards x = -1
+        let (first, end) = (data.samples[0], data.samples[35]);
+        let step = (first.x - end.x) / 13.0;
+        for (k, s) in data.samples[36..].iter().enumerate() {
+            let expected = end.x + step * (k + 1) as f32;
+            assert!((s.x - expected).abs() < 1e-5, "{} != {}", s.x, expected);
+            assert!(s.y.abs() < 1e-6);
+        }
+    }
+
+    /// Circle that breaks down to NaN over part of its range
+    struct GlitchyCircle;
+
+    impl Shape for GlitchyCircle {
+        fn sample(&self, t: f32) -> (f32, f32) {
+            if (0.3..0.4).contains(&t) {
+                (f32::NAN, f32::INFINITY)
+            } else {
+                Circle::new(0.5).sample(t)
+            }
+        }
+
+        fn name(&self) -> &str {
+            "Glitchy circle"
+        }
+    }
+
+    #[test]
+    fn test_non_finite_points_are_replaced() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&GlitchyCircle);
+
+        let data = engine.shape_data.read().unwrap();
+        assert!(data
+            .samples
+            .iter()
+            .all(|s| s.x.is_finite() && s.y.is_finite()));
+
+        let mut leading = vec![XYSample::new(f32::NAN, 0.2), XYSample::new(0.5, 0.5)];
+        assert_eq!(replace_non_finite(&mut leading), 1);
+        assert_eq!((leading[0].x, leading[0].y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_try_set_shape_reports_each_problem() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Circle::new(0.5));
+        let resamples = engine.resample_count();
+
+        let empty = Path::with_options(Vec::new(), false, "empty".to_string());
+        assert_eq!(
+            engine.try_set_shape(&empty),
+            Err(AudioError::Empty("empty".to_string()))
+        );
+
+        let dot = Path::with_options(vec![(0.3, 0.3); 4], false, "dot".to_string());
+        assert_eq!(
+            engine.try_set_shape(&dot),
+            Err(AudioError::Degenerate("dot".to_string()))
+        );
+
+        assert!(matches!(
+            engine.try_set_shape(&GlitchyCircle),
+            Err(AudioError::NonFinite { count, .. }) if count > 0
+        ));
+
+        // Refused shapes leave the current one playing
+        assert_eq!(engine.resample_count(), resamples);
+        assert_eq!(engine.current_shape_name(), "Circle");
+
+        assert_eq!(engine.try_set_shape(&Circle::new(0.25)), Ok(()));
+        assert_eq!(engine.resample_count(), resamples + 1);
+    }
+
+    #[test]
+    fn test_zero_radius_circle_is_degenerate() {
+        let mut engine = AudioEngine::new(SampleBuffer::new(16));
+        engine.set_shape(&Circle::new(0.0));
+        assert!(engine.shape_warning().is_some());
+
+        engine.set_shape(&Circle::new(0.5));
+        assert!(engine.shape_warning().is_none());
+    }
+
+    #[test]
+    fn test_dead_zone_skips_center_samples() {
+        // A line through the origin, 0.01 between samples
+        let line: Vec<XYSample> = (0..=200)
+            .map(|i| XYSample::new(-1.0 + i as f32 * 0.01, 0.0))
+            .collect();
+        let skipped = skip_dead_zone(&line, 0.1);
+
+        assert!(skipped.iter().all(|s| s.x.abs() >= 0.1 - 1e-6));
+        assert!(skipped.len() >= line.len() - 20 && skipped.len() < line.len());
+        // The beam steps straight across the zone
+        let gap = skipped
+            .windows(2)
+            .map(|w| w[1].x - w[0].x)
+            .fold(0.0f32, f32::max);
+        assert!(gap > 0.19);
+
+        // A figure entirely inside the zone is left alone
+        let dot = vec![XYSample::new(0.01, 0.0), XYSample::new(0.0, 0.01)];
+        assert_eq!(skip_dead_zone(&dot, 0.1).len(), 2);
+
+        // Applied when sampling
+        let config = AudioConfig {
+            center_dead_zone: 0.1,
+            ..Default::default()
+        };
+        let sampled = sample_shape(&Line::new(-0.5, 0.0, 0.5, 0.0), &config, 48000.0);
+        assert!(sampled.samples().iter().all(|s| s.x.abs() >= 0.1 - 1e-6));
+    }
+
+    #[test]
+    fn test_pen_up_blanks_long_jumps() {
+        let mut samples = vec![
+            XYSample::new(-0.75, 0.0),
+            XYSample::new(-0.7, 0.0),
+            XYSample::new(0.8, 0.0), // 1.5 away from the previous point
+            XYSample::new(0.75, 0.0),
+            XYSample::new(-0.7, 0.0), // jump back
+            XYSample::new(-0.72, 0.0),
+        ];
+        mark_pen_up(&mut samples, 1.0);
+
+        let lit: Vec<bool> = samples.iter().map(|s| !s.is_blanked()).collect();
+        assert_eq!(lit, vec![true, true, false, true, false, true]);
+    }
+}
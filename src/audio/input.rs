@@ -0,0 +1,195 @@
+//! Audio input capture for the music visualizer
+//!
+//! Listens to an input device and measures the loudness of each buffer.
+//! The capture callback only stores the RMS level in an atomic; the UI
+//! thread reads it each frame and turns it into effect modulation.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+
+/// RMS level of a block of samples, in any sample format
+pub fn rms<T: Sample>(samples: &[T]) -> f32
+where
+    f32: FromSample<T>,
+{
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = samples.iter().map(|&s| s.to_sample::<f32>().powi(2)).sum();
+    (sum / samples.len() as f32).sqrt()
+}
+
+/// Modulation amount (0 to 1) for an RMS level at a given sensitivity
+pub fn modulation(rms: f32, sensitivity: f32) -> f32 {
+    (rms * sensitivity.max(0.0)).clamp(0.0, 1.0)
+}
+
+/// Audio input capture
+pub struct AudioInput {
+    /// Available input device names (refreshed on scan)
+    pub devices: Vec<String>,
+
+    /// Currently selected device index (for UI combo box)
+    pub selected_device: usize,
+
+    /// Active capture stream (None if disconnected)
+    stream: Option<cpal::Stream>,
+
+    /// RMS of the latest captured buffer, as f32 bits
+    level: Arc<AtomicU32>,
+
+    /// Status message
+    pub status: String,
+
+    /// Whether currently capturing
+    pub is_connected: bool,
+}
+
+impl AudioInput {
+    pub fn new() -> Self {
+        let mut input = Self {
+            devices: Vec::new(),
+            selected_device: 0,
+            stream: None,
+            level: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            status: "Disconnected".to_string(),
+            is_connected: false,
+        };
+        input.scan_devices();
+        input
+    }
+
+    /// Scan for available input devices
+    pub fn scan_devices(&mut self) {
+        self.devices = cpal::default_host()
+            .input_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default();
+        if self.devices.is_empty() {
+            self.status = "No input devices found".to_string();
+        }
+    }
+
+    /// Start capturing from the currently selected device
+    pub fn connect(&mut self) {
+        if self.is_connected {
+            return;
+        }
+
+        let Some(name) = self.devices.get(self.selected_device).cloned() else {
+            self.status = "Device not found".to_string();
+            return;
+        };
+        let device = cpal::default_host()
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        let Some(device) = device else {
+            self.status = "Device not found".to_string();
+            return;
+        };
+
+        let config = match device.default_input_config() {
+            Ok(c) => c,
+            Err(e) => {
+                self.status = format!("Error getting config: {}", e);
+                return;
+            }
+        };
+
+        let level = Arc::clone(&self.level);
+        let stream_config = config.config();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_input_stream::<f32>(&device, &stream_config, level),
+            cpal::SampleFormat::I16 => build_input_stream::<i16>(&device, &stream_config, level),
+            cpal::SampleFormat::U16 => build_input_stream::<u16>(&device, &stream_config, level),
+            format => {
+                self.status = format!("Unsupported sample format: {:?}", format);
+                return;
+            }
+        };
+
+        match stream.map_err(|e| e.to_string()).and_then(|s| {
+            s.play().map_err(|e| e.to_string())?;
+            Ok(s)
+        }) {
+            Ok(s) => {
+                self.stream = Some(s);
+                self.is_connected = true;
+                self.status = format!("Listening: {}", name);
+                log::info!("Audio input connected: {}", name);
+            }
+            Err(e) => {
+                self.status = format!("Connect error: {}", e);
+                log::error!("Audio input connect error: {}", e);
+            }
+        }
+    }
+
+    /// Stop capturing
+    pub fn disconnect(&mut self) {
+        self.stream = None;
+        self.is_connected = false;
+        self.level.store(0.0f32.to_bits(), Ordering::Relaxed);
+        self.status = "Disconnected".to_string();
+        log::info!("Audio input disconnected");
+    }
+
+    /// Toggle capture state
+    pub fn toggle(&mut self) {
+        if self.is_connected {
+            self.disconnect();
+        } else {
+            self.connect();
+        }
+    }
+
+    /// RMS level of the latest captured buffer (0 when not capturing)
+    pub fn level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+}
+
+/// Build a capture stream for a concrete sample type
+fn build_input_stream<T: SizedSample>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    level: Arc<AtomicU32>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    f32: FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            level.store(rms(data).to_bits(), Ordering::Relaxed);
+        },
+        |err| log::error!("Audio input error: {}", err),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_louder_input_modulates_more() {
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..480)
+                .map(|i| amplitude * (i as f32 * 0.1).sin())
+                .collect()
+        };
+        let quiet = rms(&tone(0.1));
+        let loud = rms(&tone(0.5));
+        assert!((loud / quiet - 5.0).abs() < 1e-3);
+        assert!(modulation(loud, 2.0) > modulation(quiet, 2.0));
+
+        // Silence does nothing, and the amount saturates
+        assert_eq!(modulation(rms::<f32>(&[]), 2.0), 0.0);
+        assert_eq!(modulation(loud, 100.0), 1.0);
+    }
+}
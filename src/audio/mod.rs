@@ -3,10 +3,20 @@
 //! This module provides:
 //! - Ring buffer for thread-safe sample sharing
 //! - Audio engine for cpal integration
+//! - Audio input capture driving the visualizer
+//! - Monitor output mirroring the scope signal to a second device
 
 mod buffer;
 mod engine;
+mod input;
+mod monitor;
 
 // Re-export public types
 pub use buffer::{SampleBuffer, XYSample};
-pub use engine::{AudioEngine, EffectParams};
+#[allow(unused_imports)]
+pub use engine::{
+    AudioEngine, AudioError, EffectParams, EffectSlot, OutputFormat, SampledShape, MAX_FREQUENCY,
+    MIN_FREQUENCY,
+};
+pub use input::{modulation, AudioInput};
+pub use monitor::output_device_names;
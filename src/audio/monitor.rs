@@ -0,0 +1,179 @@
+//! Monitor output - mirrors the scope signal to a second device
+//!
+//! A scope is usually driven from one interface while you listen through
+//! another (headphones, speakers). The scope stream stays the only one
+//! advancing the trace: each frame it writes is summed to mono and pushed
+//! into a `MonitorTap`, which a second cpal stream on the monitor device
+//! drains. Neither callback locks or allocates.
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{FromSample, Sample, SampleRate, SizedSample, StreamConfig, SupportedStreamConfig};
+
+/// Frames the tap can hold (a power of two, so indices wrap cleanly)
+pub const TAP_CAPACITY: usize = 8192;
+
+/// Mix an X/Y frame down to one channel
+pub fn mono_sum(x: f32, y: f32) -> f32 {
+    (x + y) / 2.0
+}
+
+/// Single-producer, single-consumer queue of mono samples
+///
+/// The two devices run on separate clocks, so the monitor may drift behind
+/// the scope; once more than half the capacity is pending, the reader skips
+/// ahead rather than letting latency grow.
+#[derive(Debug)]
+pub struct MonitorTap {
+    /// Values as f32 bits
+    slots: Box<[AtomicU32]>,
+    /// Total values pushed
+    written: AtomicUsize,
+    /// Total values popped
+    read: AtomicUsize,
+}
+
+impl MonitorTap {
+    /// Create an empty tap holding up to `capacity` values
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity.max(2).next_power_of_two())
+                .map(|_| AtomicU32::new(0.0f32.to_bits()))
+                .collect(),
+            written: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+        }
+    }
+
+    /// Append a value (scope stream only)
+    pub fn push(&self, value: f32) {
+        let written = self.written.load(Ordering::Relaxed);
+        let slot = written & (self.slots.len() - 1);
+        self.slots[slot].store(value.to_bits(), Ordering::Relaxed);
+        self.written
+            .store(written.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Take the oldest value, if any (monitor stream only)
+    pub fn pop(&self) -> Option<f32> {
+        let written = self.written.load(Ordering::Acquire);
+        let mut read = self.read.load(Ordering::Relaxed);
+        let pending = written.wrapping_sub(read);
+        if pending == 0 {
+            return None;
+        }
+        if pending > self.slots.len() / 2 {
+            read = written.wrapping_sub(self.slots.len() / 4);
+        }
+        let slot = read & (self.slots.len() - 1);
+        let value = f32::from_bits(self.slots[slot].load(Ordering::Relaxed));
+        self.read.store(read.wrapping_add(1), Ordering::Relaxed);
+        Some(value)
+    }
+}
+
+/// Names of the output devices on the default host
+pub fn output_device_names() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Output device with the given name on the default host
+pub(super) fn find_output_device(name: &str) -> Option<cpal::Device> {
+    cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|d| d.name().is_ok_and(|n| n == name))
+}
+
+/// Device config at the scope's sample rate, falling back to the default
+///
+/// At a different rate the monitor would play the scope signal at the
+/// wrong pitch, so a matching rate is preferred whenever the device has one.
+pub(super) fn monitor_config(
+    device: &cpal::Device,
+    sample_rate: u32,
+) -> Result<SupportedStreamConfig, cpal::DefaultStreamConfigError> {
+    let default = device.default_output_config()?;
+    if default.sample_rate().0 == sample_rate {
+        return Ok(default);
+    }
+    let matching = device
+        .supported_output_configs()
+        .ok()
+        .and_then(|mut ranges| {
+            ranges.find(|range| {
+                range.sample_format() == default.sample_format()
+                    && (range.min_sample_rate().0..=range.max_sample_rate().0)
+                        .contains(&sample_rate)
+            })
+        });
+    match matching {
+        Some(range) => Ok(range.with_sample_rate(SampleRate(sample_rate))),
+        None => {
+            log::warn!(
+                "Monitor device can't run at {} Hz, using {} Hz",
+                sample_rate,
+                default.sample_rate().0
+            );
+            Ok(default)
+        }
+    }
+}
+
+/// Build the monitor stream for a concrete sample type
+pub(super) fn build_monitor_stream<T: SizedSample + FromSample<f32>>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    tap: Arc<MonitorTap>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    let channels = config.channels as usize;
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            write_monitor_samples(data, channels, &tap);
+        },
+        |err| log::error!("Monitor stream error: {}", err),
+        None,
+    )
+}
+
+/// Write the tapped mono signal to every channel, silence on underrun
+fn write_monitor_samples<T: Sample + FromSample<f32>>(
+    data: &mut [T],
+    channels: usize,
+    tap: &MonitorTap,
+) {
+    for frame in data.chunks_mut(channels) {
+        let value = tap.pop().map_or(T::EQUILIBRIUM, T::from_sample);
+        for sample in frame.iter_mut() {
+            *sample = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mono_sum_averages_channels() {
+        assert_eq!(mono_sum(0.5, -0.25), 0.125);
+        assert_eq!(mono_sum(1.0, 1.0), 1.0);
+        assert_eq!(mono_sum(0.75, -0.75), 0.0);
+
+        // The tap hands frames on in order and runs dry cleanly
+        let tap = MonitorTap::new(8);
+        tap.push(mono_sum(0.2, 0.4));
+        tap.push(mono_sum(-1.0, 0.0));
+        let mut data = [1.0f32; 6];
+        write_monitor_samples(&mut data, 2, &tap);
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+        assert!(close(data[0], 0.3) && close(data[1], 0.3));
+        assert_eq!(&data[2..], &[-0.5, -0.5, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Bounds - keeping effected coordinates inside the DAC range
+//!
+//! Effects such as translation or tiling can push points past ±1, where
+//! the output hard-clips and part of the figure is lost. The bounds stage
+//! runs after the effect chain and decides what happens to those points.
+
+/// How coordinates outside ±1 are brought back into range
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum OutOfBoundsMode {
+    /// Limit each coordinate to ±1
+    #[default]
+    Clamp,
+    /// Tile coordinates modularly, so 1.5 reappears at -0.5
+    Wrap,
+    /// Shrink the whole frame uniformly until it fits
+    Scale,
+}
+
+impl OutOfBoundsMode {
+    /// Get all modes
+    pub fn all() -> &'static [OutOfBoundsMode] {
+        &[
+            OutOfBoundsMode::Clamp,
+            OutOfBoundsMode::Wrap,
+            OutOfBoundsMode::Scale,
+        ]
+    }
+
+    /// Get the name of this mode
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutOfBoundsMode::Clamp => "Clamp",
+            OutOfBoundsMode::Wrap => "Wrap",
+            OutOfBoundsMode::Scale => "Scale",
+        }
+    }
+}
+
+/// Applies an `OutOfBoundsMode` to a stream of points
+///
+/// `Scale` needs to know the peak of a whole trace, so the gain is measured
+/// over one trace and applied to the next. Lives in the stream callback so
+/// it carries over between buffers.
+#[derive(Clone, Debug)]
+pub struct BoundsLimiter {
+    /// Gain applied in `Scale` mode
+    gain: f32,
+    /// Largest absolute coordinate seen in the current trace
+    peak: f32,
+    /// Whether a point was clamped since the last `take_clipped`
+    clipped: bool,
+}
+
+impl Default for BoundsLimiter {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            peak: 0.0,
+            clipped: false,
+        }
+    }
+}
+
+impl BoundsLimiter {
+    /// Bring a point into ±1 according to `mode`
+    pub fn apply(&mut self, mode: OutOfBoundsMode, x: f32, y: f32) -> (f32, f32) {
+        match mode {
+            OutOfBoundsMode::Clamp => self.clamp(x, y),
+            OutOfBoundsMode::Wrap => (wrap(x), wrap(y)),
+            OutOfBoundsMode::Scale => {
+                self.peak = self.peak.max(x.abs()).max(y.abs());
+                // Clamp as well, in case this trace is larger than the last
+                self.clamp(x * self.gain, y * self.gain)
+            }
+        }
+    }
+
+    /// Limit each coordinate to ±1, noting whether anything was cut off
+    fn clamp(&mut self, x: f32, y: f32) -> (f32, f32) {
+        if x.abs() > 1.0 || y.abs() > 1.0 {
+            self.clipped = true;
+        }
+        (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0))
+    }
+
+    /// Whether any point was clamped since the last call, resetting the flag
+    pub fn take_clipped(&mut self) -> bool {
+        std::mem::take(&mut self.clipped)
+    }
+
+    /// Mark the end of a trace, updating the `Scale` gain from its peak
+    pub fn end_trace(&mut self) {
+        self.gain = if self.peak > 1.0 {
+            1.0 / self.peak
+        } else {
+            1.0
+        };
+        self.peak = 0.0;
+    }
+}
+
+/// Pull a point outside the unit circle back onto it, keeping its angle
+///
+/// Unlike per-axis clamping this never flattens a figure into a square, so
+/// it's safe as a last stage whatever the effects did.
+pub fn clamp_to_unit_circle(x: f32, y: f32) -> (f32, f32) {
+    let magnitude = x.hypot(y);
+    if magnitude > 1.0 {
+        (x / magnitude, y / magnitude)
+    } else {
+        (x, y)
+    }
+}
+
+/// Wrap a coordinate into [-1, 1)
+fn wrap(v: f32) -> f32 {
+    (v + 1.0).rem_euclid(2.0) - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp() {
+        let mut bounds = BoundsLimiter::default();
+        let (x, y) = bounds.apply(OutOfBoundsMode::Clamp, 1.5, 0.5);
+        assert_eq!((x, y), (1.0, 0.5));
+    }
+
+    #[test]
+    fn test_unit_circle_clamp_keeps_angle() {
+        assert_eq!(clamp_to_unit_circle(1.5, 0.0), (1.0, 0.0));
+        assert_eq!(clamp_to_unit_circle(0.5, -0.5), (0.5, -0.5));
+
+        let (x, y) = clamp_to_unit_circle(1.0, 1.0);
+        assert!((x.hypot(y) - 1.0).abs() < 1e-6);
+        assert!((y.atan2(x) - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let mut bounds = BoundsLimiter::default();
+        let (x, y) = bounds.apply(OutOfBoundsMode::Wrap, 1.5, 0.5);
+        assert!((x + 0.5).abs() < 1e-6);
+        assert!((y - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_scale_shrinks_whole_frame() {
+        let mut bounds = BoundsLimiter::default();
+        let trace = [(1.5, 0.0), (0.75, 0.3)];
+
+        // The first trace only measures the peak
+        for &(x, y) in &trace {
+            bounds.apply(OutOfBoundsMode::Scale, x, y);
+        }
+        bounds.end_trace();
+
+        let (x1, _) = bounds.apply(OutOfBoundsMode::Scale, 1.5, 0.0);
+        let (x2, y2) = bounds.apply(OutOfBoundsMode::Scale, 0.75, 0.3);
+        assert!((x1 - 1.0).abs() < 1e-6);
+        assert!((x2 - 0.5).abs() < 1e-6);
+        assert!((y2 - 0.2).abs() < 1e-6);
+    }
+}
@@ -90,6 +90,11 @@ pub struct Lfo {
     pub max: f32,
     /// Phase offset (0.0 to 1.0)
     pub phase_offset: f32,
+    /// Cycles of phase spread along the path (0.0 = every point in step)
+    ///
+    /// Non-zero values make the modulation vary along the shape itself,
+    /// so it travels round the outline instead of moving it as a whole.
+    pub spatial: f32,
     /// Whether the LFO is enabled
     pub enabled: bool,
 }
@@ -103,6 +108,7 @@ impl Lfo {
             min: -1.0,
             max: 1.0,
             phase_offset: 0.0,
+            spatial: 0.0,
             enabled: true,
         }
     }
@@ -115,6 +121,7 @@ impl Lfo {
             min,
             max,
             phase_offset: 0.0,
+            spatial: 0.0,
             enabled: true,
         }
     }
@@ -139,12 +146,19 @@ impl Lfo {
     /// # Returns
     /// Value between `min` and `max`
     pub fn sample(&self, time: f32) -> f32 {
+        self.sample_at(time, 0.0)
+    }
+
+    /// Sample the LFO at a given time for a point at `t` (0.0 to 1.0) along the path
+    ///
+    /// The phase is shifted by `t * spatial` cycles.
+    pub fn sample_at(&self, time: f32, t: f32) -> f32 {
         if !self.enabled {
             return (self.min + self.max) / 2.0; // Return center value when disabled
         }
 
         // Calculate phase (0.0 to 1.0)
-        let phase = ((time * self.frequency) + self.phase_offset).fract();
+        let phase = ((time * self.frequency) + self.phase_offset + t * self.spatial).fract();
         let phase = if phase < 0.0 { phase + 1.0 } else { phase };
 
         // Get waveform value (-1.0 to 1.0)
@@ -201,7 +215,11 @@ impl LfoRotate {
 
 impl Effect for LfoRotate {
     fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32) {
-        let angle = self.base_angle + self.lfo.sample(time);
+        self.apply_at(x, y, time, 0.0)
+    }
+
+    fn apply_at(&self, x: f32, y: f32, time: f32, t: f32) -> (f32, f32) {
+        let angle = self.base_angle + self.lfo.sample_at(time, t);
         let rotate = Rotate::new(angle);
         rotate.apply(x, y, time)
     }
@@ -245,11 +263,21 @@ impl LfoScale {
         self.lfo.waveform = waveform;
         self
     }
+
+    /// Spread the pulse along the path by this many cycles
+    pub fn spatial(mut self, cycles: f32) -> Self {
+        self.lfo.spatial = cycles;
+        self
+    }
 }
 
 impl Effect for LfoScale {
     fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32) {
-        let scale = self.lfo.sample(time);
+        self.apply_at(x, y, time, 0.0)
+    }
+
+    fn apply_at(&self, x: f32, y: f32, time: f32, t: f32) -> (f32, f32) {
+        let scale = self.lfo.sample_at(time, t);
         (x * scale, y * scale)
     }
 
@@ -298,8 +326,12 @@ impl LfoTranslate {
 
 impl Effect for LfoTranslate {
     fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32) {
-        let dx = self.lfo_x.sample(time);
-        let dy = self.lfo_y.sample(time);
+        self.apply_at(x, y, time, 0.0)
+    }
+
+    fn apply_at(&self, x: f32, y: f32, time: f32, t: f32) -> (f32, f32) {
+        let dx = self.lfo_x.sample_at(time, t);
+        let dy = self.lfo_y.sample_at(time, t);
         (x + dx, y + dy)
     }
 
@@ -355,4 +387,24 @@ mod tests {
         let v = lfo.sample(0.75); // Second half
         assert!((v - (-1.0)).abs() < 0.01);
     }
+
+    #[test]
+    fn test_spatial_lfo_varies_along_path() {
+        let mut chain = crate::effects::EffectChain::new();
+        chain.add(LfoScale::new(1.0, 0.5, 1.5).spatial(1.0));
+
+        // Same time, a quarter of the path apart: different scales
+        let a = chain.apply_at(1.0, 0.0, 0.0, 0.0);
+        let b = chain.apply_at(1.0, 0.0, 0.0, 0.25);
+        assert!((a.0 - 1.0).abs() < 1e-5);
+        assert!((b.0 - 1.5).abs() < 1e-5);
+
+        // Without spatial spread the whole path moves together
+        let mut flat = crate::effects::EffectChain::new();
+        flat.add(LfoScale::new(1.0, 0.5, 1.5));
+        assert_eq!(
+            flat.apply_at(1.0, 0.0, 0.0, 0.0),
+            flat.apply_at(1.0, 0.0, 0.0, 0.25)
+        );
+    }
 }
@@ -2,17 +2,29 @@
 //!
 //! This module provides:
 //! - `Effect` trait for defining transformations
-//! - Transform effects: Rotate, Scale, Translate, Mirror
+//! - Transform effects: Rotate, Scale, Translate, Mirror, Tile, Ripple
 //! - LFO (Low Frequency Oscillator) for parameter modulation
 //! - LFO-modulated effects: LfoRotate, LfoScale, LfoTranslate
+//! - Tempo-synced step sequencer driving size or rotation
+//! - Spring smoothing for parameter changes
+//! - Lock-free state for stateful effects, and the Jitter effect built on it
+//! - Out-of-bounds handling after the effect chain
 
+mod bounds;
 mod lfo;
+mod sequencer;
+mod spring;
+mod state;
 mod traits;
 mod transform;
 
+pub use bounds::{clamp_to_unit_circle, BoundsLimiter, OutOfBoundsMode};
 #[allow(unused_imports)]
 pub use lfo::{Lfo, LfoRotate, LfoScale, LfoTranslate, LfoWaveform};
+pub use sequencer::{SequencerEffect, SequencerTarget, StepSequencer};
+pub use spring::SpringSmoother;
+pub use state::{AtomicRng, Jitter};
 #[allow(unused_imports)]
 pub use traits::{BoxedEffect, Effect, EffectChain};
 #[allow(unused_imports)]
-pub use transform::{Mirror, MirrorAxis, Rotate, Scale, Translate};
+pub use transform::{Mirror, MirrorAxis, Ripple, Rotate, Scale, Tile, Translate};
@@ -0,0 +1,151 @@
+//! Step sequencer - stepped, tempo-synced parameter modulation
+//!
+//! Where an LFO sweeps smoothly, a step sequencer holds each of a list of
+//! values for a fixed note length before jumping to the next, which locks
+//! the visuals to a rhythm.
+
+use std::f32::consts::TAU;
+
+use super::traits::Effect;
+
+/// Parameter a step sequencer drives
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum SequencerTarget {
+    /// Uniform scale factor
+    #[default]
+    Size,
+    /// Rotation angle
+    Rotation,
+}
+
+impl SequencerTarget {
+    /// Get all targets
+    pub fn all() -> &'static [SequencerTarget] {
+        &[SequencerTarget::Size, SequencerTarget::Rotation]
+    }
+
+    /// Get the name of this target
+    pub fn name(&self) -> &'static str {
+        match self {
+            SequencerTarget::Size => "Size",
+            SequencerTarget::Rotation => "Rotation",
+        }
+    }
+}
+
+/// Steps through a list of values at a tempo-synced rate
+///
+/// Step values are 0 to 1 and are mapped onto `min..max`, like an LFO's
+/// output range.
+#[derive(Clone, Debug)]
+pub struct StepSequencer {
+    /// Step values (0.0 to 1.0)
+    pub steps: Vec<f32>,
+    /// Tempo in beats per minute
+    pub bpm: f32,
+    /// Steps per beat (4 = sixteenth notes)
+    pub steps_per_beat: f32,
+    /// Output at a step value of 0
+    pub min: f32,
+    /// Output at a step value of 1
+    pub max: f32,
+}
+
+impl StepSequencer {
+    /// Create a sequencer over `steps`, mapped onto `min..max`
+    pub fn new(steps: Vec<f32>, bpm: f32, steps_per_beat: f32, min: f32, max: f32) -> Self {
+        Self {
+            steps,
+            bpm,
+            steps_per_beat,
+            min,
+            max,
+        }
+    }
+
+    /// Index of the step active at `time` seconds
+    pub fn step_index(&self, time: f32) -> usize {
+        if self.steps.is_empty() {
+            return 0;
+        }
+        let steps_per_second = self.bpm / 60.0 * self.steps_per_beat;
+        let step = (time * steps_per_second).max(0.0).floor() as usize;
+        step % self.steps.len()
+    }
+
+    /// Output value at `time` seconds
+    pub fn sample(&self, time: f32) -> f32 {
+        let value = self
+            .steps
+            .get(self.step_index(time))
+            .copied()
+            .unwrap_or(0.0);
+        self.min + value.clamp(0.0, 1.0) * (self.max - self.min)
+    }
+}
+
+/// Applies a step sequencer's output to its target parameter
+pub struct SequencerEffect {
+    /// The sequence driving the parameter
+    pub sequencer: StepSequencer,
+    /// Which parameter it drives
+    pub target: SequencerTarget,
+}
+
+impl SequencerEffect {
+    /// Create a sequenced effect
+    pub fn new(sequencer: StepSequencer, target: SequencerTarget) -> Self {
+        Self { sequencer, target }
+    }
+}
+
+impl Effect for SequencerEffect {
+    fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32) {
+        let value = self.sequencer.sample(time);
+        match self.target {
+            SequencerTarget::Size => (x * value, y * value),
+            SequencerTarget::Rotation => {
+                // Rotation steps are in turns, so 1.0 is a full revolution
+                let (sin, cos) = (value * TAU).sin_cos();
+                (x * cos - y * sin, x * sin + y * cos)
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Step Sequencer"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_index_and_value() {
+        // 120 BPM at 4 steps per beat = 8 steps per second
+        let seq = StepSequencer::new(vec![0.0, 0.5, 1.0, 0.25], 120.0, 4.0, 1.0, 2.0);
+
+        assert_eq!(seq.step_index(0.0), 0);
+        assert_eq!(seq.step_index(0.13), 1);
+        assert_eq!(seq.step_index(0.26), 2);
+        assert!((seq.sample(0.26) - 2.0).abs() < 1e-6);
+
+        // Half a second in is step 4, which wraps back to the first value
+        assert_eq!(seq.step_index(0.51), 0);
+        assert!((seq.sample(0.51) - 1.0).abs() < 1e-6);
+
+        // Step 3 at 0.4s holds 0.25 mapped onto 1..2
+        assert_eq!(seq.step_index(0.4), 3);
+        assert!((seq.sample(0.4) - 1.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_size_target_scales_point() {
+        let seq = StepSequencer::new(vec![0.5], 120.0, 4.0, 0.0, 1.0);
+        let effect = SequencerEffect::new(seq, SequencerTarget::Size);
+        let (x, y) = effect.apply(0.8, -0.4, 1.0);
+        assert!((x - 0.4).abs() < 1e-6);
+        assert!((y + 0.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,118 @@
+//! Spring smoother - damped spring motion for parameter changes
+//!
+//! Instead of snapping to a new value, a spring-smoothed parameter
+//! accelerates towards its target, overshoots a little and settles.
+//! This gives parameter changes a lively, physical feel.
+//!
+//! ## Model
+//!
+//! ```text
+//! acceleration = stiffness * (target - value) - damping * velocity
+//! ```
+//!
+//! With `damping < 2 * sqrt(stiffness)` the spring is underdamped and
+//! overshoots; at or above that it settles without overshoot.
+
+/// Largest integration step, keeps stiff springs stable at low frame rates
+const MAX_STEP: f32 = 1.0 / 240.0;
+
+/// A damped spring that follows a target value over time
+#[derive(Clone, Debug)]
+pub struct SpringSmoother {
+    /// Spring constant (higher = faster response)
+    pub stiffness: f32,
+    /// Damping coefficient (higher = less overshoot)
+    pub damping: f32,
+    /// Current value
+    value: f32,
+    /// Current velocity
+    velocity: f32,
+    /// Value the spring is pulled towards
+    target: f32,
+}
+
+impl SpringSmoother {
+    /// Create a spring at rest at `value`
+    pub fn new(value: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            value,
+            velocity: 0.0,
+            target: value,
+        }
+    }
+
+    /// Set the value the spring moves towards
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Get the current target
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Get the current (smoothed) value
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Jump straight to a value and stop moving
+    pub fn snap(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+        self.velocity = 0.0;
+    }
+
+    /// Advance the spring by `dt` seconds and return the new value
+    pub fn update(&mut self, dt: f32) -> f32 {
+        let mut remaining = dt.max(0.0);
+        while remaining > 0.0 {
+            let step = remaining.min(MAX_STEP);
+            let accel = self.stiffness * (self.target - self.value) - self.damping * self.velocity;
+            // Semi-implicit Euler: update velocity first for stability
+            self.velocity += accel * step;
+            self.value += self.velocity * step;
+            remaining -= step;
+        }
+        self.value
+    }
+}
+
+impl Default for SpringSmoother {
+    fn default() -> Self {
+        Self::new(0.0, 120.0, 8.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_overshoots_then_settles() {
+        let mut spring = SpringSmoother::new(0.0, 120.0, 8.0);
+        spring.set_target(1.0);
+
+        let mut peak = 0.0f32;
+        for _ in 0..300 {
+            peak = peak.max(spring.update(1.0 / 60.0));
+        }
+
+        // Underdamped: goes past the target...
+        assert!(peak > 1.0);
+        // ...but settles on it
+        assert!((spring.value() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_heavy_damping_does_not_overshoot() {
+        let mut spring = SpringSmoother::new(0.0, 100.0, 40.0);
+        spring.set_target(1.0);
+
+        for _ in 0..600 {
+            assert!(spring.update(1.0 / 60.0) <= 1.0 + 1e-4);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Lock-free state for stateful effects
+//!
+//! `Effect::apply` takes `&self` and runs on the audio thread, so effects
+//! that remember something between samples (feedback, jitter, sample and
+//! hold) need interior mutability that never blocks or allocates. These
+//! building blocks keep their state in atomics, sized once up front.
+//!
+//! The allocation check for `apply` lives in `tests/effect_state.rs`, since
+//! it installs a counting global allocator for its whole test binary.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use super::traits::Effect;
+
+/// Seeded xorshift random number generator with atomic state
+///
+/// Deterministic for a given seed, so stateful effects can be tested and
+/// reproduced.
+#[derive(Debug)]
+pub struct AtomicRng {
+    state: AtomicU32,
+}
+
+impl AtomicRng {
+    /// Create a generator from a seed (0 is replaced, as xorshift needs a non-zero state)
+    pub fn new(seed: u32) -> Self {
+        Self {
+            state: AtomicU32::new(if seed == 0 { 0x9E37_79B9 } else { seed }),
+        }
+    }
+
+    /// Next value in [-1, 1]
+    pub fn next_bipolar(&self) -> f32 {
+        let mut x = self.state.load(Ordering::Relaxed);
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state.store(x, Ordering::Relaxed);
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// Random per-sample displacement, for a fuzzy, unstable beam
+pub struct Jitter {
+    /// Largest displacement on each axis
+    pub amount: f32,
+    /// Noise source, shared so the sequence carries on across rebuilt chains
+    rng: Arc<AtomicRng>,
+}
+
+impl Jitter {
+    /// Create a jitter effect drawing from a shared generator
+    pub fn new(amount: f32, rng: Arc<AtomicRng>) -> Self {
+        Self { amount, rng }
+    }
+}
+
+impl Effect for Jitter {
+    fn apply(&self, x: f32, y: f32, _time: f32) -> (f32, f32) {
+        let dx = self.rng.next_bipolar() * self.amount;
+        let dy = self.rng.next_bipolar() * self.amount;
+        (x + dx, y + dy)
+    }
+
+    fn name(&self) -> &str {
+        "Jitter"
+    }
+}
@@ -7,6 +7,20 @@
 ///
 /// Effects are applied after shape sampling, modifying the output coordinates.
 /// The `time` parameter allows time-based effects like rotation animation.
+///
+/// ## Stateful Effects
+///
+/// `apply` runs on the audio thread for every sample, so it must never
+/// block or allocate. Effects that carry state between samples keep it in
+/// atomics, like `effects::state::AtomicRng`, with any buffers preallocated
+/// to a fixed size when the effect is built. No `Mutex`, `RefCell` or
+/// growable collections.
+///
+/// The engine builds the chain on the UI thread whenever the parameters
+/// change and hands it to the audio thread behind an `Arc`, so the
+/// callback never builds or boxes effects itself. Retired chains are
+/// dropped on the UI thread too, once the callback has let go of them. State that must outlive
+/// one chain belongs in a handle built once and shared via `Arc`.
 pub trait Effect: Send + Sync {
     /// Apply the effect to an XY point
     ///
@@ -19,6 +33,15 @@ pub trait Effect: Send + Sync {
     /// Transformed (x, y) coordinates
     fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32);
 
+    /// Apply the effect to a point at position `t` (0.0 to 1.0) along the path
+    ///
+    /// Effects that vary along the shape (a wave travelling round the
+    /// outline, say) override this; the rest ignore `t`.
+    fn apply_at(&self, x: f32, y: f32, time: f32, t: f32) -> (f32, f32) {
+        let _ = t;
+        self.apply(x, y, time)
+    }
+
     /// Get the name of this effect (for UI)
     fn name(&self) -> &str;
 
@@ -64,6 +87,11 @@ impl EffectChain {
         self.effects.len()
     }
 
+    /// Iterate over the effects in order
+    pub fn iter(&self) -> impl Iterator<Item = &BoxedEffect> {
+        self.effects.iter()
+    }
+
     /// Check if the chain is empty
     pub fn is_empty(&self) -> bool {
         self.effects.is_empty()
@@ -71,10 +99,15 @@ impl EffectChain {
 
     /// Apply all effects in sequence
     pub fn apply(&self, x: f32, y: f32, time: f32) -> (f32, f32) {
+        self.apply_at(x, y, time, 0.0)
+    }
+
+    /// Apply all effects in sequence to a point at position `t` along the path
+    pub fn apply_at(&self, x: f32, y: f32, time: f32, t: f32) -> (f32, f32) {
         let mut result = (x, y);
         for effect in &self.effects {
             if effect.is_enabled() {
-                result = effect.apply(result.0, result.1, time);
+                result = effect.apply_at(result.0, result.1, time, t);
             }
         }
         result
@@ -1,4 +1,4 @@
-//! Transform effects - Rotate, Scale, Translate
+//! Transform effects - Rotate, Scale, Translate, Mirror, Tile, Ripple
 //!
 //! These effects apply geometric transformations to shape coordinates.
 #![allow(dead_code)]
@@ -203,6 +203,105 @@ impl Effect for Mirror {
     }
 }
 
+/// Tile effect
+///
+/// Repeats the shape across the display by scaling it up by the tile count
+/// and folding coordinates back into [-1, 1]. With `mirror` set, every other
+/// tile is reflected (a kaleidoscope-style fold) so edges line up seamlessly;
+/// otherwise coordinates wrap around like a repeating texture.
+pub struct Tile {
+    /// Number of tiles across
+    pub tiles_x: u32,
+    /// Number of tiles down
+    pub tiles_y: u32,
+    /// Reflect alternate tiles instead of wrapping
+    pub mirror: bool,
+    /// Whether the effect is enabled
+    pub enabled: bool,
+}
+
+impl Tile {
+    /// Create a tiling effect
+    pub fn new(tiles_x: u32, tiles_y: u32, mirror: bool) -> Self {
+        Self {
+            tiles_x,
+            tiles_y,
+            mirror,
+            enabled: true,
+        }
+    }
+
+    /// Fold a single coordinate scaled by `tiles` back into [-1, 1]
+    fn fold(v: f32, tiles: u32, mirror: bool) -> f32 {
+        let scaled = v * tiles.max(1) as f32 + 1.0;
+        if mirror {
+            // Triangle wave: period 4, reflect on the way back down
+            let m = scaled.rem_euclid(4.0);
+            (if m > 2.0 { 4.0 - m } else { m }) - 1.0
+        } else {
+            scaled.rem_euclid(2.0) - 1.0
+        }
+    }
+}
+
+impl Effect for Tile {
+    fn apply(&self, x: f32, y: f32, _time: f32) -> (f32, f32) {
+        (
+            Self::fold(x, self.tiles_x, self.mirror),
+            Self::fold(y, self.tiles_y, self.mirror),
+        )
+    }
+
+    fn name(&self) -> &str {
+        "Tile"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Ripple effect
+///
+/// Modulates each point's distance from the origin by a sinusoid of its
+/// angle - FM synthesis applied spatially. A circle becomes a rippled
+/// flower with `mod_freq` petals; polygons get scalloped edges.
+pub struct Ripple {
+    /// Ripples per revolution
+    pub mod_freq: f32,
+    /// Radius change at the ripple peaks, as a fraction of the radius
+    pub mod_depth: f32,
+    /// Whether the effect is enabled
+    pub enabled: bool,
+}
+
+impl Ripple {
+    /// Create a ripple effect
+    pub fn new(mod_freq: f32, mod_depth: f32) -> Self {
+        Self {
+            mod_freq,
+            mod_depth,
+            enabled: true,
+        }
+    }
+}
+
+impl Effect for Ripple {
+    fn apply(&self, x: f32, y: f32, _time: f32) -> (f32, f32) {
+        let angle = y.atan2(x);
+        let gain = 1.0 + self.mod_depth * (self.mod_freq * angle).sin();
+        (x * gain, y * gain)
+    }
+
+    fn name(&self) -> &str {
+        "Ripple"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +323,23 @@ mod tests {
         assert!(y.abs() < 0.001);
     }
 
+    #[test]
+    fn test_ripple_zero_depth_is_identity() {
+        let ripple = Ripple::new(7.0, 0.0);
+        let (x, y) = ripple.apply(0.3, -0.6, 0.0);
+        assert_eq!((x, y), (0.3, -0.6));
+    }
+
+    #[test]
+    fn test_ripple_peak_radius() {
+        // At 30 degrees a 3x ripple is at its peak: sin(3 * pi/6) = 1
+        let ripple = Ripple::new(3.0, 0.2);
+        let angle = PI / 6.0;
+        let (x, y) = ripple.apply(angle.cos(), angle.sin(), 0.0);
+        assert!(((x * x + y * y).sqrt() - 1.2).abs() < 0.001);
+        assert!((y.atan2(x) - angle).abs() < 0.001);
+    }
+
     #[test]
     fn test_scale() {
         let scale = Scale::uniform(2.0);
@@ -247,4 +363,24 @@ mod tests {
         assert!((x - (-0.5)).abs() < 0.001);
         assert!((y - 0.3).abs() < 0.001);
     }
+
+    #[test]
+    fn test_tile_mirrored() {
+        let tile = Tile::new(2, 2, true);
+
+        // Inside the first tile: just scaled up
+        let (x, y) = tile.apply(0.1, 0.1, 0.0);
+        assert!((x - 0.2).abs() < 0.001);
+        assert!((y - 0.2).abs() < 0.001);
+
+        // Past the tile edge: reflected back into the neighbouring sub-cell
+        let (x, y) = tile.apply(0.6, -0.6, 0.0);
+        assert!((x - 0.8).abs() < 0.001);
+        assert!((y - (-0.8)).abs() < 0.001);
+
+        // Wrapping instead of mirroring jumps to the opposite side
+        let wrap = Tile::new(2, 2, false);
+        let (x, _) = wrap.apply(0.6, 0.0, 0.0);
+        assert!((x - (-0.8)).abs() < 0.001);
+    }
 }
@@ -0,0 +1,109 @@
+//! Point export - dump the sampled trace for analysis
+//!
+//! Writes the current `{x, y, z}` samples as CSV or JSON so they can be
+//! plotted, analysed or turned into toolpaths outside the app.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::audio::XYSample;
+
+/// Errors that can occur while exporting points
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to write file: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Failed to encode JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// File format for exported points
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointFormat {
+    /// `x,y,z` header followed by one row per sample
+    Csv,
+    /// Array of `{"x", "y", "z"}` objects
+    Json,
+}
+
+impl PointFormat {
+    /// Pick the format from a file extension, defaulting to CSV
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => PointFormat::Json,
+            _ => PointFormat::Csv,
+        }
+    }
+}
+
+/// One exported sample
+#[derive(serde::Serialize)]
+struct PointRow {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+/// Write `samples` to `path` in the given format
+pub fn export_points(
+    samples: &[XYSample],
+    path: &Path,
+    format: PointFormat,
+) -> Result<(), ExportError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        PointFormat::Csv => {
+            writeln!(out, "x,y,z")?;
+            for s in samples {
+                writeln!(out, "{},{},{}", s.x, s.y, s.z)?;
+            }
+        }
+        PointFormat::Json => {
+            let rows: Vec<PointRow> = samples
+                .iter()
+                .map(|s| PointRow {
+                    x: s.x,
+                    y: s.y,
+                    z: s.z,
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_export_round_trips() {
+        let samples = vec![
+            XYSample::new(0.5, -0.25),
+            XYSample::with_z(-1.0, 1.0, 0.0),
+            XYSample::new(0.125, 0.75),
+        ];
+        let path = std::env::temp_dir().join(format!("osci-rs-points-{}.csv", std::process::id()));
+
+        assert_eq!(PointFormat::from_path(&path), PointFormat::Csv);
+        export_points(&samples, &path, PointFormat::Csv).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("x,y,z"));
+        let rows: Vec<Vec<f32>> = lines
+            .map(|line| line.split(',').map(|v| v.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(rows.len(), samples.len());
+        for (row, s) in rows.iter().zip(&samples) {
+            assert_eq!(row, &vec![s.x, s.y, s.z]);
+        }
+    }
+}
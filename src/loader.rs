@@ -0,0 +1,280 @@
+//! Background file loading
+//!
+//! Parsing a large SVG, tracing a high-resolution image or reading a dense
+//! mesh can take long enough to freeze the UI. A `BackgroundLoad` runs the
+//! work on its own thread and hands the result back over a channel, which
+//! the UI polls once per frame.
+//!
+//! Work that is re-requested on every edit, like resampling while a slider
+//! drags, goes to a `LatestWorker` instead: one long-lived thread that only
+//! ever runs the newest request.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// A load running on a background thread
+pub struct BackgroundLoad<T> {
+    /// What is being loaded, for status display
+    label: String,
+    /// Receives the result when the thread finishes
+    receiver: Receiver<T>,
+}
+
+impl<T: Send + 'static> BackgroundLoad<T> {
+    /// Run `load` on a new thread
+    pub fn spawn(label: impl Into<String>, load: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver may be gone if the app closed mid-load
+            let _ = sender.send(load());
+        });
+        Self {
+            label: label.into(),
+            receiver,
+        }
+    }
+}
+
+impl<T> BackgroundLoad<T> {
+    /// What is being loaded
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Take the result if the load has finished
+    ///
+    /// Returns `Some(None)` if the thread died without producing a result,
+    /// so the caller can drop the load.
+    pub fn poll(&self) -> Option<Option<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(Some(result)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+/// A job for a `LatestWorker`
+type Job<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// The request waiting for a `LatestWorker`'s thread
+struct WorkerSlot<T> {
+    /// Newest job not yet started, with its submission number
+    job: Option<(u64, Job<T>)>,
+    /// Set when the owner is dropped, so the thread exits
+    closed: bool,
+}
+
+/// A single long-lived worker thread that runs only the newest job
+///
+/// Submitting while a job is still queued replaces it, so a burst of
+/// requests runs the one in progress and then the latest, never the ones
+/// between. Results are tagged with their submission, and any but the
+/// newest are dropped when polled.
+pub struct LatestWorker<T> {
+    slot: Arc<(Mutex<WorkerSlot<T>>, Condvar)>,
+    /// Results by submission number; `None` if the job panicked
+    receiver: Receiver<(u64, Option<T>)>,
+    /// Number of the newest submission
+    submitted: u64,
+    /// Label of the newest submission while its result is outstanding
+    pending: Option<String>,
+}
+
+impl<T: Send + 'static> LatestWorker<T> {
+    /// Start the worker thread
+    pub fn new(name: &str) -> Self {
+        let slot = Arc::new((
+            Mutex::new(WorkerSlot {
+                job: None,
+                closed: false,
+            }),
+            Condvar::new(),
+        ));
+        let (sender, receiver) = mpsc::channel();
+        let worker_slot = Arc::clone(&slot);
+        let spawned = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || run_worker(&worker_slot, &sender));
+        if let Err(e) = spawned {
+            log::error!("Failed to start {} thread: {}", name, e);
+        }
+        Self {
+            slot,
+            receiver,
+            submitted: 0,
+            pending: None,
+        }
+    }
+
+    /// Queue `job`, replacing any job that hasn't started yet
+    pub fn submit(&mut self, label: impl Into<String>, job: impl FnOnce() -> T + Send + 'static) {
+        self.submitted += 1;
+        self.pending = Some(label.into());
+        let (lock, ready) = &*self.slot;
+        if let Ok(mut slot) = lock.lock() {
+            slot.job = Some((self.submitted, Box::new(job)));
+            ready.notify_one();
+        }
+    }
+
+    /// Forget the outstanding request; its result will be dropped
+    pub fn cancel(&mut self) {
+        if self.pending.take().is_none() {
+            return;
+        }
+        self.submitted += 1;
+        if let Ok(mut slot) = self.slot.0.lock() {
+            slot.job = None;
+        }
+    }
+}
+
+impl<T> LatestWorker<T> {
+    /// Label of the request still being worked on, if any
+    pub fn pending(&self) -> Option<&str> {
+        self.pending.as_deref()
+    }
+
+    /// Take the result of the newest request if it has finished
+    ///
+    /// Returns `Some(None)` if the job panicked, so the caller can report it.
+    pub fn poll(&mut self) -> Option<Option<T>> {
+        self.pending.as_ref()?;
+        let mut newest = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok((id, result)) if id == self.submitted => newest = Some(result),
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    newest.get_or_insert(None);
+                    break;
+                }
+            }
+        }
+        if newest.is_some() {
+            self.pending = None;
+        }
+        newest
+    }
+}
+
+impl<T> Drop for LatestWorker<T> {
+    fn drop(&mut self) {
+        let (lock, ready) = &*self.slot;
+        if let Ok(mut slot) = lock.lock() {
+            slot.closed = true;
+            slot.job = None;
+            ready.notify_one();
+        }
+    }
+}
+
+/// Body of a `LatestWorker` thread: run queued jobs until closed
+fn run_worker<T>(slot: &(Mutex<WorkerSlot<T>>, Condvar), sender: &Sender<(u64, Option<T>)>) {
+    let (lock, ready) = slot;
+    loop {
+        let (id, job) = {
+            let Ok(mut slot) = lock.lock() else {
+                return;
+            };
+            loop {
+                if slot.closed {
+                    return;
+                }
+                if let Some(job) = slot.job.take() {
+                    break job;
+                }
+                slot = match ready.wait(slot) {
+                    Ok(slot) => slot,
+                    Err(_) => return,
+                };
+            }
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(job)).ok();
+        if sender.send((id, result)).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{SvgOptions, SvgShape};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_background_load_delivers_shape() {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("square.svg");
+        let load = BackgroundLoad::spawn("square.svg", move || {
+            SvgShape::load(&path, &SvgOptions::default())
+        });
+        assert_eq!(load.label(), "square.svg");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let result = loop {
+            if let Some(result) = load.poll() {
+                break result;
+            }
+            assert!(Instant::now() < deadline, "background load timed out");
+            thread::sleep(Duration::from_millis(5));
+        };
+
+        let svg = result.expect("load thread panicked").unwrap();
+        assert!(svg.point_count() > 0);
+    }
+
+    #[test]
+    fn test_latest_worker_skips_superseded_jobs() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut worker = LatestWorker::new("test-worker");
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        // Hold the thread in the first job while more requests pile up
+        let (release, gate) = mpsc::channel::<()>();
+        let first_runs = Arc::clone(&runs);
+        worker.submit("first", move || {
+            gate.recv().ok();
+            first_runs.fetch_add(1, Ordering::SeqCst);
+            1
+        });
+        thread::sleep(Duration::from_millis(20));
+        for value in 2..=4 {
+            let runs = Arc::clone(&runs);
+            worker.submit(format!("job {}", value), move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+                value
+            });
+        }
+        assert_eq!(worker.pending(), Some("job 4"));
+        release.send(()).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let result = loop {
+            if let Some(result) = worker.poll() {
+                break result;
+            }
+            assert!(Instant::now() < deadline, "worker timed out");
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(result, Some(4));
+        assert_eq!(worker.pending(), None);
+
+        // The first job and the last ran; the two between were replaced
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        // A cancelled request's result never shows up
+        worker.submit("cancelled", || 5);
+        worker.cancel();
+        thread::sleep(Duration::from_millis(20));
+        assert!(worker.poll().is_none());
+    }
+}
@@ -17,22 +17,189 @@ use eframe::egui;
 
 mod audio;
 mod effects;
+mod export;
+mod loader;
 mod midi;
 mod render;
 mod settings;
 mod shapes;
+mod widgets;
 
-use audio::{AudioEngine, EffectParams, SampleBuffer};
-use effects::LfoWaveform;
-use render::Oscilloscope;
+use audio::{
+    modulation, output_device_names, AudioEngine, AudioInput, EffectParams, EffectSlot,
+    OutputFormat, SampleBuffer, XYSample, MAX_FREQUENCY, MIN_FREQUENCY,
+};
+use effects::{LfoWaveform, OutOfBoundsMode, SequencerTarget, SpringSmoother};
+use loader::BackgroundLoad;
+use render::{LineJoin, Oscilloscope, TriggerEdge, TriggerSource, WaveformChannel, WaveformView};
 use shapes::{
-    Camera, Circle, ImageOptions, ImageShape, Line, Mesh, Mesh3DOptions, Mesh3DShape, Path,
-    Polygon, Rectangle, Scene, SvgOptions, SvgShape, TextOptions, TextShape,
+    Camera, Circle, CsvOptions, Easing, FontCache, HarmonographParams, ImageMode, ImageOptions,
+    ImageShape, Line, Mesh, Mesh3DOptions, Mesh3DShape, MorphShape, NormalizeMode, Path, Polygon,
+    Rectangle, Scene, ScenePlayback, SceneShape, Shape, SvgOptions, SvgShape, TextOptions,
+    TextShape, TextStyle,
 };
+use widgets::nudge_slider;
 
 /// Buffer size for audio samples
 const BUFFER_SIZE: usize = 2048;
 
+/// Selectable audio stream buffer sizes (None = device default)
+const BUFFER_FRAME_OPTIONS: &[Option<u32>] = &[
+    None,
+    Some(64),
+    Some(128),
+    Some(256),
+    Some(512),
+    Some(1024),
+    Some(2048),
+];
+
+/// Upper end of the point budget slider
+const MAX_POINT_BUDGET: usize = 96_000;
+
+/// Output channels offered for X/Y routing
+const MAX_OUTPUT_CHANNELS: usize = 8;
+
+/// Points in the idle animation shown while stopped
+const IDLE_POINTS: usize = 512;
+
+/// Width and height of exported PNG images in pixels
+const EXPORT_SIZE: usize = 1024;
+
+/// Supersampling factors offered for PNG export
+const EXPORT_QUALITY_OPTIONS: &[usize] = &[1, 2, 4];
+
+/// Ratios per side of the Lissajous preview table
+const LISSAJOUS_GRID_SIZE: u32 = 5;
+
+/// Points per figure in the Lissajous preview table
+const LISSAJOUS_GRID_POINTS: usize = 200;
+
+/// Width and height of shape library thumbnails in pixels
+const THUMBNAIL_SIZE: usize = 64;
+
+/// Points each shape library thumbnail is sampled with
+const THUMBNAIL_SAMPLES: usize = 1024;
+
+/// Thumbnails per row in the shape library
+const LIBRARY_COLUMNS: usize = 4;
+
+/// Intensity boost at the start of a beat flash
+const BEAT_FLASH_BOOST: f32 = 0.6;
+
+/// Time constant of the beat flash decay (seconds)
+const BEAT_FLASH_DECAY: f32 = 0.08;
+
+/// Time constant of the visualizer's fall after a peak (seconds)
+const VISUALIZER_RELEASE: f32 = 0.15;
+
+/// How long the clip indicator stays lit after the output clamps (seconds)
+const CLIP_HOLD: f32 = 1.0;
+
+/// Points each morph endpoint is resampled to
+const MORPH_POINTS: usize = 500;
+
+/// Shapes that can be used as morph endpoints
+const MORPH_ENDPOINTS: &[ShapeType] = &[
+    ShapeType::Circle,
+    ShapeType::Rectangle,
+    ShapeType::Triangle,
+    ShapeType::Square,
+    ShapeType::Pentagon,
+    ShapeType::Hexagon,
+    ShapeType::Star,
+    ShapeType::Heart,
+    ShapeType::Lissajous,
+    ShapeType::Spiral,
+];
+
+/// A representative shape for each type's library thumbnail
+///
+/// Uses the default parameters. File-backed types show the placeholder
+/// they display before a file is loaded.
+fn library_shape(shape_type: ShapeType) -> Box<dyn Shape> {
+    let params = ShapeParams::default();
+    let size = params.size;
+    match shape_type {
+        ShapeType::Rectangle => Box::new(Rectangle::new(params.width, params.height)),
+        ShapeType::Triangle => Box::new(Polygon::triangle(size)),
+        ShapeType::Square => Box::new(Rectangle::square(size)),
+        ShapeType::Pentagon => Box::new(Polygon::pentagon(size)),
+        ShapeType::Hexagon => Box::new(Polygon::hexagon(size)),
+        ShapeType::Star => Box::new(Polygon::star(params.points, size, params.inner_radius)),
+        ShapeType::Line => {
+            let half = size / 2.0;
+            Box::new(Line::new(-half, -half, half, half))
+        }
+        ShapeType::Heart => Box::new(Path::heart(size, 200)),
+        ShapeType::Lissajous => Box::new(Path::lissajous(
+            params.lissajous_a,
+            params.lissajous_b,
+            params.lissajous_delta,
+            params.lissajous_points,
+            params.lissajous_close,
+        )),
+        ShapeType::Harmonograph => Box::new(Path::harmonograph(
+            &params.harmonograph,
+            params.harmonograph_points,
+        )),
+        ShapeType::Spiral => Box::new(Path::spiral(0.1, size, params.spiral_turns, 300)),
+        ShapeType::Morph => {
+            let mut morph =
+                MorphShape::new(&Circle::new(size), &Rectangle::square(size), MORPH_POINTS);
+            morph.set_blend(0.5);
+            Box::new(morph)
+        }
+        ShapeType::Text => match TextShape::new("Aa", &TextOptions::default()) {
+            Ok(text) => Box::new(text),
+            Err(_) => Box::new(Circle::new(0.5)),
+        },
+        ShapeType::Mesh3D => Box::new(Mesh3DShape::new(Mesh::cube(), Mesh3DOptions::default())),
+        ShapeType::Circle | ShapeType::Svg | ShapeType::Csv | ShapeType::Image => {
+            Box::new(Circle::new(size))
+        }
+    }
+}
+
+/// Render the library thumbnail for a shape type
+fn shape_thumbnail(scope: &mut Oscilloscope, shape_type: ShapeType) -> egui::ColorImage {
+    let shape = library_shape(shape_type);
+    let samples: Vec<XYSample> = (0..THUMBNAIL_SAMPLES)
+        .map(|i| {
+            let (x, y) = shape.sample(i as f32 / THUMBNAIL_SAMPLES as f32);
+            XYSample::new(x, y)
+        })
+        .collect();
+    scope.render_to_image(&samples, [THUMBNAIL_SIZE, THUMBNAIL_SIZE], 2)
+}
+
+/// Solo button for one effect: while on, only that effect is applied
+fn effect_solo_toggle(ui: &mut egui::Ui, solo: &mut Option<EffectSlot>, slot: EffectSlot) {
+    let mut soloed = *solo == Some(slot);
+    if ui
+        .toggle_value(&mut soloed, "S")
+        .on_hover_text("Solo: apply only this effect")
+        .changed()
+    {
+        *solo = soloed.then_some(slot);
+    }
+}
+
+/// Extensions accepted by the image loader
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+/// Pick the file-backed shape type for a dropped file by its extension
+fn dropped_file_shape(path: &std::path::Path) -> Option<ShapeType> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "svg" => Some(ShapeType::Svg),
+        "csv" | "txt" | "wav" => Some(ShapeType::Csv),
+        "obj" => Some(ShapeType::Mesh3D),
+        e if IMAGE_EXTENSIONS.contains(&e) => Some(ShapeType::Image),
+        _ => None,
+    }
+}
+
 fn main() -> eframe::Result<()> {
     env_logger::init();
     log::info!("Starting osci-rs");
@@ -51,6 +218,13 @@ fn main() -> eframe::Result<()> {
     )
 }
 
+/// A file loaded on a background thread, with the path it came from
+enum LoadedFile {
+    Svg(std::path::PathBuf, Result<SvgShape, String>),
+    Image(std::path::PathBuf, Result<ImageShape, String>),
+    Obj(std::path::PathBuf, Result<Mesh, String>),
+}
+
 /// Available shape types
 #[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
 enum ShapeType {
@@ -64,8 +238,11 @@ enum ShapeType {
     Line,
     Heart,
     Lissajous,
+    Harmonograph,
     Spiral,
+    Morph,  // Animated blend between two shapes
     Svg,    // Loaded SVG file
+    Csv,    // Loaded coordinate list
     Image,  // Traced image file
     Text,   // Rendered text
     Mesh3D, // 3D wireframe mesh
@@ -84,8 +261,11 @@ impl ShapeType {
             ShapeType::Line,
             ShapeType::Heart,
             ShapeType::Lissajous,
+            ShapeType::Harmonograph,
             ShapeType::Spiral,
+            ShapeType::Morph,
             ShapeType::Svg,
+            ShapeType::Csv,
             ShapeType::Image,
             ShapeType::Text,
             ShapeType::Mesh3D,
@@ -104,8 +284,11 @@ impl ShapeType {
             ShapeType::Line => "Line",
             ShapeType::Heart => "Heart",
             ShapeType::Lissajous => "Lissajous",
+            ShapeType::Harmonograph => "Harmonograph",
             ShapeType::Spiral => "Spiral",
+            ShapeType::Morph => "Morph",
             ShapeType::Svg => "SVG File",
+            ShapeType::Csv => "Coordinate File",
             ShapeType::Image => "Image File",
             ShapeType::Text => "Text",
             ShapeType::Mesh3D => "3D Mesh",
@@ -124,7 +307,12 @@ enum EditorMode {
 struct SceneEntry {
     shape_type: ShapeType,
     weight: f32,
+    /// Size within the composition, independent of master volume
+    amplitude: f32,
     enabled: bool,
+    soloed: bool,
+    /// Mesh, options and camera for a Mesh3D entry (cube when unset)
+    mesh: Option<SceneMesh>,
 }
 
 impl SceneEntry {
@@ -132,11 +320,36 @@ impl SceneEntry {
         Self {
             shape_type,
             weight: 1.0,
+            amplitude: 1.0,
             enabled: true,
+            soloed: false,
+            mesh: None,
+        }
+    }
+
+    /// Build this entry's mesh shape, falling back to the default cube
+    fn mesh_shape(&self) -> Mesh3DShape {
+        match &self.mesh {
+            Some(mesh) => mesh.shape(),
+            None => Mesh3DShape::new(Mesh::cube(), Mesh3DOptions::default()),
         }
     }
 }
 
+/// Snapshot of the mesh configuration taken when a Mesh3D scene entry is added
+#[derive(Clone)]
+struct SceneMesh {
+    mesh: Mesh,
+    options: Mesh3DOptions,
+    camera: Camera,
+}
+
+impl SceneMesh {
+    fn shape(&self) -> Mesh3DShape {
+        Mesh3DShape::new(self.mesh.clone(), self.options.clone()).with_camera(self.camera.clone())
+    }
+}
+
 /// Shape parameters (varies by shape type)
 struct ShapeParams {
     // Common
@@ -154,9 +367,21 @@ struct ShapeParams {
     lissajous_a: f32,
     lissajous_b: f32,
     lissajous_delta: f32,
+    lissajous_points: usize,
+    lissajous_close: bool,
+
+    // Harmonograph specific
+    harmonograph: HarmonographParams,
+    harmonograph_points: usize,
 
     // Spiral specific
     spiral_turns: f32,
+
+    // Morph specific
+    morph_from: ShapeType,
+    morph_to: ShapeType,
+    morph_period: f32,
+    morph_easing: Easing,
 }
 
 /// Built-in 3D mesh primitives
@@ -212,7 +437,15 @@ impl Default for ShapeParams {
             lissajous_a: 3.0,
             lissajous_b: 2.0,
             lissajous_delta: std::f32::consts::FRAC_PI_2,
+            lissajous_points: 500,
+            lissajous_close: true,
+            harmonograph: HarmonographParams::default(),
+            harmonograph_points: 3000,
             spiral_turns: 3.0,
+            morph_from: ShapeType::Circle,
+            morph_to: ShapeType::Star,
+            morph_period: 4.0,
+            morph_easing: Easing::default(),
         }
     }
 }
@@ -223,6 +456,17 @@ struct OsciApp {
     audio: AudioEngine,
     oscilloscope: Oscilloscope,
     show_settings: bool,
+    show_lissajous_grid: bool,
+    /// Whether the shape library window is open
+    show_shape_library: bool,
+    /// Cached shape library thumbnails, rendered on first open
+    shape_thumbnails: Vec<(ShapeType, egui::TextureHandle)>,
+    idle_animation: bool,
+    /// Count where the trace crosses itself in the status bar
+    show_crossings: bool,
+    /// Show one channel against time instead of the XY display
+    show_waveform: bool,
+    waveform: WaveformView,
 
     // Editor mode
     editor_mode: EditorMode,
@@ -231,16 +475,26 @@ struct OsciApp {
     selected_shape: ShapeType,
     shape_params: ShapeParams,
     shape_needs_update: bool,
+    // Only volume/width/rotation/auto-fit changed: reuse sampled geometry
+    output_needs_update: bool,
 
     // Scene composition
     scene_entries: Vec<SceneEntry>,
     scene_shape_to_add: ShapeType,
+    scene_by_length: bool,
+    scene_playback: ScenePlayback,
 
     // SVG import
     loaded_svg: Option<SvgShape>,
     svg_options: SvgOptions,
     svg_error: Option<String>,
 
+    // CSV coordinate import
+    loaded_csv: Option<Path>,
+    csv_path: Option<std::path::PathBuf>,
+    csv_options: CsvOptions,
+    csv_error: Option<String>,
+
     // Image import
     loaded_image: Option<ImageShape>,
     image_options: ImageOptions,
@@ -249,17 +503,21 @@ struct OsciApp {
     // Text rendering
     text_input: String,
     text_shape: Option<TextShape>,
+    /// Parsed default font, kept so re-layouts don't re-parse it
+    text_font: FontCache,
     text_options: TextOptions,
     text_error: Option<String>,
 
     // 3D mesh rendering
     loaded_mesh: Option<Mesh>,
-    mesh_shape: Option<Mesh3DShape>,
     mesh_options: Mesh3DOptions,
     mesh_camera: Camera,
     mesh_primitive: MeshPrimitive,
     mesh_error: Option<String>,
 
+    // Morph animation
+    morph_shape: Option<MorphShape>,
+
     // Effects
     enable_rotation: bool,
     rotation_speed: f32,
@@ -268,12 +526,73 @@ struct OsciApp {
     scale_lfo_min: f32,
     scale_lfo_max: f32,
     scale_lfo_waveform: LfoWaveform,
+    scale_lfo_spatial: f32,
+    enable_tile: bool,
+    tile_x: u32,
+    tile_y: u32,
+    tile_mirror: bool,
+    enable_ripple: bool,
+    ripple_freq: f32,
+    ripple_depth: f32,
+    enable_jitter: bool,
+    jitter_amount: f32,
+    enable_sequencer: bool,
+    sequencer_steps: Vec<f32>,
+    sequencer_bpm: f32,
+    sequencer_rate: f32,
+    sequencer_min: f32,
+    sequencer_max: f32,
+    sequencer_target: SequencerTarget,
+    solo_effect: Option<EffectSlot>,
+    out_of_bounds: OutOfBoundsMode,
+    radial_limit: bool,
+
+    // Spring smoothing of size/rotation changes
+    enable_spring: bool,
+    size_spring: SpringSmoother,
+    rotation_spring: SpringSmoother,
+
+    // Settings sharing
+    share_input: String,
+    share_error: Option<String>,
+
+    // Image export
+    export_supersample: usize,
+    export_error: Option<String>,
+
+    // Files being loaded in the background
+    pending_loads: Vec<BackgroundLoad<LoadedFile>>,
+
+    /// Output devices offered for the monitor, listed at startup
+    output_devices: Vec<String>,
 
     // MIDI controller
     midi: midi::MidiController,
+    midi_out: midi::MidiFeedback,
+    /// Ramps MIDI changes to frequency, master speed and volume
+    midi_smoothing: midi::MidiSmoothing,
+    beat_flash: bool,
+    beat_pulse: f32,
+    /// Dims a figure left unchanged for a long time
+    idle_dimmer: render::IdleDimmer,
+
+    // Music visualizer: live input drives size and rotation
+    audio_input: AudioInput,
+    /// Gain from input RMS to modulation amount
+    visualizer_sensitivity: f32,
+    /// Size added at full modulation (fraction of the shape size)
+    visualizer_size: f32,
+    /// Rotation at full modulation (radians)
+    visualizer_spin: f32,
+    /// Current modulation amount, following the input envelope
+    visualizer_level: f32,
+    /// Seconds the clip indicator has left to show
+    clip_hold: f32,
 
     // Time tracking for effects
     start_time: std::time::Instant,
+    /// Seconds of shape animation (morph, SVG frames), run at the master speed
+    animation_time: f32,
 }
 
 impl OsciApp {
@@ -290,18 +609,34 @@ impl OsciApp {
             audio,
             oscilloscope: Oscilloscope::new(),
             show_settings: true,
+            show_lissajous_grid: false,
+            show_shape_library: false,
+            shape_thumbnails: Vec::new(),
+            idle_animation: false,
+            show_crossings: false,
+            show_waveform: false,
+            waveform: WaveformView::default(),
             editor_mode: EditorMode::SingleShape,
             selected_shape: ShapeType::Circle,
             shape_params: ShapeParams::default(),
             shape_needs_update: false,
+            output_needs_update: false,
             scene_entries: Vec::new(),
             scene_shape_to_add: ShapeType::Circle,
+            scene_by_length: false,
+            scene_playback: ScenePlayback::Forward,
 
             // SVG import
             loaded_svg: None,
             svg_options: SvgOptions::default(),
             svg_error: None,
 
+            // CSV import
+            loaded_csv: None,
+            csv_path: None,
+            csv_options: CsvOptions::default(),
+            csv_error: None,
+
             // Image import
             loaded_image: None,
             image_options: ImageOptions::default(),
@@ -310,16 +645,17 @@ impl OsciApp {
             // Text rendering
             text_input: "Hello".to_string(),
             text_shape: None,
+            text_font: FontCache::embedded(),
             text_options: TextOptions::default(),
             text_error: None,
 
             // 3D mesh rendering
             loaded_mesh: None,
-            mesh_shape: None,
             mesh_options: Mesh3DOptions::default(),
             mesh_camera: Camera::default(),
             mesh_primitive: MeshPrimitive::Cube,
             mesh_error: None,
+            morph_shape: None,
 
             // Effects
             enable_rotation: false,
@@ -329,11 +665,54 @@ impl OsciApp {
             scale_lfo_min: 0.8,
             scale_lfo_max: 1.2,
             scale_lfo_waveform: LfoWaveform::Sine,
+            scale_lfo_spatial: 0.0,
+            enable_tile: false,
+            tile_x: 2,
+            tile_y: 2,
+            tile_mirror: true,
+            enable_ripple: false,
+            ripple_freq: 8.0,
+            ripple_depth: 0.1,
+            enable_jitter: false,
+            jitter_amount: 0.01,
+            enable_sequencer: false,
+            sequencer_steps: vec![1.0, 0.5, 0.75, 0.5],
+            sequencer_bpm: 120.0,
+            sequencer_rate: 1.0,
+            sequencer_min: 0.5,
+            sequencer_max: 1.0,
+            sequencer_target: SequencerTarget::Size,
+            solo_effect: None,
+            out_of_bounds: OutOfBoundsMode::Clamp,
+            radial_limit: false,
+
+            // Spring smoothing
+            enable_spring: false,
+            size_spring: SpringSmoother::new(0.8, 120.0, 8.0),
+            rotation_spring: SpringSmoother::new(1.0, 120.0, 8.0),
 
             // MIDI
+            share_input: String::new(),
+            share_error: None,
+            export_supersample: 2,
+            export_error: None,
+            pending_loads: Vec::new(),
+            output_devices: output_device_names(),
             midi: midi::MidiController::new(),
+            midi_out: midi::MidiFeedback::new(),
+            midi_smoothing: midi::MidiSmoothing::default(),
+            beat_flash: false,
+            beat_pulse: 0.0,
+            idle_dimmer: render::IdleDimmer::default(),
+            audio_input: AudioInput::new(),
+            visualizer_sensitivity: 4.0,
+            visualizer_size: 0.5,
+            visualizer_spin: 0.0,
+            visualizer_level: 0.0,
+            clip_hold: 0.0,
 
             start_time: std::time::Instant::now(),
+            animation_time: 0.0,
         };
 
         // Load and apply persisted settings
@@ -343,8 +722,33 @@ impl OsciApp {
         app
     }
 
+    /// Mute the output, switch off every effect and the visualizer, and reset
+    /// the camera (Escape)
+    ///
+    /// For live use: one action that makes the output safe, whatever state
+    /// the performance left it in.
+    fn panic(&mut self) {
+        self.enable_rotation = false;
+        self.enable_scale_lfo = false;
+        self.enable_sequencer = false;
+        self.enable_tile = false;
+        self.enable_ripple = false;
+        self.enable_jitter = false;
+        self.enable_spring = false;
+        self.solo_effect = None;
+        self.midi_smoothing = midi::MidiSmoothing::default();
+        self.mesh_camera = Camera::default();
+        // The visualizer modulates the figure too
+        self.audio_input.disconnect();
+        self.visualizer_level = 0.0;
+        self.audio.panic();
+        self.shape_needs_update = true;
+    }
+
     /// Create and set the current shape based on selection and parameters
     fn update_shape(&mut self) {
+        // Only imported figures have subpaths worth numbering
+        self.oscilloscope.set_draw_order(&[]);
         match self.selected_shape {
             ShapeType::Circle => {
                 let shape = Circle::new(self.shape_params.size);
@@ -392,7 +796,15 @@ impl OsciApp {
                     self.shape_params.lissajous_a,
                     self.shape_params.lissajous_b,
                     self.shape_params.lissajous_delta,
-                    500,
+                    self.shape_params.lissajous_points,
+                    self.shape_params.lissajous_close,
+                );
+                self.audio.set_shape(&shape);
+            }
+            ShapeType::Harmonograph => {
+                let shape = Path::harmonograph(
+                    &self.shape_params.harmonograph,
+                    self.shape_params.harmonograph_points,
                 );
                 self.audio.set_shape(&shape);
             }
@@ -405,20 +817,51 @@ impl OsciApp {
                 );
                 self.audio.set_shape(&shape);
             }
+            ShapeType::Morph => {
+                let from = self.morph_endpoint(self.shape_params.morph_from);
+                let to = self.morph_endpoint(self.shape_params.morph_to);
+                let mut shape = MorphShape::new(from.as_ref(), to.as_ref(), MORPH_POINTS);
+                shape.set_easing(self.shape_params.morph_easing);
+                shape.set_time(self.animation_time, self.shape_params.morph_period);
+                self.audio.set_shape(&shape);
+                self.morph_shape = Some(shape);
+            }
             ShapeType::Svg => {
-                // Use loaded SVG if available
-                if let Some(ref svg) = self.loaded_svg {
-                    self.audio.set_shape(svg);
+                // Use loaded SVG if available, rebuilt with the current options
+                if let Some(ref mut svg) = self.loaded_svg {
+                    let mut error = svg
+                        .set_options(&self.svg_options)
+                        .err()
+                        .map(|e| e.to_string());
+                    if let Err(e) = self.audio.try_set_shape(svg) {
+                        error = Some(e.to_string());
+                    }
+                    self.svg_error = error;
+                    let subpaths: Vec<_> = svg.paths().iter().map(|p| p.points()).collect();
+                    self.oscilloscope.set_draw_order(&subpaths);
                 } else {
                     // No SVG loaded, show a placeholder circle
                     let shape = Circle::new(0.5);
                     self.audio.set_shape(&shape);
                 }
             }
+            ShapeType::Csv => {
+                // Use loaded coordinate list if available
+                if let Some(ref csv) = self.loaded_csv {
+                    self.csv_error = self.audio.try_set_shape(csv).err().map(|e| e.to_string());
+                } else {
+                    // No CSV loaded, show a placeholder circle
+                    let shape = Circle::new(0.5);
+                    self.audio.set_shape(&shape);
+                }
+            }
             ShapeType::Image => {
-                // Use loaded image if available
+                // Use loaded image if available; large traces sample off-thread
                 if let Some(ref img) = self.loaded_image {
-                    self.audio.set_shape(img);
+                    // A trace is one continuous path
+                    self.oscilloscope.set_draw_order(&[img.points()]);
+                    let img = img.clone();
+                    self.audio.resample_in_background("image", move || img);
                 } else {
                     // No image loaded, show a placeholder circle
                     let shape = Circle::new(0.5);
@@ -428,11 +871,15 @@ impl OsciApp {
             ShapeType::Text => {
                 // Render text if we have input
                 if !self.text_input.is_empty() {
-                    match TextShape::new(&self.text_input, &self.text_options) {
+                    match TextShape::with_font(
+                        &self.text_input,
+                        &mut self.text_font,
+                        &self.text_options,
+                    ) {
                         Ok(text) => {
-                            self.audio.set_shape(&text);
+                            self.text_error =
+                                self.audio.try_set_shape(&text).err().map(|e| e.to_string());
                             self.text_shape = Some(text);
-                            self.text_error = None;
                         }
                         Err(e) => {
                             self.text_error = Some(e.to_string());
@@ -447,19 +894,10 @@ impl OsciApp {
                 }
             }
             ShapeType::Mesh3D => {
-                // Get the mesh (from primitive or loaded file)
-                let mesh = if self.mesh_primitive == MeshPrimitive::Custom {
-                    self.loaded_mesh.clone()
-                } else {
-                    self.mesh_primitive.to_mesh()
-                };
-
-                if let Some(mesh) = mesh {
-                    let shape = Mesh3DShape::new(mesh, self.mesh_options.clone())
-                        .with_camera(self.mesh_camera.clone());
-                    self.audio.set_shape(&shape);
-                    self.mesh_shape = Some(shape);
-                    self.mesh_error = None;
+                if let Some(mesh) = self.current_scene_mesh() {
+                    // Subdivision and sampling of dense meshes happen off-thread
+                    self.audio
+                        .resample_in_background("mesh", move || mesh.shape());
                 } else {
                     // No mesh available, show placeholder
                     let shape = Circle::new(0.5);
@@ -470,13 +908,483 @@ impl OsciApp {
         self.shape_needs_update = false;
     }
 
+    /// Build one endpoint of a morph from the current shape parameters
+    fn morph_endpoint(&self, shape_type: ShapeType) -> Box<dyn Shape> {
+        let size = self.shape_params.size;
+        match shape_type {
+            ShapeType::Rectangle => Box::new(Rectangle::new(
+                self.shape_params.width,
+                self.shape_params.height,
+            )),
+            ShapeType::Triangle => Box::new(Polygon::triangle(size)),
+            ShapeType::Square => Box::new(Rectangle::square(size)),
+            ShapeType::Pentagon => Box::new(Polygon::pentagon(size)),
+            ShapeType::Hexagon => Box::new(Polygon::hexagon(size)),
+            ShapeType::Star => Box::new(Polygon::star(
+                self.shape_params.points,
+                size,
+                self.shape_params.inner_radius,
+            )),
+            ShapeType::Heart => Box::new(Path::heart(size, 200)),
+            ShapeType::Lissajous => Box::new(Path::lissajous(
+                self.shape_params.lissajous_a,
+                self.shape_params.lissajous_b,
+                self.shape_params.lissajous_delta,
+                self.shape_params.lissajous_points,
+                self.shape_params.lissajous_close,
+            )),
+            ShapeType::Spiral => {
+                Box::new(Path::spiral(0.1, size, self.shape_params.spiral_turns, 300))
+            }
+            _ => Box::new(Circle::new(size)),
+        }
+    }
+
+    /// Advance the morph blend and push the updated shape to the audio engine
+    fn animate_morph(&mut self) {
+        if self.editor_mode != EditorMode::SingleShape || self.selected_shape != ShapeType::Morph {
+            return;
+        }
+        if let Some(ref mut shape) = self.morph_shape {
+            let previous = shape.blend();
+            shape.set_time(self.animation_time, self.shape_params.morph_period);
+            // Resting at an endpoint leaves nothing to resample
+            if shape.blend() != previous {
+                self.audio.set_shape(shape);
+            }
+        }
+    }
+
+    /// Step a frame-animated SVG and push each new frame to the audio engine
+    fn animate_svg(&mut self) {
+        if self.editor_mode != EditorMode::SingleShape || self.selected_shape != ShapeType::Svg {
+            return;
+        }
+        if let Some(ref mut svg) = self.loaded_svg {
+            if svg.frame_count() == 0 {
+                return;
+            }
+            let previous = svg.frame();
+            svg.set_time(self.animation_time, self.svg_options.frame_rate);
+            if svg.frame() != previous {
+                if let Err(e) = self.audio.try_set_shape(svg) {
+                    self.svg_error = Some(e.to_string());
+                }
+            }
+        }
+    }
+
+    /// Show the grid of Lissajous ratios; clicking one loads it
+    fn show_lissajous_grid_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut picked = None;
+        let grid = Path::lissajous_grid(
+            LISSAJOUS_GRID_SIZE,
+            self.shape_params.lissajous_delta,
+            LISSAJOUS_GRID_POINTS,
+        );
+
+        egui::Window::new("Lissajous ratios")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("lissajous_grid").show(ui, |ui| {
+                    for ((a, b), path) in &grid {
+                        let response = self
+                            .oscilloscope
+                            .show_preview(ui, path.points(), egui::vec2(64.0, 64.0))
+                            .on_hover_text(format!("{}:{}", a, b));
+                        if response.clicked() {
+                            picked = Some((*a, *b));
+                        }
+                        if *a == LISSAJOUS_GRID_SIZE {
+                            ui.end_row();
+                        }
+                    }
+                });
+            });
+
+        if let Some((a, b)) = picked {
+            self.shape_params.lissajous_a = a as f32;
+            self.shape_params.lissajous_b = b as f32;
+            self.selected_shape = ShapeType::Lissajous;
+            self.editor_mode = EditorMode::SingleShape;
+            self.shape_needs_update = true;
+        }
+        self.show_lissajous_grid = open;
+    }
+
+    /// Show the shape library; clicking a thumbnail selects that shape
+    fn show_shape_library_window(&mut self, ctx: &egui::Context) {
+        // The thumbnails use default parameters, so they never go stale
+        if self.shape_thumbnails.is_empty() {
+            let mut settings = self.oscilloscope.settings.clone();
+            settings.frame_blend = 0.0;
+            let mut scope = Oscilloscope::with_settings(settings);
+            self.shape_thumbnails = ShapeType::all()
+                .iter()
+                .map(|&shape_type| {
+                    let image = shape_thumbnail(&mut scope, shape_type);
+                    let texture = ctx.load_texture(
+                        format!("thumbnail_{}", shape_type.name()),
+                        image,
+                        egui::TextureOptions::LINEAR,
+                    );
+                    (shape_type, texture)
+                })
+                .collect();
+        }
+
+        let mut open = true;
+        let mut picked = None;
+        let thumbnail_size = egui::vec2(THUMBNAIL_SIZE as f32, THUMBNAIL_SIZE as f32);
+
+        egui::Window::new("Shape library")
+            .open(&mut open)
+            .default_height(320.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("shape_library").show(ui, |ui| {
+                        for (i, (shape_type, texture)) in self.shape_thumbnails.iter().enumerate() {
+                            ui.vertical_centered(|ui| {
+                                let button = egui::ImageButton::new((texture.id(), thumbnail_size))
+                                    .selected(*shape_type == self.selected_shape);
+                                if ui.add(button).clicked() {
+                                    picked = Some(*shape_type);
+                                }
+                                ui.small(shape_type.name());
+                            });
+                            if (i + 1) % LIBRARY_COLUMNS == 0 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+                });
+            });
+
+        if let Some(shape_type) = picked {
+            self.selected_shape = shape_type;
+            self.editor_mode = EditorMode::SingleShape;
+            self.shape_needs_update = true;
+        }
+        self.show_shape_library = open;
+    }
+
+    /// Advance the spring smoothers towards the current parameter values
+    fn update_springs(&mut self, dt: f32) {
+        if self.enable_spring {
+            self.size_spring.set_target(self.shape_params.size);
+            self.rotation_spring.set_target(self.rotation_speed);
+            self.size_spring.update(dt);
+            self.rotation_spring.update(dt);
+        } else {
+            self.size_spring.snap(self.shape_params.size);
+            self.rotation_spring.snap(self.rotation_speed);
+        }
+    }
+
+    /// Pulse the beam brightness on each MIDI clock beat
+    fn update_beat_flash(&mut self, dt: f32) {
+        // Always drain the clock so a flash doesn't fire for stale ticks
+        let beat = self.midi.poll_beat();
+        if self.beat_flash && self.midi.is_connected && beat {
+            self.beat_pulse = 1.0;
+        } else {
+            self.beat_pulse *= (-dt / BEAT_FLASH_DECAY).exp();
+        }
+        self.oscilloscope
+            .set_flash(self.beat_pulse * BEAT_FLASH_BOOST);
+    }
+
+    /// Dim the display and the Z output while the figure stands still,
+    /// restoring them on any change
+    fn update_idle_dimming(&mut self, dt: f32, changed: bool) {
+        let brightness = self.idle_dimmer.update(dt, changed);
+        self.oscilloscope.set_dimming(brightness);
+    }
+
+    /// Follow the input level: jump up on peaks, fall back smoothly
+    fn update_visualizer(&mut self, dt: f32) {
+        let target = modulation(self.audio_input.level(), self.visualizer_sensitivity);
+        if target > self.visualizer_level {
+            self.visualizer_level = target;
+        } else {
+            let fall = (-dt / VISUALIZER_RELEASE).exp();
+            self.visualizer_level = target + (self.visualizer_level - target) * fall;
+        }
+    }
+
+    /// Keep the clip indicator lit for a moment after the output clamps
+    fn update_clip_indicator(&mut self, dt: f32) {
+        if self.audio.take_clipped() {
+            self.clip_hold = CLIP_HOLD;
+        } else {
+            self.clip_hold = (self.clip_hold - dt).max(0.0);
+        }
+    }
+
+    /// Collect the effect parameters to send to the audio engine
+    fn effect_params(&self) -> EffectParams {
+        // The shape is already sampled at the target size, so the spring
+        // only contributes the ratio between where it is and where it's going
+        let target = self.size_spring.target();
+        let spring_scale = if self.enable_spring && target > 0.0 {
+            self.size_spring.value() / target
+        } else {
+            1.0
+        };
+        let level = self.visualizer_level;
+
+        EffectParams {
+            rotation_speed: self.rotation_spring.value(),
+            rotation_enabled: self.enable_rotation,
+            scale_lfo_freq: self.scale_lfo_freq,
+            scale_lfo_min: self.scale_lfo_min,
+            scale_lfo_max: self.scale_lfo_max,
+            scale_lfo_enabled: self.enable_scale_lfo,
+            scale_lfo_waveform: self.scale_lfo_waveform,
+            scale_lfo_spatial: self.scale_lfo_spatial,
+            sequencer_enabled: self.enable_sequencer,
+            sequencer_steps: self.sequencer_steps.clone(),
+            sequencer_bpm: self.sequencer_bpm,
+            sequencer_rate: self.sequencer_rate,
+            sequencer_min: self.sequencer_min,
+            sequencer_max: self.sequencer_max,
+            sequencer_target: self.sequencer_target,
+            tile_enabled: self.enable_tile,
+            tile_x: self.tile_x,
+            tile_y: self.tile_y,
+            tile_mirror: self.tile_mirror,
+            ripple_enabled: self.enable_ripple,
+            ripple_freq: self.ripple_freq,
+            ripple_depth: self.ripple_depth,
+            jitter_enabled: self.enable_jitter,
+            jitter_amount: self.jitter_amount,
+            solo: self.solo_effect,
+            time_scale: self.audio.config.time_scale,
+            brightness: self.idle_dimmer.brightness(),
+            out_of_bounds: self.out_of_bounds,
+            radial_limit: self.radial_limit,
+            spring_scale: spring_scale * (1.0 + self.visualizer_size * level),
+            reactive_angle: self.visualizer_spin * level,
+        }
+    }
+
     /// Load an SVG file using file dialog
     fn load_svg_file(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("SVG Files", &["svg"])
             .pick_file()
         {
-            match SvgShape::load(&path, &self.svg_options) {
+            self.load_svg_path(&path);
+        }
+    }
+
+    /// Start loading an SVG file from a path in the background
+    fn load_svg_path(&mut self, path: &std::path::Path) {
+        let options = self.svg_options.clone();
+        self.start_load(path, move |path| {
+            let result = SvgShape::load(&path, &options).map_err(|e| e.to_string());
+            LoadedFile::Svg(path, result)
+        });
+    }
+
+    /// Load a CSV coordinate file using file dialog
+    fn load_csv_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Coordinate Files", &["csv", "txt", "wav"])
+            .pick_file()
+        {
+            self.csv_path = Some(path);
+            self.reload_csv();
+        }
+    }
+
+    /// Re-read the current coordinate file (CSV or WAV) with the current options
+    fn reload_csv(&mut self) {
+        let Some(path) = self.csv_path.clone() else {
+            return;
+        };
+
+        match Path::from_file(&path, &self.csv_options) {
+            Ok(csv) => {
+                log::info!(
+                    "Loaded coordinates: {} ({} points)",
+                    path.display(),
+                    csv.len()
+                );
+                self.loaded_csv = Some(csv);
+                self.selected_shape = ShapeType::Csv;
+                self.csv_error = None;
+                self.shape_needs_update = true;
+            }
+            Err(e) => {
+                log::error!("Failed to load coordinates: {}", e);
+                self.csv_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Load an image file using file dialog
+    fn load_image_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Image Files", IMAGE_EXTENSIONS)
+            .pick_file()
+        {
+            self.load_image_path(&path);
+        }
+    }
+
+    /// Start loading an image file from a path in the background
+    fn load_image_path(&mut self, path: &std::path::Path) {
+        let options = self.image_options.clone();
+        self.start_load(path, move |path| {
+            let result = ImageShape::load(&path, &options).map_err(|e| e.to_string());
+            LoadedFile::Image(path, result)
+        });
+    }
+
+    /// Reload image with current options
+    fn reload_image(&mut self) {
+        // If we have a loaded image, we need to reload from file
+        // For now, just trigger an update - user can reload manually
+        self.shape_needs_update = true;
+    }
+
+    /// Save the current display as a PNG chosen with a file dialog
+    fn export_png(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG Image", &["png"])
+            .set_file_name("osci.png")
+            .save_file()
+        else {
+            return;
+        };
+
+        // A fresh scope, so the export isn't blended with the live frames
+        let mut settings = self.oscilloscope.settings.clone();
+        settings.frame_blend = 0.0;
+        let mut scope = render::Oscilloscope::with_settings(settings);
+        let image = scope.render_to_image(
+            &self.buffer.get_samples(),
+            [EXPORT_SIZE, EXPORT_SIZE],
+            self.export_supersample,
+        );
+
+        let bytes: Vec<u8> = image.pixels.iter().flat_map(|p| p.to_array()).collect();
+        let result = image::RgbaImage::from_raw(EXPORT_SIZE as u32, EXPORT_SIZE as u32, bytes)
+            .ok_or_else(|| "image buffer has the wrong size".to_string())
+            .and_then(|png| png.save(&path).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                log::info!("Exported {}", path.display());
+                self.export_error = None;
+            }
+            Err(e) => {
+                log::error!("Failed to export PNG: {}", e);
+                self.export_error = Some(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    /// Save the current sampled points as CSV or JSON chosen with a file dialog
+    fn export_points(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"])
+            .set_file_name("points.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        let format = export::PointFormat::from_path(&path);
+        match export::export_points(&self.buffer.get_samples(), &path, format) {
+            Ok(()) => {
+                log::info!("Exported {}", path.display());
+                self.export_error = None;
+            }
+            Err(e) => {
+                log::error!("Failed to export points: {}", e);
+                self.export_error = Some(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    /// Load an OBJ file using file dialog
+    fn load_obj_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("OBJ Files", &["obj"])
+            .pick_file()
+        {
+            self.load_obj_path(&path);
+        }
+    }
+
+    /// Start loading an OBJ file from a path in the background
+    fn load_obj_path(&mut self, path: &std::path::Path) {
+        self.start_load(path, move |path| {
+            let result = Mesh::from_obj(&path).map_err(|e| e.to_string());
+            LoadedFile::Obj(path, result)
+        });
+    }
+
+    /// Run a file load on a background thread, to be applied by `poll_loads`
+    fn start_load(
+        &mut self,
+        path: &std::path::Path,
+        load: impl FnOnce(std::path::PathBuf) -> LoadedFile + Send + 'static,
+    ) {
+        let label = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let path = path.to_path_buf();
+        self.pending_loads
+            .push(BackgroundLoad::spawn(label, move || load(path)));
+    }
+
+    /// Apply any background loads that have finished
+    fn poll_loads(&mut self) {
+        let mut finished = Vec::new();
+        self.pending_loads.retain(|load| match load.poll() {
+            Some(Some(loaded)) => {
+                finished.push(loaded);
+                false
+            }
+            Some(None) => {
+                log::error!("Loading {} failed unexpectedly", load.label());
+                false
+            }
+            None => true,
+        });
+
+        for loaded in finished {
+            self.apply_loaded(loaded);
+        }
+    }
+
+    /// Apply a finished background resample, or record why it was refused
+    fn poll_resample(&mut self) {
+        let Some((label, result)) = self.audio.poll_resample() else {
+            return;
+        };
+        let error = result.err().map(|e| e.to_string());
+        match label.as_str() {
+            "image" => self.image_error = error,
+            "mesh" => self.mesh_error = error,
+            _ => {
+                if let Some(e) = error {
+                    log::error!("{}", e);
+                }
+            }
+        }
+    }
+
+    /// Make a finished load the active shape, or record its error
+    fn apply_loaded(&mut self, loaded: LoadedFile) {
+        match loaded {
+            LoadedFile::Svg(path, result) => match result {
                 Ok(svg) => {
                     log::info!(
                         "Loaded SVG: {} ({} paths, {} points)",
@@ -491,19 +1399,10 @@ impl OsciApp {
                 }
                 Err(e) => {
                     log::error!("Failed to load SVG: {}", e);
-                    self.svg_error = Some(e.to_string());
+                    self.svg_error = Some(e);
                 }
-            }
-        }
-    }
-
-    /// Load an image file using file dialog
-    fn load_image_file(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Image Files", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
-            .pick_file()
-        {
-            match ImageShape::load(&path, &self.image_options) {
+            },
+            LoadedFile::Image(path, result) => match result {
                 Ok(img) => {
                     let (w, h) = img.dimensions();
                     log::info!(
@@ -520,26 +1419,10 @@ impl OsciApp {
                 }
                 Err(e) => {
                     log::error!("Failed to load image: {}", e);
-                    self.image_error = Some(e.to_string());
+                    self.image_error = Some(e);
                 }
-            }
-        }
-    }
-
-    /// Reload image with current options
-    fn reload_image(&mut self) {
-        // If we have a loaded image, we need to reload from file
-        // For now, just trigger an update - user can reload manually
-        self.shape_needs_update = true;
-    }
-
-    /// Load an OBJ file using file dialog
-    fn load_obj_file(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("OBJ Files", &["obj"])
-            .pick_file()
-        {
-            match Mesh::from_obj(&path) {
+            },
+            LoadedFile::Obj(path, result) => match result {
                 Ok(mesh) => {
                     log::info!(
                         "Loaded OBJ: {} ({} vertices, {} edges)",
@@ -555,88 +1438,152 @@ impl OsciApp {
                 }
                 Err(e) => {
                     log::error!("Failed to load OBJ: {}", e);
-                    self.mesh_error = Some(e.to_string());
+                    self.mesh_error = Some(e);
+                }
+            },
+        }
+    }
+
+    /// Load files dropped onto the window, switching to the last one loaded
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<_> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|f| f.path.clone())
+                .collect()
+        });
+
+        for path in dropped {
+            let Some(shape_type) = dropped_file_shape(&path) else {
+                log::warn!("Ignoring dropped file: {}", path.display());
+                continue;
+            };
+
+            match shape_type {
+                ShapeType::Svg => self.load_svg_path(&path),
+                ShapeType::Csv => {
+                    self.csv_path = Some(path);
+                    self.reload_csv();
                 }
+                ShapeType::Image => self.load_image_path(&path),
+                ShapeType::Mesh3D => self.load_obj_path(&path),
+                _ => continue,
             }
+
+            // Show the loader's panel even on failure so its error is visible
+            self.selected_shape = shape_type;
+            self.editor_mode = EditorMode::SingleShape;
         }
     }
 
+    /// The mesh configured in single-shape mode (primitive or loaded file)
+    fn current_scene_mesh(&self) -> Option<SceneMesh> {
+        let mesh = if self.mesh_primitive == MeshPrimitive::Custom {
+            self.loaded_mesh.clone()
+        } else {
+            self.mesh_primitive.to_mesh()
+        }?;
+        Some(SceneMesh {
+            mesh,
+            options: self.mesh_options.clone(),
+            camera: self.mesh_camera.clone(),
+        })
+    }
+
     /// Build and set the scene from scene entries
     fn update_scene(&mut self) {
+        self.oscilloscope.set_draw_order(&[]);
         let mut scene = Scene::new("Custom Scene");
+        scene.set_proportional_to_length(self.scene_by_length);
+        scene.set_playback(self.scene_playback);
 
+        // Entries that won't be drawn are left out before they're built
+        let any_soloed = self.scene_entries.iter().any(|e| e.soloed);
+        let mut shapes = Vec::new();
         for entry in &self.scene_entries {
-            if entry.enabled {
-                // Create shape based on type (using default params for simplicity)
-                match entry.shape_type {
-                    ShapeType::Circle => {
-                        scene.add_weighted(Circle::new(0.7), entry.weight);
-                    }
-                    ShapeType::Rectangle => {
-                        scene.add_weighted(Rectangle::new(1.0, 0.6), entry.weight);
-                    }
-                    ShapeType::Triangle => {
-                        scene.add_weighted(Polygon::triangle(0.7), entry.weight);
-                    }
-                    ShapeType::Square => {
-                        scene.add_weighted(Rectangle::square(0.7), entry.weight);
-                    }
-                    ShapeType::Pentagon => {
-                        scene.add_weighted(Polygon::pentagon(0.7), entry.weight);
-                    }
-                    ShapeType::Hexagon => {
-                        scene.add_weighted(Polygon::hexagon(0.7), entry.weight);
-                    }
-                    ShapeType::Star => {
-                        scene.add_weighted(Polygon::star(5, 0.7, 0.3), entry.weight);
-                    }
-                    ShapeType::Line => {
-                        scene.add_weighted(Line::new(-0.5, -0.5, 0.5, 0.5), entry.weight);
-                    }
-                    ShapeType::Heart => {
-                        scene.add_weighted(Path::heart(0.7, 200), entry.weight);
-                    }
-                    ShapeType::Lissajous => {
-                        scene.add_weighted(
-                            Path::lissajous(3.0, 2.0, std::f32::consts::FRAC_PI_2, 500),
-                            entry.weight,
-                        );
-                    }
-                    ShapeType::Spiral => {
-                        scene.add_weighted(Path::spiral(0.1, 0.7, 3.0, 300), entry.weight);
-                    }
-                    ShapeType::Svg => {
-                        if let Some(ref svg) = self.loaded_svg {
-                            scene.add_weighted(svg.clone(), entry.weight);
-                        } else {
-                            scene.add_weighted(Circle::new(0.5), entry.weight);
-                        }
+            if !Scene::is_drawn(entry.enabled, entry.soloed, any_soloed) {
+                continue;
+            }
+
+            // Create shape based on type (using default params for simplicity)
+            let mut shape = match entry.shape_type {
+                ShapeType::Circle => SceneShape::with_weight(Circle::new(0.7), entry.weight),
+                ShapeType::Rectangle => {
+                    SceneShape::with_weight(Rectangle::new(1.0, 0.6), entry.weight)
+                }
+                ShapeType::Triangle => {
+                    SceneShape::with_weight(Polygon::triangle(0.7), entry.weight)
+                }
+                ShapeType::Square => SceneShape::with_weight(Rectangle::square(0.7), entry.weight),
+                ShapeType::Pentagon => {
+                    SceneShape::with_weight(Polygon::pentagon(0.7), entry.weight)
+                }
+                ShapeType::Hexagon => SceneShape::with_weight(Polygon::hexagon(0.7), entry.weight),
+                ShapeType::Star => {
+                    SceneShape::with_weight(Polygon::star(5, 0.7, 0.3), entry.weight)
+                }
+                ShapeType::Line => {
+                    SceneShape::with_weight(Line::new(-0.5, -0.5, 0.5, 0.5), entry.weight)
+                }
+                ShapeType::Heart => SceneShape::with_weight(Path::heart(0.7, 200), entry.weight),
+                ShapeType::Lissajous => SceneShape::with_weight(
+                    Path::lissajous(3.0, 2.0, std::f32::consts::FRAC_PI_2, 500, true),
+                    entry.weight,
+                ),
+                ShapeType::Harmonograph => SceneShape::with_weight(
+                    Path::harmonograph(&HarmonographParams::default(), 3000),
+                    entry.weight,
+                ),
+                ShapeType::Spiral => {
+                    SceneShape::with_weight(Path::spiral(0.1, 0.7, 3.0, 300), entry.weight)
+                }
+                ShapeType::Morph => {
+                    // Scenes are static, so freeze the morph halfway
+                    let mut morph = MorphShape::new(
+                        &Circle::new(0.7),
+                        &Polygon::star(5, 0.7, 0.3),
+                        MORPH_POINTS,
+                    );
+                    morph.set_blend(0.5);
+                    SceneShape::with_weight(morph, entry.weight)
+                }
+                ShapeType::Svg => {
+                    if let Some(ref svg) = self.loaded_svg {
+                        SceneShape::with_weight(svg.clone(), entry.weight)
+                    } else {
+                        SceneShape::with_weight(Circle::new(0.5), entry.weight)
                     }
-                    ShapeType::Image => {
-                        if let Some(ref img) = self.loaded_image {
-                            scene.add_weighted(img.clone(), entry.weight);
-                        } else {
-                            scene.add_weighted(Circle::new(0.5), entry.weight);
-                        }
+                }
+                ShapeType::Csv => {
+                    if let Some(ref csv) = self.loaded_csv {
+                        SceneShape::with_weight(csv.clone(), entry.weight)
+                    } else {
+                        SceneShape::with_weight(Circle::new(0.5), entry.weight)
                     }
-                    ShapeType::Text => {
-                        if let Some(ref text) = self.text_shape {
-                            scene.add_weighted(text.clone(), entry.weight);
-                        } else {
-                            scene.add_weighted(Circle::new(0.5), entry.weight);
-                        }
+                }
+                ShapeType::Image => {
+                    if let Some(ref img) = self.loaded_image {
+                        SceneShape::with_weight(img.clone(), entry.weight)
+                    } else {
+                        SceneShape::with_weight(Circle::new(0.5), entry.weight)
                     }
-                    ShapeType::Mesh3D => {
-                        // 3D mesh in scene - use cube as default
-                        let mesh = Mesh::cube();
-                        let shape = Mesh3DShape::new(mesh, Mesh3DOptions::default());
-                        scene.add_weighted(shape, entry.weight);
+                }
+                ShapeType::Text => {
+                    if let Some(ref text) = self.text_shape {
+                        SceneShape::with_weight(text.clone(), entry.weight)
+                    } else {
+                        SceneShape::with_weight(Circle::new(0.5), entry.weight)
                     }
                 }
-            }
+                ShapeType::Mesh3D => SceneShape::with_weight(entry.mesh_shape(), entry.weight),
+            };
+            shape.set_amplitude(entry.amplitude);
+            shapes.push(shape);
         }
+        scene.add_entries(shapes);
 
-        if !scene.is_empty() {
+        if scene.active_count() > 0 {
             self.audio.set_shape(&scene);
         }
         self.shape_needs_update = false;
@@ -651,21 +1598,47 @@ impl Drop for OsciApp {
 
 impl eframe::App for OsciApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_dropped_files(ctx);
+        self.poll_loads();
+        self.poll_resample();
+
         ctx.request_repaint();
 
-        // Poll MIDI and apply CC updates
-        let midi_updates = self.midi.poll();
+        // Poll MIDI and apply CC updates, recording or replaying automation
+        let now = self.start_time.elapsed().as_secs_f32();
+        let mut midi_updates = self.midi.poll();
+        self.midi.recorder.record(now, &midi_updates);
+        midi_updates.extend(self.midi.recorder.poll(now));
         if !midi_updates.is_empty() {
             midi::apply_updates(&midi_updates, self);
         }
+        midi::update_smoothing(self, ctx.input(|i| i.stable_dt));
+        if self.midi_out.is_connected {
+            let values = midi::feedback_values(self);
+            self.midi_out.update(&values);
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.panic();
+        }
+
+        // Any input or parameter change counts as activity for idle dimming
+        let figure_changed = self.shape_needs_update
+            || self.output_needs_update
+            || !midi_updates.is_empty()
+            || ctx.input(|i| !i.events.is_empty() || i.pointer.is_moving());
 
-        // Update shape if parameters changed
+        // Update shape if parameters changed; output-only changes reuse
+        // the sampled geometry
         if self.shape_needs_update {
             match self.editor_mode {
                 EditorMode::SingleShape => self.update_shape(),
                 EditorMode::Scene => self.update_scene(),
             }
+        } else if self.output_needs_update {
+            self.audio.refresh_output();
         }
+        self.output_needs_update = false;
 
         // Top panel
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
@@ -683,11 +1656,35 @@ impl eframe::App for OsciApp {
                 if ui.button(button_text).clicked() {
                     self.audio.toggle();
                 }
+                if ui
+                    .button("⚠ Panic")
+                    .on_hover_text(
+                        "Mute output, switch off all effects and stop the visualizer (Esc)",
+                    )
+                    .clicked()
+                {
+                    self.panic();
+                }
 
                 ui.separator();
                 ui.toggle_value(&mut self.show_settings, "⚙ Settings");
                 ui.separator();
+                if self.clip_hold > 0.0 {
+                    ui.colored_label(egui::Color32::RED, "CLIP")
+                        .on_hover_text("The output went past ±1 and was clamped");
+                }
                 ui.label(&self.audio.status);
+                if let Some(warning) = self.audio.shape_warning() {
+                    ui.colored_label(egui::Color32::YELLOW, warning);
+                }
+                for load in &self.pending_loads {
+                    ui.spinner();
+                    ui.label(format!("Loading {}...", load.label()));
+                }
+                if self.audio.pending_resample().is_some() {
+                    ui.spinner();
+                    ui.label("Resampling...");
+                }
             });
         });
 
@@ -738,6 +1735,8 @@ impl eframe::App for OsciApp {
                                         }
                                     }
                                 });
+                            ui.toggle_value(&mut self.show_shape_library, "📚 Library")
+                                .on_hover_text("Browse shapes by thumbnail");
 
                             ui.separator();
 
@@ -752,150 +1751,262 @@ impl eframe::App for OsciApp {
                                 | ShapeType::Hexagon
                                 | ShapeType::Line
                                 | ShapeType::Heart => {
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.size,
-                                                0.1..=1.0,
-                                            )
-                                            .text("Size"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.size,
+                                        0.1..=1.0,
+                                        |s| s.text("Size"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
                                 }
 
                                 ShapeType::Rectangle => {
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.width,
-                                                0.1..=1.8,
-                                            )
-                                            .text("Width"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.width,
+                                        0.1..=1.8,
+                                        |s| s.text("Width"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.height,
-                                                0.1..=1.8,
-                                            )
-                                            .text("Height"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.height,
+                                        0.1..=1.8,
+                                        |s| s.text("Height"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
                                 }
 
                                 ShapeType::Star => {
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.points,
-                                                3..=12,
-                                            )
-                                            .text("Points"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.points,
+                                        3..=12,
+                                        |s| s.text("Points"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.size,
-                                                0.1..=1.0,
-                                            )
-                                            .text("Outer radius"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.size,
+                                        0.1..=1.0,
+                                        |s| s.text("Outer radius"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.inner_radius,
-                                                0.1..=0.9,
-                                            )
-                                            .text("Inner radius"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.inner_radius,
+                                        0.1..=0.9,
+                                        |s| s.text("Inner radius"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
                                 }
 
                                 ShapeType::Lissajous => {
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.lissajous_a,
-                                                1.0..=10.0,
-                                            )
-                                            .text("A (X freq)"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.lissajous_a,
+                                        1.0..=10.0,
+                                        |s| s.text("A (X freq)"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.lissajous_b,
-                                                1.0..=10.0,
-                                            )
-                                            .text("B (Y freq)"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.lissajous_b,
+                                        1.0..=10.0,
+                                        |s| s.text("B (Y freq)"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.lissajous_delta,
+                                        0.0..=std::f32::consts::PI,
+                                        |s| s.text("Phase"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.lissajous_points,
+                                        50..=5000,
+                                        |s| s.text("Points").logarithmic(true),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.lissajous_delta,
-                                                0.0..=std::f32::consts::PI,
-                                            )
-                                            .text("Phase"),
+                                        .checkbox(
+                                            &mut self.shape_params.lissajous_close,
+                                            "Force close",
                                         )
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
+                                    ui.toggle_value(&mut self.show_lissajous_grid, "Ratio table");
                                 }
 
-                                ShapeType::Spiral => {
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.size,
-                                                0.2..=1.0,
-                                            )
-                                            .text("Radius"),
+                                ShapeType::Harmonograph => {
+                                    let params = &mut self.shape_params.harmonograph;
+                                    let mut changed = false;
+                                    let pendulums = params
+                                        .x
+                                        .iter_mut()
+                                        .map(|p| ("X", p))
+                                        .chain(params.y.iter_mut().map(|p| ("Y", p)));
+                                    for (i, (axis, pendulum)) in pendulums.enumerate() {
+                                        ui.label(format!("{} pendulum {}:", axis, i % 2 + 1));
+                                        changed |= nudge_slider(
+                                            ui,
+                                            &mut pendulum.frequency,
+                                            0.5..=10.0,
+                                            |s| s.text("Frequency"),
                                         )
-                                        .changed()
+                                        .changed();
+                                        changed |= nudge_slider(
+                                            ui,
+                                            &mut pendulum.phase,
+                                            0.0..=std::f32::consts::TAU,
+                                            |s| s.text("Phase"),
+                                        )
+                                        .changed();
+                                        changed |= nudge_slider(
+                                            ui,
+                                            &mut pendulum.amplitude,
+                                            0.0..=1.0,
+                                            |s| s.text("Amplitude"),
+                                        )
+                                        .changed();
+                                        changed |=
+                                            nudge_slider(ui, &mut pendulum.decay, 0.0..=0.1, |s| {
+                                                s.text("Decay")
+                                            })
+                                            .changed();
+                                    }
+                                    ui.separator();
+                                    changed |=
+                                        nudge_slider(ui, &mut params.cycles, 1.0..=100.0, |s| {
+                                            s.text("Cycles").logarithmic(true)
+                                        })
+                                        .changed();
+                                    changed |= nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.harmonograph_points,
+                                        500..=8000,
+                                        |s| s.text("Points").logarithmic(true),
+                                    )
+                                    .changed();
+                                    if changed {
+                                        self.shape_needs_update = true;
+                                    }
+                                }
+
+                                ShapeType::Spiral => {
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.size,
+                                        0.2..=1.0,
+                                        |s| s.text("Radius"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.shape_params.spiral_turns,
-                                                1.0..=10.0,
-                                            )
-                                            .text("Turns"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.spiral_turns,
+                                        1.0..=10.0,
+                                        |s| s.text("Turns"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+                                }
+
+                                ShapeType::Morph => {
+                                    let endpoints = [
+                                        ("morph_from", "From", &mut self.shape_params.morph_from),
+                                        ("morph_to", "To", &mut self.shape_params.morph_to),
+                                    ];
+                                    for (id, label, endpoint) in endpoints {
+                                        ui.horizontal(|ui| {
+                                            ui.label(label);
+                                            egui::ComboBox::from_id_salt(id)
+                                                .selected_text(endpoint.name())
+                                                .show_ui(ui, |ui| {
+                                                    for shape_type in MORPH_ENDPOINTS {
+                                                        if ui
+                                                            .selectable_value(
+                                                                endpoint,
+                                                                *shape_type,
+                                                                shape_type.name(),
+                                                            )
+                                                            .changed()
+                                                        {
+                                                            self.shape_needs_update = true;
+                                                        }
+                                                    }
+                                                });
+                                        });
+                                    }
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.size,
+                                        0.2..=1.0,
+                                        |s| s.text("Size"),
+                                    )
+                                    .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
+                                    nudge_slider(
+                                        ui,
+                                        &mut self.shape_params.morph_period,
+                                        0.5..=30.0,
+                                        |s| s.text("Period (s)").logarithmic(true),
+                                    );
+                                    let previous_easing = self.shape_params.morph_easing;
+                                    egui::ComboBox::from_label("Easing")
+                                        .selected_text(self.shape_params.morph_easing.name())
+                                        .show_ui(ui, |ui| {
+                                            for easing in Easing::all() {
+                                                ui.selectable_value(
+                                                    &mut self.shape_params.morph_easing,
+                                                    *easing,
+                                                    easing.name(),
+                                                );
+                                            }
+                                        });
+                                    if self.shape_params.morph_easing != previous_easing {
+                                        self.shape_needs_update = true;
+                                    }
                                 }
 
                                 ShapeType::Svg => {
@@ -908,6 +2019,9 @@ impl eframe::App for OsciApp {
                                     if let Some(ref svg) = self.loaded_svg {
                                         ui.label(format!("Paths: {}", svg.path_count()));
                                         ui.label(format!("Points: {}", svg.point_count()));
+                                        if svg.frame_count() > 0 {
+                                            ui.label(format!("Frames: {}", svg.frame_count()));
+                                        }
                                     } else {
                                         ui.label("No SVG loaded");
                                     }
@@ -922,15 +2036,30 @@ impl eframe::App for OsciApp {
 
                                     // Curve samples
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.svg_options.curve_samples,
-                                                2..=32,
-                                            )
-                                            .text("Curve detail"),
+                                        .checkbox(&mut self.svg_options.adaptive, "Adaptive curves")
+                                        .on_hover_text(
+                                            "Spend points where curves bend, not on flat stretches",
                                         )
                                         .changed()
                                     {
+                                        self.shape_needs_update = true;
+                                    }
+                                    let detail = if self.svg_options.adaptive {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.svg_options.curve_tolerance,
+                                            0.0005..=0.02,
+                                            |s| s.logarithmic(true).text("Curve tolerance"),
+                                        )
+                                    } else {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.svg_options.curve_samples,
+                                            2..=32,
+                                            |s| s.text("Curve detail"),
+                                        )
+                                    };
+                                    if detail.changed() {
                                         // Reload SVG with new options
                                         self.shape_needs_update = true;
                                     }
@@ -945,6 +2074,106 @@ impl eframe::App for OsciApp {
                                     {
                                         self.shape_needs_update = true;
                                     }
+
+                                    if ui
+                                        .checkbox(
+                                            &mut self.svg_options.recenter,
+                                            "Center on centroid",
+                                        )
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if ui
+                                        .checkbox(
+                                            &mut self.svg_options.preserve_aspect,
+                                            "Keep proportions",
+                                        )
+                                        .on_hover_text("Off stretches the shape to fill the square")
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    let previous_normalize = self.svg_options.normalize;
+                                    egui::ComboBox::new("svg_normalize", "Normalize")
+                                        .selected_text(self.svg_options.normalize.name())
+                                        .show_ui(ui, |ui| {
+                                            for mode in NormalizeMode::all() {
+                                                ui.selectable_value(
+                                                    &mut self.svg_options.normalize,
+                                                    *mode,
+                                                    mode.name(),
+                                                );
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text("Fit scales the drawing, never clamping");
+                                    if self.svg_options.normalize != previous_normalize {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if ui
+                                        .toggle_value(
+                                            &mut self.svg_options.optimize_order,
+                                            "Optimize path",
+                                        )
+                                        .on_hover_text("Chain subpaths end to end to cut the jumps")
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    ui.checkbox(
+                                        &mut self.svg_options.frames_from_groups,
+                                        "Groups as frames",
+                                    )
+                                    .on_hover_text(
+                                        "Play top-level groups as animation frames (on load)",
+                                    );
+                                    if self.svg_options.frames_from_groups {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.svg_options.frame_rate,
+                                            1.0..=60.0,
+                                            |s| s.text("Frame rate"),
+                                        );
+                                    }
+                                }
+
+                                ShapeType::Csv => {
+                                    // Coordinate file loading UI
+                                    if ui
+                                        .button("Load CSV or WAV...")
+                                        .on_hover_text("A stereo WAV draws left as X, right as Y")
+                                        .clicked()
+                                    {
+                                        self.load_csv_file();
+                                    }
+
+                                    // Show CSV info if loaded
+                                    if let Some(ref csv) = self.loaded_csv {
+                                        ui.label(format!("Points: {}", csv.len()));
+                                    } else {
+                                        ui.label("No CSV loaded");
+                                    }
+
+                                    // Show error if any
+                                    if let Some(ref error) = self.csv_error {
+                                        ui.colored_label(egui::Color32::RED, error);
+                                    }
+
+                                    ui.separator();
+                                    ui.label("CSV Options:");
+
+                                    if ui
+                                        .checkbox(&mut self.csv_options.normalize, "Fit to screen")
+                                        .on_hover_text("Off: coordinates are already in [-1, 1]")
+                                        .changed()
+                                    {
+                                        self.reload_csv();
+                                    }
                                 }
 
                                 ShapeType::Image => {
@@ -968,59 +2197,128 @@ impl eframe::App for OsciApp {
                                     }
 
                                     ui.separator();
+
+                                    let previous_mode = self.image_options.mode;
+                                    egui::ComboBox::from_label("Trace mode")
+                                        .selected_text(self.image_options.mode.name())
+                                        .show_ui(ui, |ui| {
+                                            for mode in ImageMode::all() {
+                                                ui.selectable_value(
+                                                    &mut self.image_options.mode,
+                                                    *mode,
+                                                    mode.name(),
+                                                );
+                                            }
+                                        });
+                                    if self.image_options.mode != previous_mode {
+                                        self.shape_needs_update = true;
+                                    }
+
                                     ui.label("Edge Detection:");
 
                                     // Threshold slider
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.image_options.threshold,
+                                        0.05..=0.9,
+                                        |s| s.text("Threshold"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Edge minimum
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.image_options.edge_min,
+                                        0.0..=0.5,
+                                        |s| s.text("Min edge"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Max points
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.image_options.max_points,
+                                        500..=20000,
+                                        |s| s.text("Max points").logarithmic(true),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Smoothing window
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.image_options.smoothing,
+                                        0..=16,
+                                        |s| s.text("Smoothing"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Invert option
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.image_options.threshold,
-                                                0.05..=0.9,
-                                            )
-                                            .text("Threshold"),
-                                        )
+                                        .checkbox(&mut self.image_options.invert, "Invert image")
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
 
-                                    // Edge minimum
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.image_options.edge_min,
-                                                0.0..=0.5,
-                                            )
-                                            .text("Min edge"),
-                                        )
+                                        .checkbox(&mut self.image_options.thin_edges, "Thin edges")
+                                        .on_hover_text("Keep only the ridge of each edge")
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
 
-                                    // Max points
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.image_options.max_points,
-                                                500..=20000,
-                                            )
-                                            .text("Max points")
-                                            .logarithmic(true),
+                                        .checkbox(
+                                            &mut self.image_options.recenter,
+                                            "Center on centroid",
                                         )
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
 
-                                    // Invert option
                                     if ui
-                                        .checkbox(&mut self.image_options.invert, "Invert image")
+                                        .checkbox(
+                                            &mut self.image_options.preserve_aspect,
+                                            "Keep proportions",
+                                        )
+                                        .on_hover_text("Off stretches the shape to fill the square")
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
 
+                                    let previous_normalize = self.image_options.normalize;
+                                    egui::ComboBox::new("image_normalize", "Normalize")
+                                        .selected_text(self.image_options.normalize.name())
+                                        .show_ui(ui, |ui| {
+                                            for mode in NormalizeMode::all() {
+                                                ui.selectable_value(
+                                                    &mut self.image_options.normalize,
+                                                    *mode,
+                                                    mode.name(),
+                                                );
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text("Fit scales the traced outline");
+                                    if self.image_options.normalize != previous_normalize {
+                                        self.shape_needs_update = true;
+                                    }
+
                                     // Reload button
                                     if self.loaded_image.is_some()
                                         && ui.button("Reload with options").clicked()
@@ -1051,43 +2349,94 @@ impl eframe::App for OsciApp {
                                     ui.separator();
                                     ui.label("Text Options:");
 
+                                    let previous_style = self.text_options.style;
+                                    egui::ComboBox::from_label("Lettering")
+                                        .selected_text(self.text_options.style.name())
+                                        .show_ui(ui, |ui| {
+                                            for style in TextStyle::all() {
+                                                ui.selectable_value(
+                                                    &mut self.text_options.style,
+                                                    *style,
+                                                    style.name(),
+                                                );
+                                            }
+                                        });
+                                    if self.text_options.style != previous_style {
+                                        self.shape_needs_update = true;
+                                    }
+
                                     // Font size
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.text_options.size,
+                                        16.0..=128.0,
+                                        |s| s.text("Font size"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Curve detail
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.text_options.size,
-                                                16.0..=128.0,
-                                            )
-                                            .text("Font size"),
+                                        .checkbox(
+                                            &mut self.text_options.adaptive,
+                                            "Adaptive curves",
+                                        )
+                                        .on_hover_text(
+                                            "Spend points where curves bend, not on flat stretches",
                                         )
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
+                                    let detail = if self.text_options.adaptive {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.text_options.curve_tolerance,
+                                            0.02..=1.0,
+                                            |s| s.logarithmic(true).text("Curve tolerance"),
+                                        )
+                                    } else {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.text_options.curve_samples,
+                                            2..=16,
+                                            |s| s.text("Curve detail"),
+                                        )
+                                    };
+                                    if detail.changed() {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    // Letter spacing
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.text_options.letter_spacing,
+                                        0.5..=2.0,
+                                        |s| s.text("Letter spacing"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
 
-                                    // Curve detail
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.text_options.curve_samples,
-                                                2..=16,
-                                            )
-                                            .text("Curve detail"),
+                                        .checkbox(
+                                            &mut self.text_options.recenter,
+                                            "Center on centroid",
                                         )
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
                                     }
 
-                                    // Letter spacing
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.text_options.letter_spacing,
-                                                0.5..=2.0,
-                                            )
-                                            .text("Letter spacing"),
+                                        .checkbox(
+                                            &mut self.text_options.preserve_aspect,
+                                            "Keep proportions",
                                         )
+                                        .on_hover_text("Off stretches the shape to fill the square")
                                         .changed()
                                     {
                                         self.shape_needs_update = true;
@@ -1130,6 +2479,12 @@ impl eframe::App for OsciApp {
                                     if let Some(mesh) = mesh {
                                         ui.label(format!("Vertices: {}", mesh.vertices.len()));
                                         ui.label(format!("Edges: {}", mesh.edges.len()));
+                                        if !mesh.materials.is_empty() {
+                                            ui.label(format!(
+                                                "Materials: {}",
+                                                mesh.materials.join(", ")
+                                            ));
+                                        }
                                     } else if self.mesh_primitive != MeshPrimitive::Custom {
                                         if let Some(m) = self.mesh_primitive.to_mesh() {
                                             ui.label(format!("Vertices: {}", m.vertices.len()));
@@ -1182,12 +2537,10 @@ impl eframe::App for OsciApp {
 
                                     // FOV slider (degrees, converted to radians)
                                     let mut fov_deg = self.mesh_camera.fov_degrees();
-                                    if ui
-                                        .add(
-                                            egui::Slider::new(&mut fov_deg, 30.0..=120.0)
-                                                .text("FOV"),
-                                        )
-                                        .changed()
+                                    if nudge_slider(ui, &mut fov_deg, 30.0..=120.0, |s| {
+                                        s.text("FOV")
+                                    })
+                                    .changed()
                                     {
                                         self.mesh_camera.set_fov_degrees(fov_deg);
                                         self.shape_needs_update = true;
@@ -1202,14 +2555,88 @@ impl eframe::App for OsciApp {
                                     ui.separator();
                                     ui.label("Rendering:");
 
+                                    if ui
+                                        .checkbox(
+                                            &mut self.mesh_options.proportional,
+                                            "Detail follows edge length",
+                                        )
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
                                     // Line detail slider
+                                    let detail = if self.mesh_options.proportional {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.mesh_options.edge_density,
+                                            2.0..=64.0,
+                                            |s| s.text("Edge density"),
+                                        )
+                                    } else {
+                                        nudge_slider(
+                                            ui,
+                                            &mut self.mesh_options.edge_samples,
+                                            2..=50,
+                                            |s| s.text("Edge detail"),
+                                        )
+                                    };
+                                    if detail.changed() {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.mesh_options.subdivision,
+                                        0..=3,
+                                        |s| s.text("Subdivision"),
+                                    )
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if nudge_slider(
+                                        ui,
+                                        &mut self.mesh_options.max_points,
+                                        1000..=200_000,
+                                        |s| s.text("Point budget").logarithmic(true),
+                                    )
+                                    .on_hover_text("Edges past this many points are not drawn")
+                                    .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
                                     if ui
-                                        .add(
-                                            egui::Slider::new(
-                                                &mut self.mesh_options.edge_samples,
-                                                2..=50,
-                                            )
-                                            .text("Edge detail"),
+                                        .checkbox(&mut self.mesh_options.clean, "Clean mesh")
+                                        .on_hover_text(
+                                            "Merge coincident vertices and drop duplicate edges",
+                                        )
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if ui
+                                        .checkbox(
+                                            &mut self.mesh_options.optimize_order,
+                                            "Optimize path",
+                                        )
+                                        .on_hover_text("Chain edges end to end to cut the jumps")
+                                        .changed()
+                                    {
+                                        self.shape_needs_update = true;
+                                    }
+
+                                    if ui
+                                        .checkbox(
+                                            &mut self.mesh_options.hide_back_faces,
+                                            "Hide back faces",
+                                        )
+                                        .on_hover_text(
+                                            "Leave out edges whose faces all point away from the \
+                                             camera",
                                         )
                                         .changed()
                                     {
@@ -1223,6 +2650,34 @@ impl eframe::App for OsciApp {
                             ui.heading("Scene");
                             ui.separator();
 
+                            if ui
+                                .checkbox(&mut self.scene_by_length, "Equal beam speed")
+                                .on_hover_text("Share trace time by shape length as well as weight")
+                                .changed()
+                            {
+                                self.shape_needs_update = true;
+                            }
+
+                            let playback = self.scene_playback;
+                            egui::ComboBox::from_label("Order")
+                                .selected_text(self.scene_playback.name())
+                                .show_ui(ui, |ui| {
+                                    for option in ScenePlayback::all() {
+                                        ui.selectable_value(
+                                            &mut self.scene_playback,
+                                            *option,
+                                            option.name(),
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Trace the shapes backwards, or there and back in each trace",
+                                );
+                            if self.scene_playback != playback {
+                                self.shape_needs_update = true;
+                            }
+
                             // Add shape to scene
                             ui.horizontal(|ui| {
                                 egui::ComboBox::from_id_salt("add_shape")
@@ -1237,8 +2692,11 @@ impl eframe::App for OsciApp {
                                         }
                                     });
                                 if ui.button("+ Add").clicked() {
-                                    self.scene_entries
-                                        .push(SceneEntry::new(self.scene_shape_to_add));
+                                    let mut entry = SceneEntry::new(self.scene_shape_to_add);
+                                    if entry.shape_type == ShapeType::Mesh3D {
+                                        entry.mesh = self.current_scene_mesh();
+                                    }
+                                    self.scene_entries.push(entry);
                                     self.shape_needs_update = true;
                                 }
                             });
@@ -1254,6 +2712,7 @@ impl eframe::App for OsciApp {
                                 let mut to_remove: Option<usize> = None;
                                 let mut to_move_up: Option<usize> = None;
                                 let mut to_move_down: Option<usize> = None;
+                                let mut to_recapture: Option<usize> = None;
 
                                 egui::ScrollArea::vertical()
                                     .max_height(200.0)
@@ -1262,23 +2721,65 @@ impl eframe::App for OsciApp {
                                         {
                                             ui.horizontal(|ui| {
                                                 // Enable checkbox
-                                                if ui.checkbox(&mut entry.enabled, "").changed() {
+                                                if ui
+                                                    .checkbox(&mut entry.enabled, "")
+                                                    .on_hover_text("Unchecked = muted")
+                                                    .changed()
+                                                {
+                                                    self.shape_needs_update = true;
+                                                }
+
+                                                // Solo toggle
+                                                if ui
+                                                    .toggle_value(&mut entry.soloed, "S")
+                                                    .on_hover_text("Solo (overrides mute)")
+                                                    .changed()
+                                                {
                                                     self.shape_needs_update = true;
                                                 }
 
                                                 // Shape name
                                                 ui.label(entry.shape_type.name());
+                                                if entry.shape_type == ShapeType::Mesh3D {
+                                                    let name = entry
+                                                        .mesh
+                                                        .as_ref()
+                                                        .map_or("cube", |m| m.mesh.name.as_str());
+                                                    let hover =
+                                                        format!("{}: click to use current", name);
+                                                    if ui
+                                                        .small_button("⟳")
+                                                        .on_hover_text(hover)
+                                                        .clicked()
+                                                    {
+                                                        to_recapture = Some(i);
+                                                    }
+                                                }
 
                                                 // Weight slider
-                                                if ui
-                                                    .add(
-                                                        egui::Slider::new(
-                                                            &mut entry.weight,
-                                                            0.1..=3.0,
-                                                        )
-                                                        .show_value(false),
-                                                    )
-                                                    .changed()
+                                                if nudge_slider(
+                                                    ui,
+                                                    &mut entry.weight,
+                                                    0.1..=3.0,
+                                                    |s| s.show_value(false),
+                                                )
+                                                .changed()
+                                                {
+                                                    self.shape_needs_update = true;
+                                                }
+
+                                                // Amplitude slider
+                                                if nudge_slider(
+                                                    ui,
+                                                    &mut entry.amplitude,
+                                                    0.0..=2.0,
+                                                    |s| s.show_value(false),
+                                                )
+                                                .on_hover_text(format!(
+                                                    "Size: {:.2}",
+                                                    entry.amplitude
+                                                ))
+                                                .changed()
                                                 {
                                                     self.shape_needs_update = true;
                                                 }
@@ -1300,6 +2801,10 @@ impl eframe::App for OsciApp {
                                     });
 
                                 // Process deferred actions
+                                if let Some(i) = to_recapture {
+                                    self.scene_entries[i].mesh = self.current_scene_mesh();
+                                    self.shape_needs_update = true;
+                                }
                                 if let Some(i) = to_remove {
                                     self.scene_entries.remove(i);
                                     self.shape_needs_update = true;
@@ -1331,26 +2836,311 @@ impl eframe::App for OsciApp {
 
                     // Audio settings
                     ui.collapsing("Audio", |ui| {
-                        if ui
-                            .add(
-                                egui::Slider::new(&mut self.audio.config.frequency, 20.0..=200.0)
-                                    .text("Speed (Hz)")
-                                    .logarithmic(true),
+                        let speed_range = MIN_FREQUENCY..=MAX_FREQUENCY;
+                        if nudge_slider(
+                            ui,
+                            &mut self.audio.config.frequency,
+                            speed_range.clone(),
+                            |s| s.text("Speed (Hz)").logarithmic(true),
+                        )
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        // Precise numeric entry
+                        ui.horizontal(|ui| {
+                            ui.label("Exact:");
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.audio.config.frequency)
+                                        .range(speed_range)
+                                        .speed(0.1)
+                                        .suffix(" Hz"),
+                                )
+                                .changed()
+                            {
+                                self.shape_needs_update = true;
+                            }
+                        });
+
+                        if nudge_slider(ui, &mut self.audio.config.time_scale, 0.1..=2.0, |s| {
+                            s.text("Master speed").suffix("×").logarithmic(true)
+                        })
+                        .on_hover_text("Slow down or speed up the trace and every effect together")
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        if nudge_slider(ui, &mut self.audio.config.volume, 0.0..=1.0, |s| {
+                            s.text("Volume")
+                        })
+                        .changed()
+                        {
+                            self.output_needs_update = true;
+                        }
+
+                        // Stream buffer size (latency)
+                        let buffer_label = |frames: Option<u32>| match frames {
+                            Some(f) => format!("{} frames", f),
+                            None => "Device default".to_string(),
+                        };
+                        let mut buffer_frames = self.audio.config.buffer_frames;
+                        egui::ComboBox::from_label("Buffer")
+                            .selected_text(buffer_label(buffer_frames))
+                            .show_ui(ui, |ui| {
+                                for option in BUFFER_FRAME_OPTIONS {
+                                    ui.selectable_value(
+                                        &mut buffer_frames,
+                                        *option,
+                                        buffer_label(*option),
+                                    );
+                                }
+                            });
+                        if buffer_frames != self.audio.config.buffer_frames {
+                            self.audio.config.buffer_frames = buffer_frames;
+                            self.audio.restart();
+                        }
+
+                        // X/Y output channel routing for multi-channel interfaces
+                        let mut channels =
+                            [self.audio.config.x_channel, self.audio.config.y_channel];
+                        ui.horizontal(|ui| {
+                            for (axis, name) in ["X", "Y"].iter().enumerate() {
+                                // X and Y can't share a channel
+                                let other = channels[1 - axis];
+                                let channel = &mut channels[axis];
+                                egui::ComboBox::from_label(format!("{} out", name))
+                                    .selected_text(format!("{}", *channel + 1))
+                                    .width(40.0)
+                                    .show_ui(ui, |ui| {
+                                        for ch in (0..MAX_OUTPUT_CHANNELS).filter(|&ch| ch != other)
+                                        {
+                                            ui.selectable_value(channel, ch, format!("{}", ch + 1));
+                                        }
+                                    });
+                            }
+                        });
+                        if channels != [self.audio.config.x_channel, self.audio.config.y_channel] {
+                            [self.audio.config.x_channel, self.audio.config.y_channel] = channels;
+                            self.audio.restart();
+                        }
+
+                        // Z blanking is a DC level, so it's only sent where asked
+                        let mut z_channel = self.audio.config.z_channel;
+                        egui::ComboBox::from_label("Z out")
+                            .selected_text(
+                                z_channel.map_or("Off".to_string(), |ch| format!("{}", ch + 1)),
                             )
+                            .width(40.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut z_channel, None, "Off");
+                                for ch in 0..MAX_OUTPUT_CHANNELS {
+                                    ui.selectable_value(
+                                        &mut z_channel,
+                                        Some(ch),
+                                        format!("{}", ch + 1),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text("Blanking signal for scopes with a Z input (1 = lit)");
+                        if z_channel != self.audio.config.z_channel {
+                            self.audio.config.z_channel = z_channel;
+                            self.audio.restart();
+                        }
+
+                        // Mirror the output, summed to mono, to a second device
+                        let mut monitor = self.audio.config.monitor_device.clone();
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_label("Monitor")
+                                .selected_text(monitor.as_deref().unwrap_or("Off"))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut monitor, None, "Off");
+                                    for name in &self.output_devices {
+                                        ui.selectable_value(&mut monitor, Some(name.clone()), name);
+                                    }
+                                });
+                            if ui
+                                .small_button("⟳")
+                                .on_hover_text("Refresh devices")
+                                .clicked()
+                            {
+                                self.output_devices = output_device_names();
+                            }
+                        });
+                        if monitor != self.audio.config.monitor_device {
+                            self.audio.config.monitor_device = monitor;
+                            self.audio.restart();
+                        }
+
+                        // Sample format, and dither for integer formats
+                        let mut format = self.audio.config.output_format;
+                        egui::ComboBox::from_label("Format")
+                            .selected_text(format.name())
+                            .show_ui(ui, |ui| {
+                                for option in OutputFormat::all() {
+                                    ui.selectable_value(&mut format, *option, option.name());
+                                }
+                            });
+                        let dither = ui
+                            .checkbox(&mut self.audio.config.dither, "Dither")
+                            .on_hover_text("Hide integer quantization steps in slow figures");
+                        if format != self.audio.config.output_format || dither.changed() {
+                            self.audio.config.output_format = format;
+                            self.audio.restart();
+                        }
+
+                        // Loop region: trace only part of the shape
+                        let config = &mut self.audio.config;
+                        let from = nudge_slider(ui, &mut config.start_t, 0.0..=1.0, |s| {
+                            s.text("Trace from")
+                        });
+                        let to =
+                            nudge_slider(ui, &mut config.end_t, 0.0..=1.0, |s| s.text("Trace to"));
+                        if from.changed() || to.changed() {
+                            self.shape_needs_update = true;
+                        }
+
+                        if nudge_slider(ui, &mut self.audio.config.blank_distance, 0.0..=2.0, |s| {
+                            s.text("Pen-up distance")
+                        })
+                        .on_hover_text("Blank jumps longer than this (0 = off)")
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        if nudge_slider(
+                            ui,
+                            &mut self.audio.config.center_dead_zone,
+                            0.0..=0.2,
+                            |s| s.text("Center dead zone"),
+                        )
+                        .on_hover_text("Skip samples this close to the center to avoid burn-in")
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        if ui
+                            .checkbox(&mut self.audio.config.close_gap, "Close loops")
+                            .on_hover_text("Join closed shapes back to their start point")
                             .changed()
                         {
                             self.shape_needs_update = true;
                         }
 
+                        if nudge_slider(
+                            ui,
+                            &mut self.audio.config.retrace_fraction,
+                            0.0..=0.5,
+                            |s| s.text("Retrace"),
+                        )
+                        .on_hover_text(
+                            "Part of each cycle spent sweeping open shapes back to their start",
+                        )
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        if nudge_slider(ui, &mut self.audio.config.corner_dwell, 0..=16, |s| {
+                            s.text("Corner dwell")
+                        })
+                        .on_hover_text("Extra samples held at sharp corners to brighten them")
+                        .changed()
+                        {
+                            self.shape_needs_update = true;
+                        }
+
+                        // Layered passes over closed shapes
+                        let config = &mut self.audio.config;
+                        let passes = nudge_slider(ui, &mut config.revolutions, 1..=8, |s| {
+                            s.text("Revolutions")
+                        })
+                        .on_hover_text("Times each closed shape is traced per cycle");
+                        let turn =
+                            nudge_slider(ui, &mut config.per_rev_rotation, -180.0..=180.0, |s| {
+                                s.text("Turn per revolution").suffix("°")
+                            });
+                        if passes.changed() || turn.changed() {
+                            self.shape_needs_update = true;
+                        }
+
+                        if nudge_slider(
+                            ui,
+                            &mut self.audio.config.channel_phase_offset,
+                            -64..=64,
+                            |s| s.text("Y phase offset"),
+                        )
+                        .on_hover_text("Samples the Y channel runs ahead of X, for a pseudo-3D lag")
+                        .changed()
+                        {
+                            self.output_needs_update = true;
+                        }
+
                         if ui
-                            .add(
-                                egui::Slider::new(&mut self.audio.config.volume, 0.0..=1.0)
-                                    .text("Volume"),
-                            )
+                            .checkbox(&mut self.audio.config.auto_fit, "Auto-fit")
+                            .on_hover_text("Scale each shape to fill the screen")
                             .changed()
+                        {
+                            self.output_needs_update = true;
+                        }
+
+                        // Mastering-style output matrix
+                        let config = &mut self.audio.config;
+                        let rotation =
+                            nudge_slider(ui, &mut config.output_rotation, -180.0..=180.0, |s| {
+                                s.text("Output rotation").suffix("°")
+                            });
+                        let width = nudge_slider(ui, &mut config.stereo_width, 0.0..=2.0, |s| {
+                            s.text("Stereo width")
+                        })
+                        .on_hover_text("Gain on the X - Y difference (1 = unchanged)");
+                        if rotation.changed() || width.changed() {
+                            self.output_needs_update = true;
+                        }
+
+                        if nudge_slider(
+                            ui,
+                            &mut self.audio.config.max_total_points,
+                            0..=MAX_POINT_BUDGET,
+                            |s| s.text("Point budget").logarithmic(true),
+                        )
+                        .on_hover_text("Decimate traces with more points than this (0 = no limit)")
+                        .changed()
                         {
                             self.shape_needs_update = true;
                         }
+
+                        let mut invert_y = self.audio.invert_y();
+                        if ui.checkbox(&mut invert_y, "Invert Y").changed() {
+                            self.audio.set_invert_y(invert_y);
+                        }
+
+                        let mut max_slew = self.audio.max_slew();
+                        if nudge_slider(ui, &mut max_slew, 0.0..=0.5, |s| {
+                            s.text("Slew limit").logarithmic(true)
+                        })
+                        .on_hover_text(
+                            "Largest X/Y step per sample, for galvos and lasers (0 = off)",
+                        )
+                        .changed()
+                        {
+                            self.audio.set_max_slew(max_slew);
+                        }
+
+                        let mut overlap = self.audio.trace_overlap();
+                        if nudge_slider(ui, &mut overlap, 0.0..=0.9, |s| s.text("Trace overlap"))
+                            .on_hover_text(
+                                "Blend each trace with the previous one in the signal itself",
+                            )
+                            .changed()
+                        {
+                            self.audio.set_trace_overlap(overlap);
+                        }
                     });
 
                     ui.separator();
@@ -1358,31 +3148,33 @@ impl eframe::App for OsciApp {
                     // Effects settings
                     ui.collapsing("Effects", |ui| {
                         // Rotation effect
-                        ui.checkbox(&mut self.enable_rotation, "Rotation");
-                        if self.enable_rotation {
-                            ui.add(
-                                egui::Slider::new(&mut self.rotation_speed, -5.0..=5.0)
-                                    .text("Speed (rad/s)"),
-                            );
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_rotation, "Rotation");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::Rotation);
+                        });
+                        if self.enable_rotation || self.solo_effect == Some(EffectSlot::Rotation) {
+                            nudge_slider(ui, &mut self.rotation_speed, -5.0..=5.0, |s| {
+                                s.text("Speed (rad/s)")
+                            });
                         }
 
                         ui.separator();
 
                         // Scale LFO effect
-                        ui.checkbox(&mut self.enable_scale_lfo, "Pulsing Scale");
-                        if self.enable_scale_lfo {
-                            ui.add(
-                                egui::Slider::new(&mut self.scale_lfo_freq, 0.1..=10.0)
-                                    .text("Frequency (Hz)"),
-                            );
-                            ui.add(
-                                egui::Slider::new(&mut self.scale_lfo_min, 0.1..=1.5)
-                                    .text("Min scale"),
-                            );
-                            ui.add(
-                                egui::Slider::new(&mut self.scale_lfo_max, 0.5..=2.0)
-                                    .text("Max scale"),
-                            );
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_scale_lfo, "Pulsing Scale");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::ScaleLfo);
+                        });
+                        if self.enable_scale_lfo || self.solo_effect == Some(EffectSlot::ScaleLfo) {
+                            nudge_slider(ui, &mut self.scale_lfo_freq, 0.1..=10.0, |s| {
+                                s.text("Frequency (Hz)")
+                            });
+                            nudge_slider(ui, &mut self.scale_lfo_min, 0.1..=1.5, |s| {
+                                s.text("Min scale")
+                            });
+                            nudge_slider(ui, &mut self.scale_lfo_max, 0.5..=2.0, |s| {
+                                s.text("Max scale")
+                            });
 
                             // Waveform selection
                             egui::ComboBox::from_label("Waveform")
@@ -1396,48 +3188,312 @@ impl eframe::App for OsciApp {
                                         );
                                     }
                                 });
+                            nudge_slider(ui, &mut self.scale_lfo_spatial, 0.0..=16.0, |s| {
+                                s.text("Travel (cycles)")
+                            })
+                            .on_hover_text(
+                                "Spread the pulse along the outline so it travels round the shape",
+                            );
+                        }
+
+                        ui.separator();
+
+                        // Step sequencer effect
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_sequencer, "Step Sequencer");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::Sequencer);
+                        });
+                        if self.enable_sequencer || self.solo_effect == Some(EffectSlot::Sequencer)
+                        {
+                            egui::ComboBox::from_label("Sequence target")
+                                .selected_text(self.sequencer_target.name())
+                                .show_ui(ui, |ui| {
+                                    for target in SequencerTarget::all() {
+                                        ui.selectable_value(
+                                            &mut self.sequencer_target,
+                                            *target,
+                                            target.name(),
+                                        );
+                                    }
+                                });
+                            nudge_slider(ui, &mut self.sequencer_bpm, 30.0..=300.0, |s| {
+                                s.text("BPM")
+                            });
+                            nudge_slider(ui, &mut self.sequencer_rate, 0.25..=8.0, |s| {
+                                s.logarithmic(true).text("Steps per beat")
+                            });
+                            let mut count = self.sequencer_steps.len();
+                            if nudge_slider(ui, &mut count, 2..=16, |s| s.text("Steps")).changed() {
+                                self.sequencer_steps.resize(count, 0.5);
+                            }
+                            nudge_slider(ui, &mut self.sequencer_min, -1.0..=2.0, |s| {
+                                s.text("Min")
+                            });
+                            nudge_slider(ui, &mut self.sequencer_max, -1.0..=2.0, |s| {
+                                s.text("Max")
+                            });
+
+                            // One vertical fader per step
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().slider_width = 60.0;
+                                for step in &mut self.sequencer_steps {
+                                    nudge_slider(ui, step, 0.0..=1.0, |s| {
+                                        s.vertical().show_value(false)
+                                    });
+                                }
+                            });
                         }
 
-                        // Update effect parameters on the audio engine
-                        self.audio.set_effects(EffectParams {
-                            rotation_speed: self.rotation_speed,
-                            rotation_enabled: self.enable_rotation,
-                            scale_lfo_freq: self.scale_lfo_freq,
-                            scale_lfo_min: self.scale_lfo_min,
-                            scale_lfo_max: self.scale_lfo_max,
-                            scale_lfo_enabled: self.enable_scale_lfo,
-                            scale_lfo_waveform: self.scale_lfo_waveform,
+                        ui.separator();
+
+                        // Tiling effect
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_tile, "Tile");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::Tile);
                         });
+                        if self.enable_tile || self.solo_effect == Some(EffectSlot::Tile) {
+                            nudge_slider(ui, &mut self.tile_x, 1..=6, |s| s.text("Tiles X"));
+                            nudge_slider(ui, &mut self.tile_y, 1..=6, |s| s.text("Tiles Y"));
+                            ui.checkbox(&mut self.tile_mirror, "Mirror tiles");
+                        }
+
+                        ui.separator();
+
+                        // Radial ripple (spatial FM)
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_ripple, "Ripple");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::Ripple);
+                        });
+                        if self.enable_ripple || self.solo_effect == Some(EffectSlot::Ripple) {
+                            // Whole ripples per turn keep the figure seamless, so
+                            // this keeps egui's own whole-number arrow steps
+                            ui.add(
+                                egui::Slider::new(&mut self.ripple_freq, 1.0..=32.0)
+                                    .step_by(1.0)
+                                    .text("Ripples"),
+                            );
+                            nudge_slider(ui, &mut self.ripple_depth, -0.5..=0.5, |s| {
+                                s.text("Depth")
+                            });
+                        }
+
+                        ui.separator();
+
+                        // Random per-sample displacement
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.enable_jitter, "Jitter");
+                            effect_solo_toggle(ui, &mut self.solo_effect, EffectSlot::Jitter);
+                        });
+                        if self.enable_jitter || self.solo_effect == Some(EffectSlot::Jitter) {
+                            nudge_slider(ui, &mut self.jitter_amount, 0.0..=0.1, |s| {
+                                s.text("Amount")
+                            });
+                        }
+
+                        ui.separator();
+
+                        // What happens to points the effects push off screen
+                        egui::ComboBox::from_label("Out of bounds")
+                            .selected_text(self.out_of_bounds.name())
+                            .show_ui(ui, |ui| {
+                                for mode in OutOfBoundsMode::all() {
+                                    ui.selectable_value(
+                                        &mut self.out_of_bounds,
+                                        *mode,
+                                        mode.name(),
+                                    );
+                                }
+                            });
+                        ui.checkbox(&mut self.radial_limit, "Keep inside circle")
+                            .on_hover_text(
+                            "Pull points past the unit circle back onto it, keeping their angle",
+                        );
+
+                        ui.separator();
+
+                        // Spring smoothing of size/rotation changes
+                        ui.checkbox(&mut self.enable_spring, "Spring wobble");
+                        if self.enable_spring {
+                            let mut stiffness = self.size_spring.stiffness;
+                            let mut damping = self.size_spring.damping;
+                            nudge_slider(ui, &mut stiffness, 10.0..=500.0, |s| {
+                                s.text("Stiffness").logarithmic(true)
+                            });
+                            nudge_slider(ui, &mut damping, 0.5..=40.0, |s| s.text("Damping"));
+                            for spring in [&mut self.size_spring, &mut self.rotation_spring] {
+                                spring.stiffness = stiffness;
+                                spring.damping = damping;
+                            }
+                        }
                     });
 
                     ui.separator();
 
                     // Display settings
                     ui.collapsing("Display", |ui| {
-                        ui.add(
-                            egui::Slider::new(&mut self.oscilloscope.settings.zoom, 0.1..=2.0)
-                                .text("Zoom"),
-                        );
-                        ui.add(
-                            egui::Slider::new(
-                                &mut self.oscilloscope.settings.line_width,
-                                0.5..=5.0,
-                            )
-                            .text("Line width"),
+                        let settings = &mut self.oscilloscope.settings;
+                        let zoom_x_changed =
+                            nudge_slider(ui, &mut settings.zoom_x, 0.1..=2.0, |s| s.text("Zoom X"))
+                                .changed();
+                        let zoom_y_changed =
+                            nudge_slider(ui, &mut settings.zoom_y, 0.1..=2.0, |s| s.text("Zoom Y"))
+                                .changed();
+                        if settings.zoom_linked {
+                            if zoom_x_changed {
+                                settings.zoom_y = settings.zoom_x;
+                            } else if zoom_y_changed {
+                                settings.zoom_x = settings.zoom_y;
+                            }
+                        }
+                        // Unlink to match channels with different gains
+                        ui.checkbox(&mut settings.zoom_linked, "Link X/Y zoom");
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.line_width,
+                            0.5..=5.0,
+                            |s| s.text("Line width"),
                         );
-                        ui.add(
-                            egui::Slider::new(&mut self.oscilloscope.settings.intensity, 0.1..=1.0)
-                                .text("Intensity"),
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.intensity,
+                            0.1..=1.0,
+                            |s| s.text("Intensity"),
                         );
-                        ui.add(
-                            egui::Slider::new(
-                                &mut self.oscilloscope.settings.persistence,
-                                0.0..=0.99,
-                            )
-                            .text("Persistence"),
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.persistence,
+                            0.0..=0.99,
+                            |s| s.text("Persistence"),
                         );
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.persistence_gamma,
+                            0.5..=3.0,
+                            |s| s.text("Afterglow gamma"),
+                        )
+                        .on_hover_text("Higher values fade trails faster near the beam");
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.persistence_spacing,
+                            0.0..=8.0,
+                            |s| s.text("Afterglow spacing").suffix(" px"),
+                        )
+                        .on_hover_text("Skip afterglow points closer than this to the last one");
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.frame_blend,
+                            0.0..=0.95,
+                            |s| s.text("Frame blend"),
+                        )
+                        .on_hover_text("Blend each frame over the last to reduce flicker");
                         ui.checkbox(&mut self.oscilloscope.settings.show_graticule, "Show grid");
+                        if self.oscilloscope.settings.show_graticule {
+                            let [columns, rows] =
+                                &mut self.oscilloscope.settings.graticule_divisions;
+                            ui.horizontal(|ui| {
+                                ui.label("Divisions");
+                                ui.add(egui::DragValue::new(columns).range(2..=20));
+                                ui.label("×");
+                                ui.add(egui::DragValue::new(rows).range(2..=20));
+                            });
+                        }
                         ui.checkbox(&mut self.oscilloscope.settings.draw_lines, "Draw lines");
+                        if self.oscilloscope.settings.draw_lines {
+                            let join = &mut self.oscilloscope.settings.line_join;
+                            egui::ComboBox::from_label("Corners")
+                                .selected_text(join.name())
+                                .show_ui(ui, |ui| {
+                                    for option in LineJoin::all() {
+                                        ui.selectable_value(join, *option, option.name());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("How segments meet at sharp corners");
+                        }
+                        ui.checkbox(
+                            &mut self.oscilloscope.settings.show_cursor_readout,
+                            "Cursor readout",
+                        );
+                        ui.checkbox(
+                            &mut self.oscilloscope.settings.show_direction,
+                            "Show trace direction",
+                        )
+                        .on_hover_text("Arrowheads along the path show which way the beam moves");
+                        ui.checkbox(
+                            &mut self.oscilloscope.settings.show_velocity_field,
+                            "Show velocity field",
+                        )
+                        .on_hover_text("Arrows along the path, longer where the beam is faster");
+                        ui.checkbox(
+                            &mut self.oscilloscope.settings.show_draw_order,
+                            "Show draw order",
+                        )
+                        .on_hover_text("Number each SVG or image subpath at its start point");
+                        ui.checkbox(&mut self.show_crossings, "Count crossings")
+                            .on_hover_text("Show how often the trace crosses itself");
+                        if self.oscilloscope.settings.show_direction
+                            || self.oscilloscope.settings.show_velocity_field
+                        {
+                            nudge_slider(
+                                ui,
+                                &mut self.oscilloscope.settings.direction_spacing,
+                                4..=256,
+                                |s| s.logarithmic(true).text("Arrow spacing"),
+                            );
+                        }
+                        ui.checkbox(&mut self.oscilloscope.settings.show_beam_spot, "Beam spot")
+                            .on_hover_text("Highlight where the beam is now");
+                        if self.oscilloscope.settings.show_beam_spot {
+                            nudge_slider(
+                                ui,
+                                &mut self.oscilloscope.settings.beam_spot_brightness,
+                                1.0..=4.0,
+                                |s| s.text("Spot brightness").suffix("×"),
+                            );
+                        }
+                        ui.checkbox(
+                            &mut self.oscilloscope.settings.focus_coupling,
+                            "Intensity defocuses beam",
+                        );
+                        ui.checkbox(&mut self.idle_animation, "Idle animation")
+                            .on_hover_text("Show a demo figure while audio is stopped");
+                        ui.checkbox(&mut self.idle_dimmer.enabled, "Dim when idle")
+                            .on_hover_text("Slowly dim a figure left unchanged, against burn-in");
+                        if self.idle_dimmer.enabled {
+                            nudge_slider(ui, &mut self.idle_dimmer.delay, 10.0..=600.0, |s| {
+                                s.text("Dim after").suffix(" s").logarithmic(true)
+                            });
+                        }
+
+                        // Trigger: start each frame at the same phase
+                        ui.checkbox(&mut self.oscilloscope.settings.trigger, "Trigger")
+                            .on_hover_text("Start drawing at a zero crossing for a steady image");
+                        if self.oscilloscope.settings.trigger {
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("trigger_source")
+                                    .selected_text(self.oscilloscope.settings.trigger_source.name())
+                                    .show_ui(ui, |ui| {
+                                        for source in TriggerSource::all() {
+                                            ui.selectable_value(
+                                                &mut self.oscilloscope.settings.trigger_source,
+                                                *source,
+                                                source.name(),
+                                            );
+                                        }
+                                    });
+                                egui::ComboBox::from_id_salt("trigger_edge")
+                                    .selected_text(self.oscilloscope.settings.trigger_edge.name())
+                                    .show_ui(ui, |ui| {
+                                        for edge in TriggerEdge::all() {
+                                            ui.selectable_value(
+                                                &mut self.oscilloscope.settings.trigger_edge,
+                                                *edge,
+                                                edge.name(),
+                                            );
+                                        }
+                                    });
+                            });
+                        }
 
                         if ui.button("Clear trail").clicked() {
                             self.oscilloscope.clear_persistence();
@@ -1468,6 +3524,123 @@ impl eframe::App for OsciApp {
                                     egui::Color32::from_rgb(10, 10, 20);
                             }
                         });
+                        nudge_slider(
+                            ui,
+                            &mut self.oscilloscope.settings.color_cycle_speed,
+                            0.0..=1.0,
+                            |s| s.text("Hue cycle (turns/s)"),
+                        );
+                    });
+
+                    ui.separator();
+
+                    // Save the display as an image
+                    ui.collapsing("Export", |ui| {
+                        egui::ComboBox::from_label("Quality")
+                            .selected_text(format!("{}x", self.export_supersample))
+                            .show_ui(ui, |ui| {
+                                for &factor in EXPORT_QUALITY_OPTIONS {
+                                    ui.selectable_value(
+                                        &mut self.export_supersample,
+                                        factor,
+                                        format!("{}x", factor),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text("Supersampling: render larger and scale down");
+                        if ui.button("🖼 Export PNG...").clicked() {
+                            self.export_png();
+                        }
+                        if ui
+                            .button("📄 Export points...")
+                            .on_hover_text("Save the sampled points as CSV or JSON")
+                            .clicked()
+                        {
+                            self.export_points();
+                        }
+                        if let Some(ref error) = self.export_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+                    });
+
+                    ui.separator();
+
+                    // Share settings as text
+                    ui.collapsing("Share", |ui| {
+                        if ui.button("📋 Copy settings").clicked() {
+                            let shared = settings::AppSettings::from_app(self).to_share_string();
+                            ui.ctx().copy_text(shared);
+                            self.share_error = None;
+                        }
+
+                        ui.label("Paste settings:");
+                        ui.text_edit_singleline(&mut self.share_input);
+                        if ui.button("Apply").clicked() {
+                            match settings::AppSettings::from_share_string(&self.share_input) {
+                                Ok(mut shared) => {
+                                    let local = settings::AppSettings::from_app(self);
+                                    shared.keep_local_output(&local);
+                                    shared.apply(self);
+                                    self.share_input.clear();
+                                    self.share_error = None;
+                                }
+                                Err(e) => self.share_error = Some(e.to_string()),
+                            }
+                        }
+                        if let Some(ref error) = self.share_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+                    });
+
+                    ui.separator();
+
+                    // Music visualizer
+                    ui.collapsing("Visualizer", |ui| {
+                        ui.horizontal(|ui| {
+                            let input = &mut self.audio_input;
+                            let device_label = input
+                                .devices
+                                .get(input.selected_device)
+                                .cloned()
+                                .unwrap_or_else(|| "No devices".to_string());
+                            egui::ComboBox::from_id_salt("input_device")
+                                .selected_text(device_label)
+                                .show_ui(ui, |ui| {
+                                    for (i, name) in input.devices.iter().enumerate() {
+                                        ui.selectable_value(&mut input.selected_device, i, name);
+                                    }
+                                });
+                            if ui.button("Scan").clicked() {
+                                input.scan_devices();
+                            }
+                        });
+
+                        let listen_text = if self.audio_input.is_connected {
+                            "Stop listening"
+                        } else {
+                            "Listen"
+                        };
+                        if ui.button(listen_text).clicked() {
+                            self.audio_input.toggle();
+                        }
+                        ui.label(&self.audio_input.status);
+                        ui.add(egui::ProgressBar::new(self.visualizer_level).desired_height(6.0));
+
+                        nudge_slider(ui, &mut self.visualizer_sensitivity, 0.5..=20.0, |s| {
+                            s.logarithmic(true).text("Sensitivity")
+                        });
+                        nudge_slider(ui, &mut self.visualizer_size, 0.0..=2.0, |s| {
+                            s.text("Size response")
+                        })
+                        .on_hover_text("How much the shape grows with the input level");
+                        nudge_slider(
+                            ui,
+                            &mut self.visualizer_spin,
+                            -std::f32::consts::PI..=std::f32::consts::PI,
+                            |s| s.text("Rotation response"),
+                        )
+                        .on_hover_text("How far the shape turns with the input level (radians)");
                     });
 
                     ui.separator();
@@ -1508,6 +3681,94 @@ impl eframe::App for OsciApp {
                         }
                         ui.label(&self.midi.status);
 
+                        // Echo mapped values to a controller's faders and LEDs
+                        ui.horizontal(|ui| {
+                            let port_label = self
+                                .midi_out
+                                .ports
+                                .get(self.midi_out.selected_port)
+                                .cloned()
+                                .unwrap_or_else(|| "No ports".to_string());
+                            egui::ComboBox::from_label("Feedback")
+                                .selected_text(&port_label)
+                                .show_ui(ui, |ui| {
+                                    for (i, name) in self.midi_out.ports.iter().enumerate() {
+                                        ui.selectable_value(
+                                            &mut self.midi_out.selected_port,
+                                            i,
+                                            name,
+                                        );
+                                    }
+                                });
+                            if ui.button("Scan").clicked() {
+                                self.midi_out.scan_ports();
+                            }
+                        });
+                        let feedback_text = if self.midi_out.is_connected {
+                            "Stop feedback"
+                        } else {
+                            "Send feedback"
+                        };
+                        if ui
+                            .button(feedback_text)
+                            .on_hover_text("Send mapped values back as CC when they change")
+                            .clicked()
+                        {
+                            self.midi_out.toggle();
+                        }
+                        ui.label(&self.midi_out.status);
+                        ui.checkbox(&mut self.beat_flash, "Flash on clock beat")
+                            .on_hover_text(
+                                "Pulse the beam brightness on each quarter note of the MIDI clock",
+                            );
+
+                        ui.separator();
+
+                        // Record and loop parameter automation
+                        let now = self.start_time.elapsed().as_secs_f32();
+                        let recorder = &mut self.midi.recorder;
+                        ui.horizontal(|ui| match recorder.state() {
+                            midi::RecorderState::Idle => {
+                                if ui.button("⏺ Record").clicked() {
+                                    recorder.start_recording(now);
+                                }
+                                if ui
+                                    .add_enabled(
+                                        recorder.length() > 0.0,
+                                        egui::Button::new("▶ Loop"),
+                                    )
+                                    .clicked()
+                                {
+                                    recorder.start_playback(now);
+                                }
+                            }
+                            midi::RecorderState::Recording { start } => {
+                                ui.colored_label(
+                                    egui::Color32::RED,
+                                    format!("Recording {:.1}s", now - start),
+                                );
+                                if ui.button("▶ Loop").clicked() {
+                                    recorder.start_playback(now);
+                                }
+                                if ui.button("⏹ Stop").clicked() {
+                                    recorder.stop(now);
+                                }
+                            }
+                            midi::RecorderState::Playing { .. } => {
+                                ui.label(format!("Looping {:.1}s", recorder.length()));
+                                if ui.button("⏹ Stop").clicked() {
+                                    recorder.stop(now);
+                                }
+                            }
+                        });
+                        if recorder.event_count() > 0 {
+                            ui.small(format!(
+                                "{} updates over {:.1}s",
+                                recorder.event_count(),
+                                recorder.length()
+                            ));
+                        }
+
                         ui.separator();
 
                         // Mappings list
@@ -1569,10 +3830,58 @@ impl eframe::App for OsciApp {
                 });
         }
 
+        // Update effect parameters on the audio engine
+        let dt = ctx.input(|i| i.stable_dt);
+        self.update_springs(dt);
+        self.update_beat_flash(dt);
+        self.update_clip_indicator(dt);
+        self.update_visualizer(dt);
+        // Shape animation follows the master speed, like the effect clock
+        self.animation_time += dt * self.audio.config.time_scale;
+        self.animate_morph();
+        self.animate_svg();
+        // Effects that keep the figure moving count as activity too, and
+        // the dimming reaches the beam through the Z output
+        let mut params = self.effect_params();
+        self.update_idle_dimming(dt, figure_changed || params.is_animated());
+        params.brightness = self.idle_dimmer.brightness();
+        self.audio.set_effects(params);
+
+        // Lissajous ratio table
+        if self.show_lissajous_grid {
+            self.show_lissajous_grid_window(ctx);
+        }
+
+        if self.show_shape_library {
+            self.show_shape_library_window(ctx);
+        }
+
         // Main oscilloscope display
         egui::CentralPanel::default().show(ctx, |ui| {
-            let samples = self.buffer.get_samples();
-            self.oscilloscope.show(ui, &samples, None);
+            let time = self.start_time.elapsed().as_secs_f32();
+            let samples = if self.idle_animation && !self.audio.is_playing() {
+                render::idle_samples(time, IDLE_POINTS)
+            } else {
+                self.buffer.get_samples()
+            };
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.show_waveform, false, "XY");
+                ui.selectable_value(&mut self.show_waveform, true, "Waveform");
+                if self.show_waveform {
+                    for channel in WaveformChannel::all() {
+                        ui.selectable_value(&mut self.waveform.channel, *channel, channel.name());
+                    }
+                }
+            });
+            self.oscilloscope.set_time(time);
+            self.oscilloscope
+                .set_output_transform(self.audio.output_transform());
+            if self.show_waveform {
+                let recent = self.buffer.get_recent_samples(self.waveform.sample_count);
+                self.waveform.show(ui, &recent, &self.oscilloscope.settings);
+            } else {
+                self.oscilloscope.show(ui, &samples, None);
+            }
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                 ui.horizontal(|ui| {
@@ -1580,9 +3889,102 @@ impl eframe::App for OsciApp {
                     ui.separator();
                     ui.small(format!("Samples: {}", samples.len()));
                     ui.separator();
+                    match self.audio.decimated_from() {
+                        Some(original) => ui.small(format!(
+                            "Points: {} (decimated from {})",
+                            self.audio.samples_per_shape(),
+                            original
+                        )),
+                        None => ui.small(format!("Points: {}", self.audio.samples_per_shape())),
+                    };
+                    if let Some((min_x, min_y, max_x, max_y)) = self.audio.shape_bounds() {
+                        ui.separator();
+                        let text = format!(
+                            "Bounds: x {:.2}..{:.2}, y {:.2}..{:.2}",
+                            min_x, max_x, min_y, max_y
+                        );
+                        // Judge clipping on what goes out, after volume and auto-fit
+                        if self.audio.output_peak() > 1.0 {
+                            ui.small(egui::RichText::new(text).color(egui::Color32::YELLOW))
+                                .on_hover_text(
+                                    "Output extends past ±1 at this volume and may clip",
+                                );
+                        } else {
+                            ui.small(text);
+                        }
+                    }
+                    if let Some((cx, cy)) = self.audio.shape_centroid() {
+                        ui.separator();
+                        ui.small(format!("Center: ({:.2}, {:.2})", cx, cy));
+                    }
+                    let crossings = self.show_crossings.then(|| self.audio.crossing_count());
+                    if let Some(crossings) = crossings.flatten() {
+                        ui.separator();
+                        ui.small(format!("Crossings: {}", crossings))
+                            .on_hover_text("Times the trace crosses itself");
+                    }
+                    ui.separator();
                     ui.small("Milestone 16: Distribution");
                 });
             });
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_file_shape() {
+        let kind = |p: &str| dropped_file_shape(std::path::Path::new(p));
+        assert_eq!(kind("logo.svg"), Some(ShapeType::Svg));
+        assert_eq!(kind("points.CSV"), Some(ShapeType::Csv));
+        assert_eq!(kind("coords.txt"), Some(ShapeType::Csv));
+        assert_eq!(kind("photo.JPEG"), Some(ShapeType::Image));
+        assert_eq!(kind("model.obj"), Some(ShapeType::Mesh3D));
+        assert_eq!(kind("song.wav"), Some(ShapeType::Csv));
+        assert_eq!(kind("README"), None);
+    }
+
+    #[test]
+    fn test_scene_mesh_entry_uses_its_configured_mesh() {
+        let default_entry = SceneEntry::new(ShapeType::Mesh3D);
+        assert_eq!(
+            default_entry.mesh_shape().edge_count(),
+            Mesh::cube().edges.len()
+        );
+
+        let mut entry = SceneEntry::new(ShapeType::Mesh3D);
+        entry.mesh = Some(SceneMesh {
+            mesh: Mesh::tetrahedron(),
+            options: Mesh3DOptions::default(),
+            camera: Camera::default(),
+        });
+        let shape = entry.mesh_shape();
+        assert_eq!(shape.name(), Mesh::tetrahedron().name);
+        assert_eq!(shape.edge_count(), 6);
+
+        // The scene traces the tetrahedron's edges, not the cube's
+        let mut scene = Scene::new("test");
+        scene.add_weighted(entry.mesh_shape(), entry.weight);
+        let cube = default_entry.mesh_shape();
+        let ts = (0..64).map(|i| i as f32 / 64.0);
+        assert!(ts.clone().all(|t| scene.sample(t) == shape.sample(t)));
+        assert!(ts.clone().any(|t| scene.sample(t) != cube.sample(t)));
+    }
+
+    #[test]
+    fn test_every_shape_has_a_visible_thumbnail() {
+        let mut scope = Oscilloscope::new();
+        // Blending would let one thumbnail pass for the next
+        scope.settings.frame_blend = 0.0;
+        let background = scope.settings.background;
+        for &shape_type in ShapeType::all() {
+            let image = shape_thumbnail(&mut scope, shape_type);
+            assert_eq!(image.size, [THUMBNAIL_SIZE, THUMBNAIL_SIZE]);
+            let lit = image.pixels.iter().filter(|&&p| p != background).count();
+            assert!(lit > 20, "{} thumbnail is blank", shape_type.name());
+        }
+    }
+}
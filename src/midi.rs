@@ -2,18 +2,24 @@
 //!
 //! Receives MIDI CC messages and maps them to osci-rs parameters.
 //! Uses a lock-free approach: the MIDI callback writes to shared atomics
-//! that the UI thread reads each frame.
+//! that the UI thread reads each frame. MIDI clock messages are counted
+//! the same way to follow the beat.
+//!
+//! Mapped parameters can also be echoed to a MIDI output, so motorized
+//! faders and LED rings follow changes made in the app.
 
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
 use std::sync::Arc;
 
-use midir::{MidiInput, MidiInputConnection};
+use midir::{MidiInput, MidiInputConnection, MidiOutput, MidiOutputConnection};
 use serde::{Deserialize, Serialize};
 
 /// A parameter that can be controlled via MIDI CC
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MidiParam {
     Frequency,
+    TimeScale,
     Volume,
     RotationSpeed,
     ScaleLfoFreq,
@@ -28,6 +34,7 @@ pub enum MidiParam {
 impl MidiParam {
     pub const ALL: &[MidiParam] = &[
         Self::Frequency,
+        Self::TimeScale,
         Self::Volume,
         Self::RotationSpeed,
         Self::ScaleLfoFreq,
@@ -42,6 +49,7 @@ impl MidiParam {
     pub fn name(&self) -> &'static str {
         match self {
             Self::Frequency => "Frequency",
+            Self::TimeScale => "Master Speed",
             Self::Volume => "Volume",
             Self::RotationSpeed => "Rotation Speed",
             Self::ScaleLfoFreq => "Scale LFO Freq",
@@ -61,10 +69,19 @@ impl MidiParam {
         min + t * (max - min)
     }
 
+    /// Map a value in this parameter's range back to a CC value (0-127)
+    pub fn cc_value(&self, value: f32) -> u8 {
+        let (min, max) = self.range();
+        ((value - min) / (max - min) * 127.0)
+            .round()
+            .clamp(0.0, 127.0) as u8
+    }
+
     /// The (min, max) range for this parameter
     fn range(&self) -> (f32, f32) {
         match self {
             Self::Frequency => (20.0, 200.0),
+            Self::TimeScale => (0.1, 2.0),
             Self::Volume => (0.0, 1.0),
             Self::RotationSpeed => (-5.0, 5.0),
             Self::ScaleLfoFreq => (0.1, 10.0),
@@ -79,7 +96,7 @@ impl MidiParam {
 }
 
 /// A single CC-to-parameter mapping
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MidiMapping {
     pub cc: u8,
     pub param: MidiParam,
@@ -118,6 +135,144 @@ impl SharedCcValues {
     }
 }
 
+/// MIDI clock pulses per quarter note
+const TICKS_PER_BEAT: u32 = 24;
+
+/// Accumulates MIDI clock ticks and reports quarter-note boundaries
+#[derive(Debug, Default)]
+struct BeatCounter {
+    /// Ticks into the current beat
+    ticks: u32,
+}
+
+impl BeatCounter {
+    /// Add clock ticks, returning true if a beat boundary was crossed
+    fn advance(&mut self, ticks: u32) -> bool {
+        self.ticks += ticks;
+        let crossed = self.ticks >= TICKS_PER_BEAT;
+        self.ticks %= TICKS_PER_BEAT;
+        crossed
+    }
+
+    /// Restart counting at the top of a beat (on MIDI Start)
+    fn reset(&mut self) {
+        self.ticks = 0;
+    }
+}
+
+/// What a `PerformanceRecorder` is doing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RecorderState {
+    #[default]
+    Idle,
+    /// Capturing updates since the given time
+    Recording { start: f32 },
+    /// Looping the recording, started at the given time
+    Playing { start: f32 },
+}
+
+/// Records a window of MIDI parameter automation and loops it back
+///
+/// Times are seconds on any monotonic clock the caller likes (the app
+/// uses time since startup); only differences matter.
+#[derive(Debug, Default)]
+pub struct PerformanceRecorder {
+    /// Recorded updates as (time into the recording, parameter, value)
+    events: Vec<(f32, MidiParam, f32)>,
+    /// Loop length in seconds
+    length: f32,
+    /// Current state
+    state: RecorderState,
+    /// Next event to replay in the current loop
+    cursor: usize,
+    /// Loop iteration the cursor belongs to
+    loop_index: u64,
+}
+
+impl PerformanceRecorder {
+    /// Current state
+    pub fn state(&self) -> RecorderState {
+        self.state
+    }
+
+    /// Number of recorded updates
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Loop length in seconds
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// Discard any recording and start capturing
+    pub fn start_recording(&mut self, now: f32) {
+        self.events.clear();
+        self.length = 0.0;
+        self.state = RecorderState::Recording { start: now };
+    }
+
+    /// Capture updates while recording (ignored otherwise)
+    pub fn record(&mut self, now: f32, updates: &[(MidiParam, f32)]) {
+        if let RecorderState::Recording { start } = self.state {
+            let t = now - start;
+            self.events
+                .extend(updates.iter().map(|&(param, value)| (t, param, value)));
+        }
+    }
+
+    /// Start looping the recording from its beginning
+    pub fn start_playback(&mut self, now: f32) {
+        if let RecorderState::Recording { start } = self.state {
+            self.length = now - start;
+        }
+        self.cursor = 0;
+        self.loop_index = 0;
+        self.state = if self.length > 0.0 {
+            RecorderState::Playing { start: now }
+        } else {
+            RecorderState::Idle
+        };
+    }
+
+    /// Stop recording (keeping what was captured) or playback
+    pub fn stop(&mut self, now: f32) {
+        if let RecorderState::Recording { start } = self.state {
+            self.length = now - start;
+        }
+        self.state = RecorderState::Idle;
+    }
+
+    /// Updates that have come due since the last poll, in recorded order
+    pub fn poll(&mut self, now: f32) -> Vec<(MidiParam, f32)> {
+        let RecorderState::Playing { start } = self.state else {
+            return Vec::new();
+        };
+
+        let elapsed = (now - start).max(0.0);
+        let loop_index = (elapsed / self.length) as u64;
+        let position = elapsed % self.length;
+        let mut due = Vec::new();
+
+        if loop_index > self.loop_index {
+            // Finish the loop we were in; after a long stall, skip the
+            // loops that were missed entirely rather than replay them
+            due.extend(self.events[self.cursor..].iter().map(|&(_, p, v)| (p, v)));
+            self.cursor = 0;
+            self.loop_index = loop_index;
+        }
+
+        while let Some(&(t, param, value)) = self.events.get(self.cursor) {
+            if t > position {
+                break;
+            }
+            due.push((param, value));
+            self.cursor += 1;
+        }
+        due
+    }
+}
+
 /// MIDI input controller
 pub struct MidiController {
     /// Available MIDI port names (refreshed on scan)
@@ -132,6 +287,15 @@ pub struct MidiController {
     /// Shared CC values between MIDI thread and UI
     cc_values: SharedCcValues,
 
+    /// Clock ticks received since the last beat poll
+    clock_ticks: Arc<AtomicU32>,
+
+    /// Set when a MIDI Start message arrives
+    clock_started: Arc<AtomicBool>,
+
+    /// Beat position derived from the clock
+    beat: BeatCounter,
+
     /// User-defined CC-to-parameter mappings
     pub mappings: Vec<MidiMapping>,
 
@@ -143,6 +307,9 @@ pub struct MidiController {
 
     /// CC number being learned (for MIDI learn mode)
     pub learning: Option<usize>,
+
+    /// Record-and-loop of the parameter updates
+    pub recorder: PerformanceRecorder,
 }
 
 impl MidiController {
@@ -152,10 +319,14 @@ impl MidiController {
             selected_port: 0,
             connection: None,
             cc_values: SharedCcValues::new(),
+            clock_ticks: Arc::new(AtomicU32::new(0)),
+            clock_started: Arc::new(AtomicBool::new(false)),
+            beat: BeatCounter::default(),
             mappings: Vec::new(),
             status: "Disconnected".to_string(),
             is_connected: false,
             learning: None,
+            recorder: PerformanceRecorder::default(),
         };
         controller.scan_ports();
         controller
@@ -210,6 +381,8 @@ impl MidiController {
             .unwrap_or_else(|_| "Unknown".to_string());
 
         let cc_values = self.cc_values.clone();
+        let clock_ticks = Arc::clone(&self.clock_ticks);
+        let clock_started = Arc::clone(&self.clock_started);
 
         match midi_in.connect(
             port,
@@ -221,6 +394,17 @@ impl MidiController {
                     let value = message[2] & 0x7F;
                     cc_values.set(cc, value);
                 }
+                // System real-time: 0xF8 = clock tick, 0xFA = start
+                match message.first() {
+                    Some(0xF8) => {
+                        clock_ticks.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Some(0xFA) => {
+                        clock_ticks.store(0, Ordering::Relaxed);
+                        clock_started.store(true, Ordering::Relaxed);
+                    }
+                    _ => {}
+                }
             },
             (),
         ) {
@@ -288,6 +472,16 @@ impl MidiController {
         updates
     }
 
+    /// Consume clock ticks received since the last call.
+    /// Returns true if a new beat started. Call this once per frame.
+    pub fn poll_beat(&mut self) -> bool {
+        if self.clock_started.swap(false, Ordering::Relaxed) {
+            self.beat.reset();
+        }
+        let ticks = self.clock_ticks.swap(0, Ordering::Relaxed);
+        self.beat.advance(ticks)
+    }
+
     /// Add a new mapping
     pub fn add_mapping(&mut self, cc: u8, param: MidiParam) {
         self.mappings.push(MidiMapping { cc, param });
@@ -327,17 +521,306 @@ impl MidiController {
     }
 }
 
+/// MIDI output echoing mapped parameter values back to the controller
+pub struct MidiFeedback {
+    /// Available MIDI output port names (refreshed on scan)
+    pub ports: Vec<String>,
+
+    /// Currently selected port index (for UI combo box)
+    pub selected_port: usize,
+
+    /// Active connection (None if disconnected)
+    connection: Option<MidiOutputConnection>,
+
+    /// Last value sent per CC number
+    sent: HashMap<u8, u8>,
+
+    /// CC messages waiting to be sent
+    pending: Vec<[u8; 3]>,
+
+    /// Status message
+    pub status: String,
+
+    /// Whether currently connected
+    pub is_connected: bool,
+}
+
+impl MidiFeedback {
+    pub fn new() -> Self {
+        let mut feedback = Self {
+            ports: Vec::new(),
+            selected_port: 0,
+            connection: None,
+            sent: HashMap::new(),
+            pending: Vec::new(),
+            status: "Disconnected".to_string(),
+            is_connected: false,
+        };
+        feedback.scan_ports();
+        feedback
+    }
+
+    /// Scan for available MIDI output ports
+    pub fn scan_ports(&mut self) {
+        self.ports.clear();
+        match MidiOutput::new("osci-rs-scan") {
+            Ok(midi_out) => {
+                for port in midi_out.ports().iter() {
+                    let name = midi_out
+                        .port_name(port)
+                        .unwrap_or_else(|_| "Unknown".to_string());
+                    self.ports.push(name);
+                }
+                if self.ports.is_empty() {
+                    self.status = "No MIDI outputs found".to_string();
+                }
+            }
+            Err(e) => {
+                self.status = format!("MIDI init error: {}", e);
+            }
+        }
+    }
+
+    /// Connect to the currently selected MIDI output port
+    pub fn connect(&mut self) {
+        if self.is_connected {
+            return;
+        }
+
+        let midi_out = match MidiOutput::new("osci-rs") {
+            Ok(m) => m,
+            Err(e) => {
+                self.status = format!("MIDI init error: {}", e);
+                return;
+            }
+        };
+
+        let ports = midi_out.ports();
+        let Some(port) = ports.get(self.selected_port) else {
+            self.status = "Port not found".to_string();
+            return;
+        };
+        let port_name = midi_out
+            .port_name(port)
+            .unwrap_or_else(|_| "Unknown".to_string());
+
+        match midi_out.connect(port, "osci-rs-output") {
+            Ok(conn) => {
+                self.connection = Some(conn);
+                self.is_connected = true;
+                // Send every mapped value afresh to the new device
+                self.sent.clear();
+                self.status = format!("Sending to: {}", port_name);
+                log::info!("MIDI output connected: {}", port_name);
+            }
+            Err(e) => {
+                self.status = format!("Connect error: {}", e);
+                log::error!("MIDI output connect error: {}", e);
+            }
+        }
+    }
+
+    /// Disconnect from the current MIDI output port
+    pub fn disconnect(&mut self) {
+        if let Some(conn) = self.connection.take() {
+            conn.close();
+        }
+        self.is_connected = false;
+        self.pending.clear();
+        self.status = "Disconnected".to_string();
+        log::info!("MIDI output disconnected");
+    }
+
+    /// Toggle connection state
+    pub fn toggle(&mut self) {
+        if self.is_connected {
+            self.disconnect();
+        } else {
+            self.connect();
+        }
+    }
+
+    /// Send the (CC, value) pairs that changed since they were last sent.
+    /// Call this once per frame from the UI thread.
+    pub fn update(&mut self, values: &[(u8, u8)]) {
+        if !self.is_connected {
+            return;
+        }
+        self.queue_changes(values);
+        if let Some(conn) = self.connection.as_mut() {
+            for message in self.pending.drain(..) {
+                if let Err(e) = conn.send(&message) {
+                    log::warn!("MIDI output send error: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Queue a CC message for each value that differs from the last sent
+    fn queue_changes(&mut self, values: &[(u8, u8)]) {
+        for &(cc, value) in values {
+            let (cc, value) = (cc & 0x7F, value & 0x7F);
+            if self.sent.insert(cc, value) != Some(value) {
+                self.pending.push([0xB0, cc, value]);
+            }
+        }
+    }
+}
+
+/// Current CC value of each mapped parameter, for `MidiFeedback::update`
+pub fn feedback_values(app: &crate::OsciApp) -> Vec<(u8, u8)> {
+    app.midi
+        .mappings
+        .iter()
+        .map(|m| (m.cc, m.param.cc_value(param_value(m.param, app))))
+        .collect()
+}
+
+/// Current value of a parameter in the app, the inverse of `apply_updates`
+fn param_value(param: MidiParam, app: &crate::OsciApp) -> f32 {
+    match param {
+        MidiParam::Frequency => app.audio.config.frequency,
+        MidiParam::TimeScale => app.audio.config.time_scale,
+        MidiParam::Volume => app.audio.config.volume,
+        MidiParam::RotationSpeed => app.rotation_speed,
+        MidiParam::ScaleLfoFreq => app.scale_lfo_freq,
+        MidiParam::ScaleLfoMin => app.scale_lfo_min,
+        MidiParam::ScaleLfoMax => app.scale_lfo_max,
+        MidiParam::LineWidth => app.oscilloscope.settings.line_width,
+        MidiParam::Intensity => app.oscilloscope.settings.intensity,
+        MidiParam::Persistence => app.oscilloscope.settings.persistence,
+        MidiParam::Zoom => app.oscilloscope.settings.zoom_x,
+    }
+}
+
+/// Time constant of the MIDI smoothing ramp (seconds)
+const SMOOTHING_TIME: f32 = 0.05;
+
+/// Smallest frequency change worth re-sampling the shape for (Hz)
+const FREQUENCY_STEP: f32 = 0.5;
+
+/// Smallest master speed change worth re-sampling the shape for
+const TIME_SCALE_STEP: f32 = 0.01;
+
+/// Smallest volume change worth refreshing the output for
+const VOLUME_STEP: f32 = 0.005;
+
+/// One-pole smoother between a MIDI target and a costly parameter
+///
+/// CC values arrive in steps, and each change of e.g. frequency re-samples
+/// the whole shape. The smoother ramps towards the target over a few frames
+/// and only reports a new value once it has moved at least `threshold`
+/// since the last one, or has settled on the target.
+#[derive(Clone, Debug)]
+pub struct ParamSmoother {
+    /// Smallest change to report
+    threshold: f32,
+    /// Current smoothed value
+    value: f32,
+    /// Value being ramped towards
+    target: f32,
+    /// Last value reported by `update`
+    reported: f32,
+    /// Whether a ramp is in progress
+    active: bool,
+}
+
+impl ParamSmoother {
+    /// Create an idle smoother reporting changes of at least `threshold`
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            value: 0.0,
+            target: 0.0,
+            reported: 0.0,
+            active: false,
+        }
+    }
+
+    /// Ramp from `current` (unless already ramping) towards `target`
+    pub fn set_target(&mut self, current: f32, target: f32) {
+        if !self.active {
+            self.value = current;
+            self.reported = current;
+        }
+        self.target = target;
+        self.active = true;
+    }
+
+    /// Advance the ramp by `dt` seconds, returning a value to apply if there is one
+    pub fn update(&mut self, dt: f32) -> Option<f32> {
+        if !self.active {
+            return None;
+        }
+        let alpha = 1.0 - (-dt / SMOOTHING_TIME).exp();
+        self.value += (self.target - self.value) * alpha;
+
+        let settled = (self.target - self.value).abs() < self.threshold * 0.5;
+        if settled {
+            self.value = self.target;
+            self.active = false;
+        }
+        if settled || (self.value - self.reported).abs() >= self.threshold {
+            self.reported = self.value;
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+/// Smoothers for the MIDI parameters that are expensive to change
+#[derive(Clone, Debug)]
+pub struct MidiSmoothing {
+    pub frequency: ParamSmoother,
+    pub time_scale: ParamSmoother,
+    pub volume: ParamSmoother,
+}
+
+impl Default for MidiSmoothing {
+    fn default() -> Self {
+        Self {
+            frequency: ParamSmoother::new(FREQUENCY_STEP),
+            time_scale: ParamSmoother::new(TIME_SCALE_STEP),
+            volume: ParamSmoother::new(VOLUME_STEP),
+        }
+    }
+}
+
+/// Advance the MIDI smoothers and apply whatever they report
+pub fn update_smoothing(app: &mut crate::OsciApp, dt: f32) {
+    if let Some(frequency) = app.midi_smoothing.frequency.update(dt) {
+        app.audio.config.frequency = frequency;
+        app.shape_needs_update = true;
+    }
+    if let Some(time_scale) = app.midi_smoothing.time_scale.update(dt) {
+        app.audio.config.time_scale = time_scale;
+        app.shape_needs_update = true;
+    }
+    if let Some(volume) = app.midi_smoothing.volume.update(dt) {
+        app.audio.config.volume = volume;
+        app.output_needs_update = true;
+    }
+}
+
 /// Apply MIDI parameter updates to the app state.
+///
+/// Frequency, master speed and volume are ramped by `update_smoothing`
+/// rather than set.
 pub fn apply_updates(updates: &[(MidiParam, f32)], app: &mut crate::OsciApp) {
     for &(param, value) in updates {
         match param {
             MidiParam::Frequency => {
-                app.audio.config.frequency = value;
-                app.shape_needs_update = true;
+                let current = app.audio.config.frequency;
+                app.midi_smoothing.frequency.set_target(current, value);
+            }
+            MidiParam::TimeScale => {
+                let current = app.audio.config.time_scale;
+                app.midi_smoothing.time_scale.set_target(current, value);
             }
             MidiParam::Volume => {
-                app.audio.config.volume = value;
-                app.shape_needs_update = true;
+                let current = app.audio.config.volume;
+                app.midi_smoothing.volume.set_target(current, value);
             }
             MidiParam::RotationSpeed => {
                 app.rotation_speed = value;
@@ -361,8 +844,107 @@ pub fn apply_updates(updates: &[(MidiParam, f32)], app: &mut crate::OsciApp) {
                 app.oscilloscope.settings.persistence = value;
             }
             MidiParam::Zoom => {
-                app.oscilloscope.settings.zoom = value;
+                app.oscilloscope.settings.zoom_x = value;
+                app.oscilloscope.settings.zoom_y = value;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoother_ramps_a_step_over_several_frames() {
+        let mut smoother = ParamSmoother::new(VOLUME_STEP);
+        smoother.set_target(0.0, 1.0);
+
+        let mut applied = Vec::new();
+        for _ in 0..60 {
+            if let Some(v) = smoother.update(1.0 / 60.0) {
+                applied.push(v);
+            }
+        }
+
+        assert!(applied.len() > 3, "jumped in {} steps", applied.len());
+        assert!(applied[0] < 0.5);
+        assert!(applied.windows(2).all(|w| w[1] > w[0]));
+        assert_eq!(applied.last(), Some(&1.0));
+
+        // Settled: nothing more to apply
+        assert_eq!(smoother.update(1.0 / 60.0), None);
+    }
+
+    #[test]
+    fn test_changed_parameter_queues_scaled_cc() {
+        let mut feedback = MidiFeedback {
+            ports: Vec::new(),
+            selected_port: 0,
+            connection: None,
+            sent: HashMap::new(),
+            pending: Vec::new(),
+            status: String::new(),
+            is_connected: false,
+        };
+
+        // Volume 0.5 of 0..1, frequency 200 at the top of 20..200
+        let volume = MidiParam::Volume.cc_value(0.5);
+        let frequency = MidiParam::Frequency.cc_value(200.0);
+        assert_eq!((volume, frequency), (64, 127));
+        feedback.queue_changes(&[(7, volume), (21, frequency)]);
+        assert_eq!(feedback.pending, vec![[0xB0, 7, 64], [0xB0, 21, 127]]);
+
+        // Only the value that changed is sent again
+        feedback.pending.clear();
+        let volume = MidiParam::Volume.cc_value(0.25);
+        feedback.queue_changes(&[(7, volume), (21, frequency)]);
+        assert_eq!(feedback.pending, vec![[0xB0, 7, 32]]);
+
+        // Out-of-range values are held to the CC range, and round-trip
+        assert_eq!(MidiParam::Zoom.cc_value(5.0), 127);
+        assert_eq!(MidiParam::Zoom.cc_value(-1.0), 0);
+        let cc = MidiParam::LineWidth.cc_value(MidiParam::LineWidth.map_value(90));
+        assert_eq!(cc, 90);
+    }
+
+    #[test]
+    fn test_beat_after_24_ticks() {
+        let mut beat = BeatCounter::default();
+        for _ in 0..TICKS_PER_BEAT - 1 {
+            assert!(!beat.advance(1));
+        }
+        assert!(beat.advance(1));
+        // The count restarts for the next quarter note
+        assert!(!beat.advance(1));
+    }
+
+    #[test]
+    fn test_recorder_replays_in_order() {
+        let mut recorder = PerformanceRecorder::default();
+        recorder.start_recording(10.0);
+        recorder.record(10.0, &[(MidiParam::Volume, 0.1)]);
+        recorder.record(10.5, &[(MidiParam::Zoom, 0.5)]);
+        recorder.record(11.0, &[(MidiParam::Volume, 0.9)]);
+        recorder.start_playback(12.0); // two second loop
+        assert_eq!(recorder.length(), 2.0);
+
+        // Updates come back at the same offsets from the start of playback
+        assert_eq!(recorder.poll(12.0), vec![(MidiParam::Volume, 0.1)]);
+        assert!(recorder.poll(12.4).is_empty());
+        assert_eq!(recorder.poll(12.6), vec![(MidiParam::Zoom, 0.5)]);
+        assert_eq!(recorder.poll(13.2), vec![(MidiParam::Volume, 0.9)]);
+        assert!(recorder.poll(13.9).is_empty());
+
+        // And loop
+        assert_eq!(recorder.poll(14.1), vec![(MidiParam::Volume, 0.1)]);
+    }
+
+    #[test]
+    fn test_beat_from_batched_ticks() {
+        let mut beat = BeatCounter::default();
+        assert!(!beat.advance(20));
+        assert!(beat.advance(6));
+        assert!(!beat.advance(21));
+    }
+}
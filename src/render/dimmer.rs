@@ -0,0 +1,88 @@
+//! Idle dimming - protects the tube from a figure left standing
+//!
+//! A figure that doesn't change for a long time burns into a real CRT's
+//! phosphor (and into the afterglow here). After a configurable time with
+//! no interaction or moving effect, the display and the Z output slowly
+//! dim, and any change brings them back at once.
+
+/// Seconds for the display to fade from full brightness to `DIM_FLOOR`
+const DIM_FADE: f32 = 20.0;
+
+/// Brightness the display dims down to
+const DIM_FLOOR: f32 = 0.2;
+
+/// Tracks how long the figure has stood still and how far to dim it
+#[derive(Clone, Debug)]
+pub struct IdleDimmer {
+    /// Whether idle dimming is on
+    pub enabled: bool,
+    /// Seconds without a change before dimming starts
+    pub delay: f32,
+    /// Seconds since the last change
+    idle: f32,
+}
+
+impl Default for IdleDimmer {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay: 120.0,
+            idle: 0.0,
+        }
+    }
+}
+
+impl IdleDimmer {
+    /// Advance the idle timer by `dt` seconds, restarting it if something
+    /// changed, and return the brightness factor (0 to 1)
+    pub fn update(&mut self, dt: f32, changed: bool) -> f32 {
+        if changed {
+            self.idle = 0.0;
+        } else {
+            self.idle += dt.max(0.0);
+        }
+        self.brightness()
+    }
+
+    /// Brightness factor for the current idle time (1 = undimmed)
+    pub fn brightness(&self) -> f32 {
+        if !self.enabled || self.idle <= self.delay {
+            return 1.0;
+        }
+        let fade = ((self.idle - self.delay) / DIM_FADE).min(1.0);
+        1.0 - fade * (1.0 - DIM_FLOOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dims_after_delay_and_restores_on_change() {
+        let mut dimmer = IdleDimmer {
+            enabled: true,
+            delay: 10.0,
+            ..Default::default()
+        };
+
+        // Full brightness up to the delay
+        for _ in 0..10 {
+            assert_eq!(dimmer.update(1.0, false), 1.0);
+        }
+
+        // Then a slow fade, down to the floor
+        let first = dimmer.update(1.0, false);
+        let later = dimmer.update(5.0, false);
+        assert!(first < 1.0 && later < first);
+        assert!((dimmer.update(DIM_FADE, false) - DIM_FLOOR).abs() < 1e-6);
+
+        // Any change restores it at once
+        assert_eq!(dimmer.update(1.0, true), 1.0);
+        assert_eq!(dimmer.update(1.0, false), 1.0);
+
+        // Switched off, nothing dims
+        dimmer.enabled = false;
+        assert_eq!(dimmer.update(1000.0, false), 1.0);
+    }
+}
@@ -0,0 +1,54 @@
+//! Idle animation - a demo figure shown while audio is stopped
+//!
+//! Generates samples locally on the UI side, so the display has something
+//! alive to show before the user presses play. Nothing is sent to the
+//! audio output.
+
+use std::f32::consts::TAU;
+
+use crate::audio::XYSample;
+
+/// Peak amplitude of the idle figure
+const IDLE_AMPLITUDE: f32 = 0.6;
+
+/// Phase drift of the figure (turns per second)
+const IDLE_DRIFT: f32 = 0.05;
+
+/// Slow rotation of the whole figure (radians per second)
+const IDLE_SPIN: f32 = 0.2;
+
+/// One trace of a slowly turning 3:2 Lissajous figure at `time` seconds
+pub fn idle_samples(time: f32, count: usize) -> Vec<XYSample> {
+    let phase = time * IDLE_DRIFT * TAU;
+    let (sin, cos) = (time * IDLE_SPIN).sin_cos();
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32 * TAU;
+            let x = IDLE_AMPLITUDE * (3.0 * t + phase).sin();
+            let y = IDLE_AMPLITUDE * (2.0 * t).sin();
+            XYSample::new(x * cos - y * sin, x * sin + y * cos)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_samples_bounded_and_moving() {
+        let a = idle_samples(0.0, 256);
+        let b = idle_samples(2.0, 256);
+        assert_eq!(a.len(), 256);
+
+        for s in a.iter().chain(&b) {
+            assert!(s.x.abs() <= 1.0 && s.y.abs() <= 1.0);
+        }
+
+        let moved = a
+            .iter()
+            .zip(&b)
+            .any(|(p, q)| (p.x - q.x).abs() + (p.y - q.y).abs() > 1e-3);
+        assert!(moved);
+    }
+}
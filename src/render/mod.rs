@@ -2,9 +2,20 @@
 //!
 //! This module provides:
 //! - XY oscilloscope display widget
-//! - Waveform display (future)
+//! - Idle animation shown while audio is stopped
+//! - Idle dimming against burn-in of a static figure
+//! - Trigger search for a stable display phase
+//! - Single-channel waveform display
 
+mod dimmer;
+mod idle;
 mod oscilloscope;
+mod trigger;
+mod waveform;
 
+pub use dimmer::IdleDimmer;
+pub use idle::idle_samples;
 #[allow(unused_imports)]
-pub use oscilloscope::{Oscilloscope, OscilloscopeSettings};
+pub use oscilloscope::{LineJoin, Oscilloscope, OscilloscopeSettings};
+pub use trigger::{TriggerEdge, TriggerSource};
+pub use waveform::{WaveformChannel, WaveformView};
@@ -16,8 +16,9 @@
 //! - X: -1.0 = left edge, +1.0 = right edge
 //! - Y: -1.0 = bottom edge, +1.0 = top edge
 
-use eframe::egui::{self, Color32, Pos2, Rect, Stroke, Vec2};
+use eframe::egui::{self, Color32, ColorImage, Pos2, Rect, Stroke, TextureHandle, Vec2};
 
+use super::trigger::{find_trigger, TriggerEdge, TriggerSource};
 use crate::audio::XYSample;
 
 /// Display settings for the oscilloscope
@@ -35,20 +36,92 @@ pub struct OscilloscopeSettings {
     /// Whether to draw lines between points (vs just points)
     pub draw_lines: bool,
 
+    /// How line segments meet at sharp corners
+    pub line_join: LineJoin,
+
     /// Intensity/brightness (0.0 to 1.0)
     pub intensity: f32,
 
     /// Number of samples to display
     pub sample_count: usize,
 
-    /// Zoom/scale factor (1.0 = full range)
-    pub zoom: f32,
+    /// Horizontal zoom/scale factor (1.0 = full range)
+    pub zoom_x: f32,
+
+    /// Vertical zoom/scale factor (1.0 = full range)
+    pub zoom_y: f32,
+
+    /// Keep `zoom_x` and `zoom_y` equal when either is adjusted in the UI
+    pub zoom_linked: bool,
 
     /// Whether to show graticule (grid lines)
     pub show_graticule: bool,
 
+    /// Graticule divisions across X and Y, e.g. `[10, 8]` like most bench scopes
+    pub graticule_divisions: [usize; 2],
+
     /// Persistence decay factor (0.0 = no persistence, 0.99 = long persistence)
     pub persistence: f32,
+
+    /// Gamma applied to the afterglow's fading brightness (1.0 = linear)
+    ///
+    /// Above 1 the trail drops off quickly from the beam and lingers faintly,
+    /// closer to how a phosphor's decay looks than a linear fade.
+    pub persistence_gamma: f32,
+
+    /// Minimum screen distance between afterglow points (pixels, 0 = keep all)
+    ///
+    /// Slow or dense traces land many samples on nearly the same pixel;
+    /// skipping those keeps the afterglow cheap to draw.
+    pub persistence_spacing: f32,
+
+    /// Show the [-1, 1] coordinate under the mouse cursor
+    pub show_cursor_readout: bool,
+
+    /// Couple beam width to intensity: brighter beams defocus and widen,
+    /// like turning up the intensity knob on an analog scope
+    pub focus_coupling: bool,
+
+    /// Hue rotation speed in full turns per second (0.0 = fixed color)
+    ///
+    /// `color` is the starting hue; the beam cycles around the color wheel
+    /// from there over wall-clock time.
+    pub color_cycle_speed: f32,
+
+    /// Frame blend factor (0.0 = off): each rendered frame is composited
+    /// over the previous one decayed by this amount, reducing flicker at
+    /// low trace rates. Unlike `persistence` this works on the image.
+    pub frame_blend: f32,
+
+    /// Start each frame at a zero crossing so the image holds still
+    pub trigger: bool,
+
+    /// Channel the trigger watches
+    pub trigger_source: TriggerSource,
+
+    /// Crossing direction that fires the trigger
+    pub trigger_edge: TriggerEdge,
+
+    /// Draw arrowheads showing which way the beam travels
+    pub show_direction: bool,
+
+    /// Samples between direction arrowheads and velocity vectors
+    pub direction_spacing: usize,
+
+    /// Overlay the beam's velocity as vectors scaled by speed
+    pub show_velocity_field: bool,
+
+    /// Number each subpath of an imported figure at its start point
+    pub show_draw_order: bool,
+
+    /// Mark where the beam is now with a larger, brighter spot
+    pub show_beam_spot: bool,
+
+    /// Brightness of the beam spot relative to the trace
+    ///
+    /// Above 1 the spot stands out from the trace; past full intensity it
+    /// whitens instead.
+    pub beam_spot_brightness: f32,
 }
 
 impl Default for OscilloscopeSettings {
@@ -58,15 +131,93 @@ impl Default for OscilloscopeSettings {
             background: Color32::from_rgb(10, 20, 10),
             line_width: 1.5,
             draw_lines: true,
+            line_join: LineJoin::Round,
             intensity: 1.0,
             sample_count: 2048,
-            zoom: 1.0,
+            zoom_x: 1.0,
+            zoom_y: 1.0,
+            zoom_linked: true,
             show_graticule: true,
+            graticule_divisions: [10, 10],
             persistence: 0.85,
+            persistence_gamma: 2.2,
+            persistence_spacing: 1.0,
+            show_cursor_readout: false,
+            focus_coupling: false,
+            color_cycle_speed: 0.0,
+            frame_blend: 0.0,
+            trigger: false,
+            trigger_source: TriggerSource::X,
+            trigger_edge: TriggerEdge::Rising,
+            show_direction: false,
+            direction_spacing: 32,
+            show_velocity_field: false,
+            show_draw_order: false,
+            show_beam_spot: false,
+            beam_spot_brightness: 2.0,
         }
     }
 }
 
+/// How drawn line segments meet at a sharp corner
+///
+/// Separate segments leave a notch on the outside of each corner; a join
+/// fills it in. The image export stamps round beam spots along each line,
+/// so its corners are always round.
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum LineJoin {
+    /// Leave segments as they are
+    None,
+    /// Fill the corner with a disc
+    #[default]
+    Round,
+    /// Cut the corner off with a straight edge
+    Bevel,
+}
+
+impl LineJoin {
+    /// Get all joins
+    pub fn all() -> &'static [LineJoin] {
+        &[LineJoin::None, LineJoin::Round, LineJoin::Bevel]
+    }
+
+    /// Get the name of this join
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineJoin::None => "None",
+            LineJoin::Round => "Round",
+            LineJoin::Bevel => "Bevel",
+        }
+    }
+}
+
+/// Smallest turn (radians) that gets a line join
+const JOIN_ANGLE: f32 = 0.3;
+
+/// How strongly intensity widens the beam when focus coupling is on
+const FOCUS_SPREAD: f32 = 1.0;
+
+/// How far a full flash pushes the beam color towards white
+const FLASH_WHITEN: f32 = 0.5;
+
+/// Brightness of the afterglow relative to the live beam
+const PERSISTENCE_BRIGHTNESS: f32 = 0.3;
+
+/// Narrowest stroke drawn; thinner beams are drawn this wide but fainter
+const MIN_BEAM_WIDTH: f32 = 1.0;
+
+/// Beam spot diameter relative to the line width
+const BEAM_SPOT_SIZE: f32 = 2.5;
+
+/// Length of each side of a direction arrowhead, in pixels
+const ARROW_SIZE: f32 = 6.0;
+
+/// Length of the fastest velocity vector in a frame, in pixels
+const VELOCITY_MAX_LENGTH: f32 = 24.0;
+
+/// Radius of the ring marking a subpath's start, in pixels
+const ORDER_MARKER_RADIUS: f32 = 3.0;
+
 /// XY Oscilloscope widget
 ///
 /// Renders audio samples as 2D graphics in the style of an analog oscilloscope.
@@ -77,6 +228,24 @@ pub struct Oscilloscope {
     /// Previous frame's points for persistence effect
     /// This creates the "afterglow" seen on CRT oscilloscopes
     persistence_buffer: Vec<(Pos2, f32)>, // (position, alpha)
+
+    /// Blended beam brightness per pixel (0.0 to 1.0) for frame blending
+    frame_buffer: Vec<f32>,
+    /// Size of `frame_buffer` in pixels
+    frame_size: [usize; 2],
+    /// GPU texture for the blended frame
+    frame_texture: Option<TextureHandle>,
+
+    /// Wall-clock time in seconds, for time-based effects like color cycling
+    time: f32,
+    /// Momentary brightness boost (0.0 = none), e.g. a beat flash
+    flash: f32,
+    /// Overall brightness factor (1.0 = undimmed), e.g. idle dimming
+    dimming: f32,
+    /// Draw order labels for the current figure's subpaths
+    draw_order: Vec<(usize, XYSample)>,
+    /// Linear output stage the labels are mapped through to meet the trace
+    output_transform: [[f32; 2]; 2],
 }
 
 impl Default for Oscilloscope {
@@ -88,10 +257,7 @@ impl Default for Oscilloscope {
 impl Oscilloscope {
     /// Create a new oscilloscope with default settings
     pub fn new() -> Self {
-        Self {
-            settings: OscilloscopeSettings::default(),
-            persistence_buffer: Vec::with_capacity(8192),
-        }
+        Self::with_settings(OscilloscopeSettings::default())
     }
 
     /// Create a new oscilloscope with custom settings
@@ -99,6 +265,14 @@ impl Oscilloscope {
         Self {
             settings,
             persistence_buffer: Vec::with_capacity(8192),
+            frame_buffer: Vec::new(),
+            frame_size: [0, 0],
+            frame_texture: None,
+            time: 0.0,
+            flash: 0.0,
+            dimming: 1.0,
+            draw_order: Vec::new(),
+            output_transform: [[1.0, 0.0], [0.0, 1.0]],
         }
     }
 
@@ -111,20 +285,98 @@ impl Oscilloscope {
     /// # Returns
     /// Screen position in pixels
     fn sample_to_screen(&self, sample: XYSample, rect: Rect) -> Pos2 {
-        let zoom = self.settings.zoom;
+        let (zoom_x, zoom_y) = (self.settings.zoom_x, self.settings.zoom_y);
 
         // Map from [-1, 1] to [0, 1], applying zoom
-        let norm_x = (sample.x / zoom + 1.0) / 2.0;
-        let norm_y = (sample.y / zoom + 1.0) / 2.0;
+        let norm_x = (sample.x / zoom_x + 1.0) / 2.0;
+        let norm_y = (sample.y / zoom_y + 1.0) / 2.0;
 
         // Map to screen coordinates
-        // Note: Y is inverted (screen Y increases downward)
+        // Note: Y is inverted (screen Y increases downward). This is the
+        // only flip on the display side; samples arrive with the engine's
+        // `invert_y` polarity already applied.
         Pos2::new(
             rect.left() + norm_x * rect.width(),
             rect.bottom() - norm_y * rect.height(), // Flip Y
         )
     }
 
+    /// Set the wall-clock time used by animated display effects
+    pub fn set_time(&mut self, seconds: f32) {
+        self.time = seconds;
+    }
+
+    /// Set a momentary brightness boost on top of the intensity setting
+    ///
+    /// The boost raises intensity and, since that saturates at full
+    /// brightness, also pushes the beam color towards white.
+    pub fn set_flash(&mut self, amount: f32) {
+        self.flash = amount.max(0.0);
+    }
+
+    /// Set the subpaths the draw order overlay numbers, in trace order
+    ///
+    /// Points are in figure coordinates; they are drawn through the
+    /// transform from `set_output_transform` so they land on the trace.
+    pub fn set_draw_order(&mut self, subpaths: &[&[(f32, f32)]]) {
+        self.draw_order = draw_order_labels(subpaths);
+    }
+
+    /// Set the linear output stage (volume, fit, rotation, width, polarity)
+    /// that maps figure coordinates to what the beam draws
+    pub fn set_output_transform(&mut self, matrix: [[f32; 2]; 2]) {
+        self.output_transform = matrix;
+    }
+
+    /// Scale the overall brightness, after intensity and flash
+    pub fn set_dimming(&mut self, factor: f32) {
+        self.dimming = factor.clamp(0.0, 1.0);
+    }
+
+    /// Intensity including any flash boost and dimming
+    fn effective_intensity(&self) -> f32 {
+        (self.settings.intensity + self.flash).min(1.0) * self.dimming
+    }
+
+    /// Beam color after color cycling and flash
+    fn beam_color(&self) -> Color32 {
+        let color = if self.settings.color_cycle_speed == 0.0 {
+            self.settings.color
+        } else {
+            rotate_hue(
+                self.settings.color,
+                self.settings.color_cycle_speed * self.time,
+            )
+        };
+        if self.flash > 0.0 {
+            color.lerp_to_gamma(Color32::WHITE, (self.flash * FLASH_WHITEN).min(1.0))
+        } else {
+            color
+        }
+    }
+
+    /// Convert a screen position back to sample coordinates
+    ///
+    /// The inverse of `sample_to_screen`, including zoom.
+    fn screen_to_sample(&self, pos: Pos2, rect: Rect) -> (f32, f32) {
+        let norm_x = (pos.x - rect.left()) / rect.width();
+        let norm_y = (rect.bottom() - pos.y) / rect.height(); // Flip Y back
+        (
+            (norm_x * 2.0 - 1.0) * self.settings.zoom_x,
+            (norm_y * 2.0 - 1.0) * self.settings.zoom_y,
+        )
+    }
+
+    /// Line width after applying intensity/focus coupling
+    fn effective_line_width(&self) -> f32 {
+        if self.settings.focus_coupling {
+            // Unchanged at half intensity, up to 1.5x wider at full
+            self.settings.line_width * (1.0 + FOCUS_SPREAD * (self.effective_intensity() - 0.5))
+        } else {
+            self.settings.line_width
+        }
+    }
+
     /// Draw the oscilloscope display
     ///
     /// # Arguments
@@ -147,6 +399,18 @@ impl Oscilloscope {
             Vec2::new(side, side)
         });
 
+        // Draw from the trigger point, or free-run if it never fires
+        let samples = if self.settings.trigger {
+            let start = find_trigger(
+                samples,
+                self.settings.trigger_source,
+                self.settings.trigger_edge,
+            );
+            &samples[start.unwrap_or(0)..]
+        } else {
+            samples
+        };
+
         // Allocate space for the widget
         let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
         let rect = response.rect;
@@ -154,6 +418,15 @@ impl Oscilloscope {
         // Draw background
         painter.rect_filled(rect, 4.0, self.settings.background);
 
+        // With frame blending the beam is rasterized (over the background)
+        // into a texture instead of drawn as vector lines
+        let blended = self.settings.frame_blend > 0.0;
+        if blended {
+            self.draw_blended_frame(ui.ctx(), &painter, rect, samples);
+        } else {
+            self.frame_size = [0, 0];
+        }
+
         // Draw graticule (grid)
         if self.settings.show_graticule {
             self.draw_graticule(&painter, rect);
@@ -166,7 +439,37 @@ impl Oscilloscope {
         self.draw_persistence(&painter, rect);
 
         // Draw current samples
-        self.draw_samples(&painter, rect, samples);
+        if !blended {
+            self.draw_samples(&painter, rect, samples);
+            if self.settings.show_beam_spot {
+                self.draw_beam_spot(&painter, rect, samples);
+            }
+        }
+
+        // Trace-order arrowheads go on top, blended or not
+        if self.settings.show_direction {
+            self.draw_direction(&painter, rect, samples);
+        }
+        if self.settings.show_velocity_field {
+            self.draw_velocity_field(&painter, rect, samples);
+        }
+        if self.settings.show_draw_order {
+            self.draw_subpath_order(&painter, rect);
+        }
+
+        // Coordinate readout under the cursor
+        if self.settings.show_cursor_readout {
+            if let Some(pos) = response.hover_pos() {
+                let (x, y) = self.screen_to_sample(pos, rect);
+                painter.text(
+                    rect.left_top() + Vec2::new(6.0, 6.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("x {:+.3}  y {:+.3}", x, y),
+                    egui::FontId::monospace(12.0),
+                    self.beam_color(),
+                );
+            }
+        }
 
         response
     }
@@ -179,24 +482,9 @@ impl Oscilloscope {
         let stroke_grid = Stroke::new(0.5, grid_color);
         let stroke_axis = Stroke::new(1.0, axis_color);
 
-        // Draw grid lines (10 divisions)
-        for i in 0..=10 {
-            let t = i as f32 / 10.0;
-
-            // Vertical lines
-            let x = rect.left() + t * rect.width();
-            let stroke = if i == 5 { stroke_axis } else { stroke_grid };
-            painter.line_segment(
-                [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
-                stroke,
-            );
-
-            // Horizontal lines
-            let y = rect.top() + t * rect.height();
-            painter.line_segment(
-                [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
-                stroke,
-            );
+        for (points, is_axis) in graticule_lines(rect, self.settings.graticule_divisions) {
+            let stroke = if is_axis { stroke_axis } else { stroke_grid };
+            painter.line_segment(points, stroke);
         }
     }
 
@@ -210,13 +498,22 @@ impl Oscilloscope {
             *alpha > 0.01 // Remove very faded points
         });
 
-        // Add new points
+        // Add new points (blanked samples leave no trace), skipping any
+        // too close to the last one added
+        let spacing = self.settings.persistence_spacing;
+        let mut last: Option<Pos2> = None;
         for sample in samples.iter().take(self.settings.sample_count) {
+            if sample.is_blanked() {
+                continue;
+            }
             let pos = self.sample_to_screen(*sample, rect);
             // Only add if within bounds
-            if rect.contains(pos) {
-                self.persistence_buffer.push((pos, self.settings.intensity));
+            if !rect.contains(pos) || last.is_some_and(|last| last.distance(pos) < spacing) {
+                continue;
             }
+            self.persistence_buffer
+                .push((pos, self.effective_intensity()));
+            last = Some(pos);
         }
 
         // Limit buffer size to prevent memory growth
@@ -229,7 +526,8 @@ impl Oscilloscope {
 
     /// Draw the persistence effect (afterglow)
     fn draw_persistence(&self, painter: &egui::Painter, rect: Rect) {
-        let base_color = self.settings.color;
+        let base_color = self.beam_color();
+        let line_width = self.effective_line_width();
 
         for (pos, alpha) in &self.persistence_buffer {
             if !rect.contains(*pos) {
@@ -241,11 +539,11 @@ impl Oscilloscope {
                 base_color.r(),
                 base_color.g(),
                 base_color.b(),
-                (alpha * 255.0 * 0.3) as u8, // Persistence is dimmer
+                afterglow_alpha(*alpha, self.settings.persistence_gamma),
             );
 
             // Draw as small circles for a softer look
-            painter.circle_filled(*pos, self.settings.line_width * 0.5, color);
+            painter.circle_filled(*pos, line_width * 0.5, color);
         }
     }
 
@@ -255,45 +553,320 @@ impl Oscilloscope {
             return;
         }
 
+        let beam = self.beam_color();
         let color = Color32::from_rgba_unmultiplied(
-            self.settings.color.r(),
-            self.settings.color.g(),
-            self.settings.color.b(),
-            (self.settings.intensity * 255.0) as u8,
+            beam.r(),
+            beam.g(),
+            beam.b(),
+            (self.effective_intensity() * 255.0) as u8,
         );
 
-        let stroke = Stroke::new(self.settings.line_width, color);
+        let line_width = self.effective_line_width();
+        let stroke = beam_stroke(line_width, color);
 
-        // Convert samples to screen coordinates
-        let points: Vec<Pos2> = samples
+        // Convert samples to screen coordinates, keeping the beam state
+        let points: Vec<(Pos2, bool)> = samples
             .iter()
             .take(self.settings.sample_count)
-            .map(|s| self.sample_to_screen(*s, rect))
+            .map(|s| (self.sample_to_screen(*s, rect), !s.is_blanked()))
             .collect();
 
         if self.settings.draw_lines && points.len() >= 2 {
-            // Draw connected line segments
-            for window in points.windows(2) {
-                let p1 = window[0];
-                let p2 = window[1];
-
-                // Only draw if both points are reasonably close
-                // (avoid drawing long lines across the screen for discontinuities)
-                let dist_sq = (p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2);
-                let max_dist_sq = (rect.width() * 0.5).powi(2);
+            // Draw connected line segments. Pen-up moves aren't drawn, nor
+            // are long jumps across the screen at discontinuities
+            let max_dist_sq = (rect.width() * 0.5).powi(2);
+            let drawn: Vec<bool> = points
+                .windows(2)
+                .map(|w| w[1].1 && w[0].0.distance_sq(w[1].0) < max_dist_sq)
+                .collect();
+            for (window, &drawn) in points.windows(2).zip(&drawn) {
+                if drawn {
+                    painter.line_segment([window[0].0, window[1].0], stroke);
+                }
+            }
 
-                if dist_sq < max_dist_sq {
-                    painter.line_segment([p1, p2], stroke);
+            // Fill in the corners where two drawn segments meet
+            for (i, pair) in drawn.windows(2).enumerate() {
+                if !(pair[0] && pair[1]) {
+                    continue;
+                }
+                let [prev, vertex, next] = [points[i].0, points[i + 1].0, points[i + 2].0];
+                if let Some(shape) = join_shape(
+                    self.settings.line_join,
+                    [prev, vertex, next],
+                    stroke.width,
+                    stroke.color,
+                ) {
+                    painter.add(shape);
                 }
             }
         } else {
             // Draw as points
-            for pos in points {
-                if rect.contains(pos) {
-                    painter.circle_filled(pos, self.settings.line_width, color);
+            for (pos, lit) in points {
+                if lit && rect.contains(pos) {
+                    painter.circle_filled(pos, line_width, color);
+                }
+            }
+        }
+    }
+
+    /// Where the beam is now: the newest lit sample on screen
+    ///
+    /// Samples arrive oldest first, so the trace ends at the beam.
+    fn beam_position(&self, samples: &[XYSample]) -> Option<XYSample> {
+        samples[..samples.len().min(self.settings.sample_count)]
+            .iter()
+            .rev()
+            .find(|s| !s.is_blanked())
+            .copied()
+    }
+
+    /// Draw the beam spot over the trace
+    fn draw_beam_spot(&self, painter: &egui::Painter, rect: Rect, samples: &[XYSample]) {
+        let Some(sample) = self.beam_position(samples) else {
+            return;
+        };
+        let pos = self.sample_to_screen(sample, rect);
+        if !rect.contains(pos) {
+            return;
+        }
+        let level = self.effective_intensity() * self.settings.beam_spot_brightness;
+        let radius = self.effective_line_width() * BEAM_SPOT_SIZE * 0.5;
+        painter.circle_filled(pos, radius, spot_color(self.beam_color(), level));
+    }
+
+    /// Draw arrowheads every `direction_spacing` samples, pointing along the path
+    fn draw_direction(&self, painter: &egui::Painter, rect: Rect, samples: &[XYSample]) {
+        let stroke = Stroke::new(1.5, self.beam_color().gamma_multiply(0.8));
+        let markers = direction_markers(
+            &samples[..samples.len().min(self.settings.sample_count)],
+            self.settings.direction_spacing,
+        );
+        for (sample, (dx, dy)) in markers {
+            let tip = self.sample_to_screen(sample, rect);
+            // Screen Y points down
+            let back = Vec2::new(-dx, dy) * ARROW_SIZE;
+            let side = back.rot90() * 0.5;
+            painter.line_segment([tip, tip + back + side], stroke);
+            painter.line_segment([tip, tip + back - side], stroke);
+        }
+    }
+
+    /// Draw velocity vectors along the trace, the fastest one `VELOCITY_MAX_LENGTH` long
+    fn draw_velocity_field(&self, painter: &egui::Painter, rect: Rect, samples: &[XYSample]) {
+        let stroke = Stroke::new(1.0, self.beam_color().gamma_multiply(0.6));
+        let vectors = velocity_field(
+            &samples[..samples.len().min(self.settings.sample_count)],
+            self.settings.direction_spacing,
+        );
+        let max_speed = vectors
+            .iter()
+            .map(|(_, (vx, vy))| vx.hypot(*vy))
+            .fold(0.0f32, f32::max);
+        if max_speed <= 0.0 {
+            return;
+        }
+
+        for (sample, (vx, vy)) in vectors {
+            let base = self.sample_to_screen(sample, rect);
+            // Screen Y points down
+            let arrow = Vec2::new(vx, -vy) / max_speed * VELOCITY_MAX_LENGTH;
+            let tip = base + arrow;
+            painter.line_segment([base, tip], stroke);
+
+            let head = -arrow.normalized() * ARROW_SIZE * 0.5;
+            let side = head.rot90() * 0.5;
+            painter.line_segment([tip, tip + head + side], stroke);
+            painter.line_segment([tip, tip + head - side], stroke);
+        }
+    }
+
+    /// Mark each subpath's start point with its place in the draw order
+    fn draw_subpath_order(&self, painter: &egui::Painter, rect: Rect) {
+        let color = self.beam_color();
+        let [[a, b], [c, d]] = self.output_transform;
+        for &(index, start) in &self.draw_order {
+            let mapped = XYSample::new(a * start.x + b * start.y, c * start.x + d * start.y);
+            let pos = self.sample_to_screen(mapped, rect);
+            if !rect.contains(pos) {
+                continue;
+            }
+            painter.circle_stroke(pos, ORDER_MARKER_RADIUS, Stroke::new(1.0, color));
+            painter.text(
+                pos + Vec2::new(ORDER_MARKER_RADIUS, -ORDER_MARKER_RADIUS),
+                egui::Align2::LEFT_BOTTOM,
+                index.to_string(),
+                egui::FontId::monospace(11.0),
+                color,
+            );
+        }
+    }
+
+    /// Draw a small clickable preview of a point list
+    ///
+    /// Uses the display colors but none of the persistence state, so any
+    /// number of previews can share one oscilloscope.
+    pub fn show_preview(
+        &self,
+        ui: &mut egui::Ui,
+        points: &[(f32, f32)],
+        size: Vec2,
+    ) -> egui::Response {
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 2.0, self.settings.background);
+        if response.hovered() {
+            painter.rect_stroke(rect, 2.0, Stroke::new(1.0, self.beam_color()));
+        }
+
+        let screen: Vec<Pos2> = points
+            .iter()
+            .map(|&(x, y)| self.sample_to_screen(XYSample::new(x, y), rect.shrink(2.0)))
+            .collect();
+        painter.add(egui::Shape::line(
+            screen,
+            Stroke::new(1.0, self.beam_color()),
+        ));
+
+        response
+    }
+
+    /// Render the samples with frame blending and paint the result
+    fn draw_blended_frame(
+        &mut self,
+        ctx: &egui::Context,
+        painter: &egui::Painter,
+        rect: Rect,
+        samples: &[XYSample],
+    ) {
+        let size = [
+            rect.width().round().max(1.0) as usize,
+            rect.height().round().max(1.0) as usize,
+        ];
+        let image = self.render_to_image(samples, size, 1);
+
+        let options = egui::TextureOptions::LINEAR;
+        match self.frame_texture {
+            Some(ref mut texture) => texture.set(image, options),
+            None => {
+                self.frame_texture = Some(ctx.load_texture("oscilloscope_frame", image, options))
+            }
+        }
+        if let Some(ref texture) = self.frame_texture {
+            let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+            painter.image(texture.id(), rect, uv, Color32::WHITE);
+        }
+    }
+
+    /// Rasterize the samples into an image on the CPU
+    ///
+    /// Each call renders one frame. With `frame_blend` > 0 the new frame is
+    /// alpha-composited over the previous one, decayed by `frame_blend`.
+    ///
+    /// `supersample` renders at that many times the resolution on each axis
+    /// and averages down, for smoother edges in exported images (1 = off).
+    pub fn render_to_image(
+        &mut self,
+        samples: &[XYSample],
+        size: [usize; 2],
+        supersample: usize,
+    ) -> ColorImage {
+        let factor = supersample.max(1);
+        let frame = if factor == 1 {
+            self.rasterize(samples, size, 1.0)
+        } else {
+            let large = [size[0] * factor, size[1] * factor];
+            downsample(
+                &self.rasterize(samples, large, factor as f32),
+                large,
+                factor,
+            )
+        };
+
+        let blend = self.settings.frame_blend.clamp(0.0, 0.99);
+        if blend > 0.0 && self.frame_size == size {
+            for (acc, &new) in self.frame_buffer.iter_mut().zip(&frame) {
+                // The beam spot can be brighter than 1
+                *acc = new + *acc * blend * (1.0 - new).max(0.0);
+            }
+        } else {
+            self.frame_buffer = frame;
+            self.frame_size = size;
+        }
+
+        let background = self.settings.background;
+        let color = self.beam_color();
+        let intensity = self.effective_intensity();
+        let mix = |from: u8, to: u8, t: f32| (from as f32 + (to as f32 - from as f32) * t) as u8;
+        let pixels = self
+            .frame_buffer
+            .iter()
+            .map(|&v| {
+                let t = (v * intensity).clamp(0.0, 1.0);
+                Color32::from_rgb(
+                    mix(background.r(), color.r(), t),
+                    mix(background.g(), color.g(), t),
+                    mix(background.b(), color.b(), t),
+                )
+            })
+            .collect();
+
+        ColorImage { size, pixels }
+    }
+
+    /// Rasterize one frame of beam brightness (0.0 to 1.0 per pixel, more
+    /// at the beam spot)
+    ///
+    /// `pixel_scale` widens the beam to match a supersampled `size`.
+    fn rasterize(&self, samples: &[XYSample], size: [usize; 2], pixel_scale: f32) -> Vec<f32> {
+        let [width, height] = size;
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(width as f32, height as f32));
+        let mut pixels = vec![0.0f32; width * height];
+        let line_width = self.effective_line_width() * pixel_scale;
+        let radius = (line_width * 0.5).max(0.5);
+        // Sub-pixel beams (in output pixels) light a pixel-wide line partly
+        let coverage = self.effective_line_width().clamp(0.0, MIN_BEAM_WIDTH);
+
+        let points: Vec<(Pos2, bool)> = samples
+            .iter()
+            .take(self.settings.sample_count)
+            .map(|s| (self.sample_to_screen(*s, rect), !s.is_blanked()))
+            .collect();
+
+        if self.settings.draw_lines && points.len() >= 2 {
+            // Same rules as `draw_samples`: skip pen-up moves and long jumps
+            let max_dist = rect.width() * 0.5;
+            for window in points.windows(2) {
+                let (p1, _) = window[0];
+                let (p2, lit) = window[1];
+                let dist = p1.distance(p2);
+                if !lit || dist >= max_dist {
+                    continue;
+                }
+                let steps = (dist * 2.0).ceil().max(1.0) as usize;
+                for i in 0..=steps {
+                    let pos = p1.lerp(p2, i as f32 / steps as f32);
+                    splat(&mut pixels, size, pos, radius, coverage);
+                }
+            }
+        } else {
+            for (pos, lit) in points {
+                if lit {
+                    splat(&mut pixels, size, pos, line_width, coverage);
                 }
             }
         }
+
+        if self.settings.show_beam_spot {
+            if let Some(sample) = self.beam_position(samples) {
+                let pos = self.sample_to_screen(sample, rect);
+                let strength = coverage * self.settings.beam_spot_brightness;
+                splat(&mut pixels, size, pos, radius * BEAM_SPOT_SIZE, strength);
+            }
+        }
+
+        pixels
     }
 
     /// Clear the persistence buffer
@@ -301,3 +874,546 @@ impl Oscilloscope {
         self.persistence_buffer.clear();
     }
 }
+
+/// Graticule line segments for `divisions` ([x, y]) and whether each is a center axis
+///
+/// With an odd count the center falls inside a division, so the axis gets
+/// a line of its own rather than highlighting the nearest grid line.
+fn graticule_lines(rect: Rect, divisions: [usize; 2]) -> Vec<([Pos2; 2], bool)> {
+    let [columns, rows] = divisions.map(|n| n.max(1));
+    let mut lines = Vec::with_capacity(columns + rows + 4);
+
+    // Vertical lines
+    for i in 0..=columns {
+        if 2 * i == columns {
+            continue;
+        }
+        let x = rect.left() + i as f32 / columns as f32 * rect.width();
+        lines.push((
+            [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+            false,
+        ));
+    }
+
+    // Horizontal lines
+    for i in 0..=rows {
+        if 2 * i == rows {
+            continue;
+        }
+        let y = rect.top() + i as f32 / rows as f32 * rect.height();
+        lines.push((
+            [Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)],
+            false,
+        ));
+    }
+
+    let center = rect.center();
+    lines.push((
+        [
+            Pos2::new(center.x, rect.top()),
+            Pos2::new(center.x, rect.bottom()),
+        ],
+        true,
+    ));
+    lines.push((
+        [
+            Pos2::new(rect.left(), center.y),
+            Pos2::new(rect.right(), center.y),
+        ],
+        true,
+    ));
+    lines
+}
+
+/// Positions and unit travel directions (sample space) for direction markers
+///
+/// One marker every `spacing` samples, pointing from the previous sample.
+/// Markers on pen-up moves or where the beam is standing still are skipped.
+fn direction_markers(samples: &[XYSample], spacing: usize) -> Vec<(XYSample, (f32, f32))> {
+    let spacing = spacing.max(1);
+    (spacing..samples.len())
+        .step_by(spacing)
+        .filter_map(|i| {
+            let (prev, cur) = (samples[i - 1], samples[i]);
+            let (dx, dy) = (cur.x - prev.x, cur.y - prev.y);
+            let len = (dx * dx + dy * dy).sqrt();
+            (!cur.is_blanked() && len > 0.0).then_some((cur, (dx / len, dy / len)))
+        })
+        .collect()
+}
+
+/// Draw index (from 1) and start point of each subpath, in trace order
+///
+/// Subpaths with no points are skipped without using up a number.
+fn draw_order_labels(subpaths: &[&[(f32, f32)]]) -> Vec<(usize, XYSample)> {
+    subpaths
+        .iter()
+        .filter_map(|points| points.first())
+        .enumerate()
+        .map(|(i, &(x, y))| (i + 1, XYSample::new(x, y)))
+        .collect()
+}
+
+/// Positions and velocities (sample units per sample) every `spacing` samples
+///
+/// Velocity is the step from the previous sample. Pen-up moves are skipped.
+fn velocity_field(samples: &[XYSample], spacing: usize) -> Vec<(XYSample, (f32, f32))> {
+    let spacing = spacing.max(1);
+    (spacing..samples.len())
+        .step_by(spacing)
+        .filter_map(|i| {
+            let (prev, cur) = (samples[i - 1], samples[i]);
+            (!cur.is_blanked()).then_some((cur, (cur.x - prev.x, cur.y - prev.y)))
+        })
+        .collect()
+}
+
+/// Average `factor` x `factor` blocks of a brightness buffer
+fn downsample(pixels: &[f32], size: [usize; 2], factor: usize) -> Vec<f32> {
+    let [width, height] = size;
+    let (out_w, out_h) = (width / factor, height / factor);
+    let norm = 1.0 / (factor * factor) as f32;
+    let mut out = vec![0.0f32; out_w * out_h];
+    for (y, row) in out.chunks_mut(out_w).enumerate() {
+        for (x, value) in row.iter_mut().enumerate() {
+            let mut sum = 0.0;
+            for sy in 0..factor {
+                let start = (y * factor + sy) * width + x * factor;
+                sum += pixels[start..start + factor].iter().sum::<f32>();
+            }
+            *value = sum * norm;
+        }
+    }
+    out
+}
+
+/// Rotate a color's hue by a number of full turns around the color wheel
+fn rotate_hue(color: Color32, turns: f32) -> Color32 {
+    let mut hsva = egui::ecolor::Hsva::from(color);
+    hsva.h = (hsva.h + turns).rem_euclid(1.0);
+    hsva.into()
+}
+
+/// Stroke for a beam `width` pixels wide
+///
+/// egui breaks lines thinner than a pixel up into dots, so those are drawn
+/// `MIN_BEAM_WIDTH` wide with alpha scaled by the width they should cover.
+fn beam_stroke(width: f32, color: Color32) -> Stroke {
+    if width < MIN_BEAM_WIDTH {
+        Stroke::new(
+            MIN_BEAM_WIDTH,
+            color.gamma_multiply(width.max(0.0) / MIN_BEAM_WIDTH),
+        )
+    } else {
+        Stroke::new(width, color)
+    }
+}
+
+/// Shape filling the outside of the corner at `corner[1]`, if it turns
+/// sharply enough to need one
+fn join_shape(
+    join: LineJoin,
+    corner: [Pos2; 3],
+    width: f32,
+    color: Color32,
+) -> Option<egui::Shape> {
+    let [prev, vertex, next] = corner;
+    let (incoming, outgoing) = ((vertex - prev).normalized(), (next - vertex).normalized());
+    if !incoming.is_finite() || !outgoing.is_finite() || incoming == Vec2::ZERO {
+        return None;
+    }
+    let turn = incoming.dot(outgoing).clamp(-1.0, 1.0).acos();
+    if turn < JOIN_ANGLE {
+        return None;
+    }
+
+    let half = width * 0.5;
+    match join {
+        LineJoin::None => None,
+        LineJoin::Round => Some(egui::Shape::circle_filled(vertex, half, color)),
+        LineJoin::Bevel => {
+            // The notch is on the side the path turns away from
+            let side = if incoming.x * outgoing.y - incoming.y * outgoing.x > 0.0 {
+                1.0
+            } else {
+                -1.0
+            };
+            Some(egui::Shape::convex_polygon(
+                vec![
+                    vertex,
+                    vertex + incoming.rot90() * half * side,
+                    vertex + outgoing.rot90() * half * side,
+                ],
+                color,
+                Stroke::NONE,
+            ))
+        }
+    }
+}
+
+/// Beam spot color at a brightness `level`, where 1 is a full-intensity
+/// trace
+///
+/// Alpha saturates at 1, so brighter levels push the color towards white.
+fn spot_color(beam: Color32, level: f32) -> Color32 {
+    let color = if level > 1.0 {
+        beam.lerp_to_gamma(Color32::WHITE, ((level - 1.0) * FLASH_WHITEN).min(1.0))
+    } else {
+        beam
+    };
+    Color32::from_rgba_unmultiplied(
+        color.r(),
+        color.g(),
+        color.b(),
+        (level.clamp(0.0, 1.0) * 255.0) as u8,
+    )
+}
+
+/// Alpha of an afterglow point that has faded to `alpha` (0..1)
+fn afterglow_alpha(alpha: f32, gamma: f32) -> u8 {
+    (alpha.clamp(0.0, 1.0).powf(gamma) * 255.0 * PERSISTENCE_BRIGHTNESS) as u8
+}
+
+/// Stamp an anti-aliased disc of peak brightness `strength` into a
+/// brightness buffer (max-combined)
+fn splat(pixels: &mut [f32], size: [usize; 2], center: Pos2, radius: f32, strength: f32) {
+    let [width, height] = size;
+    let reach = radius + 0.5;
+    let x0 = (center.x - reach).floor().max(0.0) as usize;
+    let y0 = (center.y - reach).floor().max(0.0) as usize;
+    let x1 = ((center.x + reach).ceil().max(0.0) as usize).min(width);
+    let y1 = ((center.y + reach).ceil().max(0.0) as usize).min(height);
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let d = Pos2::new(x as f32 + 0.5, y as f32 + 0.5).distance(center);
+            let coverage = (reach - d).clamp(0.0, 1.0) * strength;
+            let pixel = &mut pixels[y * width + x];
+            *pixel = pixel.max(coverage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A horizontal line across the middle of the screen
+    fn line_samples() -> Vec<XYSample> {
+        (0..=20)
+            .map(|i| XYSample::new(-0.5 + i as f32 * 0.05, 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_direction_markers_follow_line() {
+        let markers = direction_markers(&line_samples(), 5);
+
+        // Samples 5, 10, 15 and 20 of 21, all pointing along +X
+        assert_eq!(markers.len(), 4);
+        for (k, (sample, (dx, dy))) in markers.iter().enumerate() {
+            let expected_x = -0.5 + (k + 1) as f32 * 5.0 * 0.05;
+            assert!((sample.x - expected_x).abs() < 1e-5);
+            assert!((dx - 1.0).abs() < 1e-5 && dy.abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_draw_order_labels_mark_subpath_starts() {
+        let a = [(-0.5, 0.5), (0.0, 0.5)];
+        let b = [(0.25, -0.25), (0.5, 0.0), (0.75, 0.25)];
+        let labels = draw_order_labels(&[&a, &[], &b]);
+
+        // The empty subpath is skipped and the numbering stays dense
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].0, 1);
+        assert_eq!((labels[0].1.x, labels[0].1.y), a[0]);
+        assert_eq!(labels[1].0, 2);
+        assert_eq!((labels[1].1.x, labels[1].1.y), b[0]);
+
+        let mut scope = Oscilloscope::new();
+        scope.set_draw_order(&[&b, &a]);
+        assert_eq!((scope.draw_order[0].1.x, scope.draw_order[0].1.y), b[0]);
+    }
+
+    #[test]
+    fn test_graticule_divisions_set_line_count() {
+        let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(100.0, 80.0));
+
+        // 10 x 10: 11 lines each way, the middle ones drawn as axes
+        let square = graticule_lines(rect, [10, 10]);
+        assert_eq!(square.len(), 22);
+        assert_eq!(square.iter().filter(|(_, axis)| *axis).count(), 2);
+
+        // 10 x 8, like a bench scope: two fewer horizontal lines
+        assert_eq!(graticule_lines(rect, [10, 8]).len(), 20);
+
+        // Odd counts add centered axes between the grid lines
+        let odd = graticule_lines(rect, [5, 5]);
+        assert_eq!(odd.len(), 14);
+        let axes: Vec<_> = odd.iter().filter(|(_, axis)| *axis).collect();
+        assert_eq!(axes[0].0[0].x, 50.0);
+        assert_eq!(axes[1].0[0].y, 40.0);
+    }
+
+    #[test]
+    fn test_uniform_line_has_equal_velocities() {
+        let vectors = velocity_field(&line_samples(), 4);
+
+        assert_eq!(vectors.len(), 5);
+        for (_, (vx, vy)) in &vectors {
+            assert!((vx.hypot(*vy) - 0.05).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_half_pixel_line_is_continuous_and_dimmed() {
+        let line: Vec<XYSample> = (0..=200)
+            .map(|i| {
+                let t = i as f32 / 200.0;
+                XYSample::new(-0.8 + 1.6 * t, -0.6 + 1.2 * t)
+            })
+            .collect();
+        let mut scope = Oscilloscope::new();
+        scope.settings.line_width = 0.5;
+        let background = scope.settings.background;
+        let image = scope.render_to_image(&line, [64, 64], 1);
+
+        // Every column the diagonal crosses has a lit pixel: no gaps
+        let column_peak = |x: usize| {
+            (0..64)
+                .map(|y| image.pixels[y * 64 + x].g().abs_diff(background.g()))
+                .max()
+                .unwrap()
+        };
+        for x in 8..56 {
+            assert!(column_peak(x) > 40, "gap in column {}", x);
+        }
+
+        // ...at about half the brightness of a full-width beam
+        scope.settings.line_width = 1.0;
+        let full = scope.render_to_image(&line, [64, 64], 1);
+        let full_peak = full
+            .pixels
+            .iter()
+            .map(|p| p.g().abs_diff(background.g()))
+            .max()
+            .unwrap();
+        assert!(column_peak(32) < full_peak);
+
+        let stroke = beam_stroke(0.5, Color32::WHITE);
+        assert_eq!(stroke.width, MIN_BEAM_WIDTH);
+        assert!(stroke.color.a() < 255);
+    }
+
+    /// Sum of how far each pixel is from the background
+    fn brightness(image: &ColorImage, background: Color32) -> u32 {
+        image
+            .pixels
+            .iter()
+            .map(|p| p.g().abs_diff(background.g()) as u32)
+            .sum()
+    }
+
+    #[test]
+    fn test_afterglow_gamma_reshapes_fade() {
+        // Gamma 1 is the old linear fade
+        assert_eq!(afterglow_alpha(1.0, 1.0), 76);
+        assert_eq!(afterglow_alpha(0.5, 1.0), 38);
+
+        // A full-strength point is unchanged, a half-faded one is dimmer
+        assert_eq!(afterglow_alpha(1.0, 2.2), afterglow_alpha(1.0, 1.0));
+        assert!(afterglow_alpha(0.5, 2.2) < afterglow_alpha(0.5, 1.0));
+        assert!(afterglow_alpha(0.5, 0.5) > afterglow_alpha(0.5, 1.0));
+    }
+
+    #[test]
+    fn test_rotate_hue_full_turn_is_identity() {
+        let color = Color32::from_rgb(100, 255, 100);
+        let close = |a: Color32, b: Color32| {
+            a.r().abs_diff(b.r()) <= 1 && a.g().abs_diff(b.g()) <= 1 && a.b().abs_diff(b.b()) <= 1
+        };
+
+        assert!(close(rotate_hue(color, 1.0), color));
+        assert!(close(rotate_hue(color, 3.0), color));
+        assert!(!close(rotate_hue(color, 0.5), color));
+    }
+
+    #[test]
+    fn test_independent_axis_zoom() {
+        let mut scope = Oscilloscope::new();
+        let rect = Rect::from_min_size(Pos2::new(0.0, 0.0), Vec2::new(200.0, 100.0));
+        scope.settings.zoom_x = 0.5;
+        scope.settings.zoom_y = 2.0;
+
+        // x = 0.25 / 0.5 -> half way to the right edge; y = 0.5 / 2.0 -> an eighth up
+        let pos = scope.sample_to_screen(XYSample::new(0.25, 0.5), rect);
+        assert!((pos.x - 150.0).abs() < 1e-4);
+        assert!((pos.y - 37.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_line_join_fills_sharp_corner() {
+        // A right-angle corner, and a spot just outside it where separate
+        // segments would leave a notch
+        let corner = [
+            Pos2::new(0.0, 10.0),
+            Pos2::new(10.0, 10.0),
+            Pos2::new(10.0, 0.0),
+        ];
+        let width = 4.0;
+        let notch = Pos2::new(10.8, 10.8);
+
+        let Some(egui::Shape::Circle(disc)) =
+            join_shape(LineJoin::Round, corner, width, Color32::WHITE)
+        else {
+            panic!("expected a round join");
+        };
+        assert!(disc.center.distance(notch) <= disc.radius);
+
+        let Some(egui::Shape::Path(bevel)) =
+            join_shape(LineJoin::Bevel, corner, width, Color32::WHITE)
+        else {
+            panic!("expected a bevel join");
+        };
+        let [a, b, c] = [bevel.points[0], bevel.points[1], bevel.points[2]];
+        let cross = |p: Pos2, q: Pos2, r: Pos2| (q - p).x * (r - p).y - (q - p).y * (r - p).x;
+        let signs = [cross(a, b, notch), cross(b, c, notch), cross(c, a, notch)];
+        assert!(signs.iter().all(|&s| s >= 0.0) || signs.iter().all(|&s| s <= 0.0));
+
+        // Off, or on a straight run, nothing is added
+        assert!(join_shape(LineJoin::None, corner, width, Color32::WHITE).is_none());
+        let straight = [
+            Pos2::new(0.0, 0.0),
+            Pos2::new(5.0, 0.0),
+            Pos2::new(10.0, 0.0),
+        ];
+        assert!(join_shape(LineJoin::Round, straight, width, Color32::WHITE).is_none());
+    }
+
+    #[test]
+    fn test_coincident_samples_add_one_persistence_point() {
+        let mut scope = Oscilloscope::new();
+        let rect = Rect::from_min_size(Pos2::new(0.0, 0.0), Vec2::new(200.0, 100.0));
+
+        let dot = vec![XYSample::new(0.1, 0.2); 1000];
+        scope.update_persistence(&dot, rect);
+        assert_eq!(scope.persistence_buffer.len(), 1);
+
+        // Spread-out samples are all kept
+        scope.clear_persistence();
+        scope.update_persistence(&line_samples(), rect);
+        assert_eq!(scope.persistence_buffer.len(), line_samples().len());
+    }
+
+    #[test]
+    fn test_screen_to_sample_inverts_mapping() {
+        let mut scope = Oscilloscope::new();
+        let rect = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::new(200.0, 100.0));
+
+        let (x, y) = scope.screen_to_sample(rect.center(), rect);
+        assert!(x.abs() < 1e-5 && y.abs() < 1e-5);
+
+        scope.settings.zoom_x = 0.5;
+        scope.settings.zoom_y = 0.5;
+        let sample = XYSample::new(0.3, -0.2);
+        let (x, y) = scope.screen_to_sample(scope.sample_to_screen(sample, rect), rect);
+        assert!((x - 0.3).abs() < 1e-5 && (y + 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_focus_coupling_widens_bright_beam() {
+        let lit = |scope: &Oscilloscope| {
+            scope
+                .rasterize(&line_samples(), [64, 64], 1.0)
+                .iter()
+                .filter(|&&v| v > 0.0)
+                .count()
+        };
+
+        let mut scope = Oscilloscope::new();
+        scope.settings.line_width = 3.0;
+        scope.settings.focus_coupling = true;
+        scope.settings.intensity = 0.2;
+        let dim = lit(&scope);
+        scope.settings.intensity = 1.0;
+        let bright = lit(&scope);
+        assert!(bright > dim);
+
+        // Without coupling the footprint ignores intensity
+        scope.settings.focus_coupling = false;
+        let uncoupled = lit(&scope);
+        scope.settings.intensity = 0.2;
+        assert_eq!(lit(&scope), uncoupled);
+    }
+
+    #[test]
+    fn test_supersampling_smooths_circle_edges() {
+        let circle: Vec<XYSample> = (0..=400)
+            .map(|i| {
+                let a = i as f32 / 400.0 * std::f32::consts::TAU;
+                XYSample::new(0.63 * a.cos(), 0.63 * a.sin())
+            })
+            .collect();
+        // A hairline beam is narrower than a pixel, which a 1x raster can
+        // only spread over whole pixels
+        let mut scope = Oscilloscope::new();
+        scope.settings.line_width = 0.5;
+
+        // Largest brightness step between horizontally adjacent pixels
+        let roughness = |scope: &mut Oscilloscope, factor| {
+            let image = scope.render_to_image(&circle, [48, 48], factor);
+            let g: Vec<i32> = image.pixels.iter().map(|p| p.g() as i32).collect();
+            g.chunks(48)
+                .flat_map(|row| row.windows(2).map(|w| (w[1] - w[0]).abs()))
+                .max()
+                .unwrap()
+        };
+
+        assert!(roughness(&mut scope, 2) < roughness(&mut scope, 1));
+    }
+
+    #[test]
+    fn test_beam_spot_is_brighter_than_trace() {
+        let mut scope = Oscilloscope::new();
+        scope.settings.intensity = 0.5;
+        scope.settings.show_beam_spot = true;
+        let background = scope.settings.background;
+        let image = scope.render_to_image(&line_samples(), [64, 64], 1);
+        let level = |x: usize| image.pixels[32 * 64 + x].g().abs_diff(background.g());
+
+        // The line runs from x = 16 to 48; the beam is at its right end
+        assert!(level(48) > level(32), "{} <= {}", level(48), level(32));
+        assert!(level(32) > 0);
+
+        // Without the spot, the end of the line is as bright as the rest
+        scope.settings.show_beam_spot = false;
+        let plain = scope.render_to_image(&line_samples(), [64, 64], 1);
+        assert_eq!(
+            plain.pixels[32 * 64 + 47].g(),
+            plain.pixels[32 * 64 + 32].g()
+        );
+
+        // Past full intensity the drawn spot whitens
+        let green = Color32::from_rgb(0, 255, 0);
+        assert_eq!(spot_color(green, 0.5).a(), 127);
+        assert!(spot_color(green, 2.0).r() > 0);
+    }
+
+    #[test]
+    fn test_frame_blend_carries_previous_frame() {
+        let mut scope = Oscilloscope::new();
+        scope.settings.frame_blend = 0.8;
+        let background = scope.settings.background;
+
+        scope.render_to_image(&line_samples(), [64, 64], 1);
+        let blended = scope.render_to_image(&[], [64, 64], 1);
+        assert!(brightness(&blended, background) > 0);
+
+        // Without blending an empty frame is just background
+        scope.settings.frame_blend = 0.0;
+        scope.render_to_image(&line_samples(), [64, 64], 1);
+        let plain = scope.render_to_image(&[], [64, 64], 1);
+        assert_eq!(brightness(&plain, background), 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! Trigger - start each displayed frame at the same phase of the signal
+//!
+//! The sample buffer is a rolling window, so without a trigger each frame
+//! starts at an arbitrary point in the trace and the image drifts. Like the
+//! trigger on a real scope, this finds a zero crossing on one channel and
+//! draws from there.
+
+use crate::audio::XYSample;
+
+/// Channel the trigger watches
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum TriggerSource {
+    #[default]
+    X,
+    Y,
+}
+
+impl TriggerSource {
+    /// Get all sources
+    pub fn all() -> &'static [TriggerSource] {
+        &[TriggerSource::X, TriggerSource::Y]
+    }
+
+    /// Get the name of this source
+    pub fn name(&self) -> &'static str {
+        match self {
+            TriggerSource::X => "X",
+            TriggerSource::Y => "Y",
+        }
+    }
+}
+
+/// Direction of the zero crossing that fires the trigger
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum TriggerEdge {
+    /// Negative to non-negative
+    #[default]
+    Rising,
+    /// Non-negative to negative
+    Falling,
+}
+
+impl TriggerEdge {
+    /// Get all edges
+    pub fn all() -> &'static [TriggerEdge] {
+        &[TriggerEdge::Rising, TriggerEdge::Falling]
+    }
+
+    /// Get the name of this edge
+    pub fn name(&self) -> &'static str {
+        match self {
+            TriggerEdge::Rising => "Rising",
+            TriggerEdge::Falling => "Falling",
+        }
+    }
+}
+
+/// Index of the first sample where `source` crosses zero along `edge`
+///
+/// Returns the index of the sample just after the crossing, or `None` if
+/// the channel never crosses in that direction.
+pub fn find_trigger(
+    samples: &[XYSample],
+    source: TriggerSource,
+    edge: TriggerEdge,
+) -> Option<usize> {
+    let value = |s: &XYSample| match source {
+        TriggerSource::X => s.x,
+        TriggerSource::Y => s.y,
+    };
+    samples
+        .windows(2)
+        .position(|w| {
+            let (prev, cur) = (value(&w[0]), value(&w[1]));
+            match edge {
+                TriggerEdge::Rising => prev < 0.0 && cur >= 0.0,
+                TriggerEdge::Falling => prev >= 0.0 && cur < 0.0,
+            }
+        })
+        .map(|i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_first_rising_zero_crossing() {
+        let xs = [0.5, -0.2, -0.6, 0.1, 0.4, -0.3, 0.2];
+        let samples: Vec<XYSample> = xs.iter().map(|&x| XYSample::new(x, -x)).collect();
+
+        assert_eq!(
+            find_trigger(&samples, TriggerSource::X, TriggerEdge::Rising),
+            Some(3)
+        );
+        assert_eq!(
+            find_trigger(&samples, TriggerSource::X, TriggerEdge::Falling),
+            Some(1)
+        );
+        // Y is the mirror image, so its first rising crossing is X's first falling one
+        assert_eq!(
+            find_trigger(&samples, TriggerSource::Y, TriggerEdge::Rising),
+            Some(1)
+        );
+
+        let flat = vec![XYSample::new(0.5, 0.5); 4];
+        assert_eq!(
+            find_trigger(&flat, TriggerSource::X, TriggerEdge::Rising),
+            None
+        );
+    }
+}
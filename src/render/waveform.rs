@@ -0,0 +1,147 @@
+//! Waveform display - one channel against time
+//!
+//! The XY view hides what each channel is doing on its own. For debugging
+//! a shape or effect, this plots X or Y against sample index like a scope
+//! in normal (YT) mode.
+
+use eframe::egui::{self, Color32, Pos2, Rect, Stroke, Vec2};
+
+use super::oscilloscope::OscilloscopeSettings;
+use crate::audio::XYSample;
+
+/// Channel plotted by the waveform view
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum WaveformChannel {
+    #[default]
+    X,
+    Y,
+}
+
+impl WaveformChannel {
+    /// Get all channels
+    pub fn all() -> &'static [WaveformChannel] {
+        &[WaveformChannel::X, WaveformChannel::Y]
+    }
+
+    /// Get the name of this channel
+    pub fn name(&self) -> &'static str {
+        match self {
+            WaveformChannel::X => "X",
+            WaveformChannel::Y => "Y",
+        }
+    }
+}
+
+/// Time-domain view of a single channel
+#[derive(Clone, Debug)]
+pub struct WaveformView {
+    /// Channel to plot
+    pub channel: WaveformChannel,
+    /// Number of most recent samples shown across the width
+    pub sample_count: usize,
+}
+
+impl Default for WaveformView {
+    fn default() -> Self {
+        Self {
+            channel: WaveformChannel::X,
+            sample_count: 1024,
+        }
+    }
+}
+
+impl WaveformView {
+    /// Draw the selected channel of `samples`, oldest on the left
+    ///
+    /// Colors and line width follow the XY display's settings.
+    pub fn show(
+        &self,
+        ui: &mut egui::Ui,
+        samples: &[XYSample],
+        settings: &OscilloscopeSettings,
+    ) -> egui::Response {
+        let available = ui.available_size();
+        let size = Vec2::new(available.x, available.y.min(400.0));
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 4.0, settings.background);
+
+        // Zero line
+        let axis_color = Color32::from_rgba_unmultiplied(80, 100, 80, 150);
+        painter.line_segment(
+            [
+                Pos2::new(rect.left(), rect.center().y),
+                Pos2::new(rect.right(), rect.center().y),
+            ],
+            Stroke::new(1.0, axis_color),
+        );
+
+        let values = channel_values(samples, self.channel);
+        if values.len() >= 2 {
+            let points = values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| value_to_screen(i, values.len(), v, rect))
+                .collect();
+            painter.add(egui::Shape::line(
+                points,
+                Stroke::new(settings.line_width, settings.color),
+            ));
+        }
+
+        painter.text(
+            rect.left_top() + Vec2::new(6.0, 4.0),
+            egui::Align2::LEFT_TOP,
+            self.channel.name(),
+            egui::FontId::monospace(12.0),
+            settings.color,
+        );
+
+        response
+    }
+}
+
+/// Values of one channel, in sample order
+pub fn channel_values(samples: &[XYSample], channel: WaveformChannel) -> Vec<f32> {
+    samples
+        .iter()
+        .map(|s| match channel {
+            WaveformChannel::X => s.x,
+            WaveformChannel::Y => s.y,
+        })
+        .collect()
+}
+
+/// Screen position of sample `index` of `len` with value `value` (±1 spans the height)
+fn value_to_screen(index: usize, len: usize, value: f32, rect: Rect) -> Pos2 {
+    let x = rect.left() + index as f32 / (len - 1).max(1) as f32 * rect.width();
+    let y = rect.center().y - value.clamp(-1.0, 1.0) * rect.height() / 2.0;
+    Pos2::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_values_pick_the_channel() {
+        let samples = [
+            XYSample::new(0.1, -0.1),
+            XYSample::new(0.2, -0.2),
+            XYSample::new(0.3, -0.3),
+        ];
+        assert_eq!(
+            channel_values(&samples, WaveformChannel::X),
+            vec![0.1, 0.2, 0.3]
+        );
+        assert_eq!(
+            channel_values(&samples, WaveformChannel::Y),
+            vec![-0.1, -0.2, -0.3]
+        );
+
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 50.0));
+        assert_eq!(value_to_screen(0, 3, 1.0, rect), Pos2::new(0.0, 0.0));
+        assert_eq!(value_to_screen(2, 3, -1.0, rect), Pos2::new(100.0, 50.0));
+    }
+}
@@ -1,10 +1,31 @@
 use std::path::PathBuf;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use eframe::egui;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::audio::{OutputFormat, MAX_FREQUENCY, MIN_FREQUENCY};
 use crate::midi::MidiMapping;
-use crate::{EditorMode, LfoWaveform, MeshPrimitive, OsciApp, ShapeType};
+use crate::render::{LineJoin, TriggerEdge, TriggerSource};
+use crate::shapes::{Easing, HarmonographParams};
+use crate::{
+    EditorMode, LfoWaveform, MeshPrimitive, OsciApp, OutOfBoundsMode, SequencerTarget, ShapeType,
+};
+
+/// Prefix marking a shared settings string
+const SHARE_PREFIX: &str = "osci-rs:";
+
+/// Errors that can occur when importing a shared settings string
+#[derive(Error, Debug)]
+pub enum ShareError {
+    #[error("Not a valid settings string: {0}")]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("Settings string is corrupt: {0}")]
+    Json(#[from] serde_json::Error),
+}
 
 /// Returns the path to the settings file: `~/.config/osci-rs/settings.json`
 fn settings_path() -> PathBuf {
@@ -19,7 +40,7 @@ fn settings_path() -> PathBuf {
 /// Serialized as JSON to the platform config directory.
 /// Fields use `#[serde(default)]` so that adding new settings
 /// won't break existing config files.
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AppSettings {
     // Editor
@@ -36,11 +57,44 @@ pub struct AppSettings {
     pub lissajous_a: f32,
     pub lissajous_b: f32,
     pub lissajous_delta: f32,
+    pub lissajous_points: usize,
+    pub lissajous_close: bool,
+    pub harmonograph: HarmonographParams,
+    pub harmonograph_points: usize,
     pub spiral_turns: f32,
+    pub morph_from: ShapeType,
+    pub morph_to: ShapeType,
+    pub morph_period: f32,
+    pub morph_easing: Easing,
 
     // Audio
     pub frequency: f32,
+    pub time_scale: f32,
     pub volume: f32,
+    pub invert_y: bool,
+    pub max_slew: f32,
+    pub trace_overlap: f32,
+    pub buffer_frames: Option<u32>,
+    pub auto_fit: bool,
+    pub blank_distance: f32,
+    pub center_dead_zone: f32,
+    pub start_t: f32,
+    pub end_t: f32,
+    pub output_rotation: f32,
+    pub stereo_width: f32,
+    pub max_total_points: usize,
+    pub close_gap: bool,
+    pub retrace_fraction: f32,
+    pub corner_dwell: usize,
+    pub revolutions: usize,
+    pub per_rev_rotation: f32,
+    pub channel_phase_offset: i32,
+    pub x_channel: usize,
+    pub y_channel: usize,
+    pub z_channel: Option<usize>,
+    pub monitor_device: Option<String>,
+    pub output_format: OutputFormat,
+    pub dither: bool,
 
     // Effects
     pub enable_rotation: bool,
@@ -50,14 +104,58 @@ pub struct AppSettings {
     pub scale_lfo_min: f32,
     pub scale_lfo_max: f32,
     pub scale_lfo_waveform: LfoWaveform,
+    pub scale_lfo_spatial: f32,
+    pub enable_tile: bool,
+    pub tile_x: u32,
+    pub tile_y: u32,
+    pub tile_mirror: bool,
+    pub enable_ripple: bool,
+    pub ripple_freq: f32,
+    pub ripple_depth: f32,
+    pub enable_jitter: bool,
+    pub jitter_amount: f32,
+    pub enable_sequencer: bool,
+    pub sequencer_steps: Vec<f32>,
+    pub sequencer_bpm: f32,
+    pub sequencer_rate: f32,
+    pub sequencer_min: f32,
+    pub sequencer_max: f32,
+    pub sequencer_target: SequencerTarget,
+    pub out_of_bounds: OutOfBoundsMode,
+    pub radial_limit: bool,
+    pub enable_spring: bool,
+    pub spring_stiffness: f32,
+    pub spring_damping: f32,
 
     // Display
     pub line_width: f32,
     pub draw_lines: bool,
+    pub line_join: LineJoin,
     pub intensity: f32,
-    pub zoom: f32,
+    pub zoom_x: f32,
+    pub zoom_y: f32,
+    pub zoom_linked: bool,
     pub show_graticule: bool,
+    pub graticule_divisions: [usize; 2],
     pub persistence: f32,
+    pub persistence_gamma: f32,
+    pub persistence_spacing: f32,
+    pub frame_blend: f32,
+    pub trigger: bool,
+    pub trigger_source: TriggerSource,
+    pub trigger_edge: TriggerEdge,
+    pub focus_coupling: bool,
+    pub show_cursor_readout: bool,
+    pub show_direction: bool,
+    pub direction_spacing: usize,
+    pub show_velocity_field: bool,
+    pub show_draw_order: bool,
+    pub show_beam_spot: bool,
+    pub beam_spot_brightness: f32,
+    pub idle_animation: bool,
+    pub show_crossings: bool,
+    pub idle_dimming: bool,
+    pub idle_dim_delay: f32,
 
     // Color (stored as u8 triples since Color32 isn't serde-friendly)
     pub color_r: u8,
@@ -66,6 +164,7 @@ pub struct AppSettings {
     pub background_r: u8,
     pub background_g: u8,
     pub background_b: u8,
+    pub color_cycle_speed: f32,
 
     // Text
     pub text_input: String,
@@ -75,6 +174,10 @@ pub struct AppSettings {
 
     // MIDI
     pub midi_mappings: Vec<MidiMapping>,
+    pub beat_flash: bool,
+    pub visualizer_sensitivity: f32,
+    pub visualizer_size: f32,
+    pub visualizer_spin: f32,
 }
 
 impl Default for AppSettings {
@@ -92,10 +195,43 @@ impl Default for AppSettings {
             lissajous_a: 3.0,
             lissajous_b: 2.0,
             lissajous_delta: std::f32::consts::FRAC_PI_2,
+            lissajous_points: 500,
+            lissajous_close: true,
+            harmonograph: HarmonographParams::default(),
+            harmonograph_points: 3000,
             spiral_turns: 3.0,
+            morph_from: ShapeType::Circle,
+            morph_to: ShapeType::Star,
+            morph_period: 4.0,
+            morph_easing: Easing::default(),
 
             frequency: 80.0,
+            time_scale: 1.0,
             volume: 0.8,
+            invert_y: false,
+            max_slew: 0.0,
+            trace_overlap: 0.0,
+            buffer_frames: None,
+            auto_fit: false,
+            blank_distance: 0.0,
+            center_dead_zone: 0.0,
+            start_t: 0.0,
+            end_t: 1.0,
+            output_rotation: 0.0,
+            stereo_width: 1.0,
+            max_total_points: 0,
+            close_gap: false,
+            retrace_fraction: 0.0,
+            corner_dwell: 0,
+            revolutions: 1,
+            per_rev_rotation: 0.0,
+            channel_phase_offset: 0,
+            x_channel: 0,
+            y_channel: 1,
+            z_channel: None,
+            monitor_device: None,
+            output_format: OutputFormat::Auto,
+            dither: true,
 
             enable_rotation: false,
             rotation_speed: 1.0,
@@ -104,13 +240,57 @@ impl Default for AppSettings {
             scale_lfo_min: 0.8,
             scale_lfo_max: 1.2,
             scale_lfo_waveform: LfoWaveform::Sine,
+            scale_lfo_spatial: 0.0,
+            enable_tile: false,
+            tile_x: 2,
+            tile_y: 2,
+            tile_mirror: true,
+            enable_ripple: false,
+            ripple_freq: 8.0,
+            ripple_depth: 0.1,
+            enable_jitter: false,
+            jitter_amount: 0.01,
+            enable_sequencer: false,
+            sequencer_steps: vec![1.0, 0.5, 0.75, 0.5],
+            sequencer_bpm: 120.0,
+            sequencer_rate: 1.0,
+            sequencer_min: 0.5,
+            sequencer_max: 1.0,
+            sequencer_target: SequencerTarget::Size,
+            out_of_bounds: OutOfBoundsMode::Clamp,
+            radial_limit: false,
+            enable_spring: false,
+            spring_stiffness: 120.0,
+            spring_damping: 8.0,
 
             line_width: 1.5,
             draw_lines: true,
+            line_join: LineJoin::Round,
             intensity: 1.0,
-            zoom: 1.0,
+            zoom_x: 1.0,
+            zoom_y: 1.0,
+            zoom_linked: true,
             show_graticule: true,
+            graticule_divisions: [10, 10],
             persistence: 0.85,
+            persistence_gamma: 2.2,
+            persistence_spacing: 1.0,
+            frame_blend: 0.0,
+            trigger: false,
+            trigger_source: TriggerSource::X,
+            trigger_edge: TriggerEdge::Rising,
+            focus_coupling: false,
+            show_cursor_readout: false,
+            show_direction: false,
+            direction_spacing: 32,
+            show_velocity_field: false,
+            show_draw_order: false,
+            show_beam_spot: false,
+            beam_spot_brightness: 2.0,
+            idle_animation: false,
+            show_crossings: false,
+            idle_dimming: false,
+            idle_dim_delay: 120.0,
 
             color_r: 100,
             color_g: 255,
@@ -118,12 +298,17 @@ impl Default for AppSettings {
             background_r: 10,
             background_g: 20,
             background_b: 10,
+            color_cycle_speed: 0.0,
 
             text_input: "Hello".to_string(),
 
             mesh_primitive: MeshPrimitive::Cube,
 
             midi_mappings: Vec::new(),
+            beat_flash: false,
+            visualizer_sensitivity: 4.0,
+            visualizer_size: 0.5,
+            visualizer_spin: 0.0,
         }
     }
 }
@@ -133,7 +318,7 @@ impl AppSettings {
     pub fn load() -> Self {
         let path = settings_path();
         match std::fs::read_to_string(&path) {
-            Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(contents) => match Self::from_json(contents.as_bytes()) {
                 Ok(settings) => {
                     log::info!("Loaded settings from {}", path.display());
                     settings
@@ -171,6 +356,58 @@ impl AppSettings {
         }
     }
 
+    /// Encode as a compact single-line string for sharing (e.g. on forums).
+    ///
+    /// The format is `osci-rs:` followed by URL-safe base64 of compact JSON.
+    pub fn to_share_string(&self) -> String {
+        // Serializing plain data to JSON cannot fail
+        let json = serde_json::to_string(self).unwrap_or_default();
+        format!("{}{}", SHARE_PREFIX, URL_SAFE_NO_PAD.encode(json))
+    }
+
+    /// Decode a string produced by `to_share_string`.
+    ///
+    /// Surrounding whitespace and a missing prefix are tolerated; fields
+    /// missing from older strings fall back to their defaults.
+    pub fn from_share_string(text: &str) -> Result<Self, ShareError> {
+        let text = text.trim();
+        let encoded = text.strip_prefix(SHARE_PREFIX).unwrap_or(text);
+        let json = URL_SAFE_NO_PAD.decode(encoded)?;
+        Ok(Self::from_json(&json)?)
+    }
+
+    /// Replace the output device and stream fields with `local`'s.
+    ///
+    /// Channel routing, buffer size, sample format and the monitor device
+    /// describe the machine, not the figure, so a pasted share string
+    /// keeps the receiver's own. Nothing that needs a stream restart is
+    /// then left to change.
+    pub fn keep_local_output(&mut self, local: &AppSettings) {
+        self.buffer_frames = local.buffer_frames;
+        self.x_channel = local.x_channel;
+        self.y_channel = local.y_channel;
+        self.z_channel = local.z_channel;
+        self.monitor_device.clone_from(&local.monitor_device);
+        self.output_format = local.output_format;
+        self.dither = local.dither;
+    }
+
+    /// Parse settings JSON, migrating fields renamed since older versions.
+    ///
+    /// Older files stored a single uniform `zoom`, which now seeds both
+    /// `zoom_x` and `zoom_y` unless the per-axis values are present.
+    fn from_json(json: &[u8]) -> Result<Self, serde_json::Error> {
+        let mut value: serde_json::Value = serde_json::from_slice(json)?;
+        if let Some(fields) = value.as_object_mut() {
+            if let Some(zoom) = fields.remove("zoom") {
+                for key in ["zoom_x", "zoom_y"] {
+                    fields.entry(key).or_insert_with(|| zoom.clone());
+                }
+            }
+        }
+        serde_json::from_value(value)
+    }
+
     /// Extract current settings from the running application.
     pub fn from_app(app: &OsciApp) -> Self {
         Self {
@@ -186,10 +423,43 @@ impl AppSettings {
             lissajous_a: app.shape_params.lissajous_a,
             lissajous_b: app.shape_params.lissajous_b,
             lissajous_delta: app.shape_params.lissajous_delta,
+            lissajous_points: app.shape_params.lissajous_points,
+            lissajous_close: app.shape_params.lissajous_close,
+            harmonograph: app.shape_params.harmonograph.clone(),
+            harmonograph_points: app.shape_params.harmonograph_points,
             spiral_turns: app.shape_params.spiral_turns,
+            morph_from: app.shape_params.morph_from,
+            morph_to: app.shape_params.morph_to,
+            morph_period: app.shape_params.morph_period,
+            morph_easing: app.shape_params.morph_easing,
 
             frequency: app.audio.config.frequency,
+            time_scale: app.audio.config.time_scale,
             volume: app.audio.config.volume,
+            invert_y: app.audio.invert_y(),
+            max_slew: app.audio.max_slew(),
+            trace_overlap: app.audio.trace_overlap(),
+            buffer_frames: app.audio.config.buffer_frames,
+            auto_fit: app.audio.config.auto_fit,
+            blank_distance: app.audio.config.blank_distance,
+            center_dead_zone: app.audio.config.center_dead_zone,
+            start_t: app.audio.config.start_t,
+            end_t: app.audio.config.end_t,
+            output_rotation: app.audio.config.output_rotation,
+            stereo_width: app.audio.config.stereo_width,
+            max_total_points: app.audio.config.max_total_points,
+            close_gap: app.audio.config.close_gap,
+            retrace_fraction: app.audio.config.retrace_fraction,
+            corner_dwell: app.audio.config.corner_dwell,
+            revolutions: app.audio.config.revolutions,
+            per_rev_rotation: app.audio.config.per_rev_rotation,
+            channel_phase_offset: app.audio.config.channel_phase_offset,
+            x_channel: app.audio.config.x_channel,
+            y_channel: app.audio.config.y_channel,
+            z_channel: app.audio.config.z_channel,
+            monitor_device: app.audio.config.monitor_device.clone(),
+            output_format: app.audio.config.output_format,
+            dither: app.audio.config.dither,
 
             enable_rotation: app.enable_rotation,
             rotation_speed: app.rotation_speed,
@@ -198,13 +468,57 @@ impl AppSettings {
             scale_lfo_min: app.scale_lfo_min,
             scale_lfo_max: app.scale_lfo_max,
             scale_lfo_waveform: app.scale_lfo_waveform,
+            scale_lfo_spatial: app.scale_lfo_spatial,
+            enable_tile: app.enable_tile,
+            tile_x: app.tile_x,
+            tile_y: app.tile_y,
+            tile_mirror: app.tile_mirror,
+            enable_ripple: app.enable_ripple,
+            ripple_freq: app.ripple_freq,
+            ripple_depth: app.ripple_depth,
+            enable_jitter: app.enable_jitter,
+            jitter_amount: app.jitter_amount,
+            enable_sequencer: app.enable_sequencer,
+            sequencer_steps: app.sequencer_steps.clone(),
+            sequencer_bpm: app.sequencer_bpm,
+            sequencer_rate: app.sequencer_rate,
+            sequencer_min: app.sequencer_min,
+            sequencer_max: app.sequencer_max,
+            sequencer_target: app.sequencer_target,
+            out_of_bounds: app.out_of_bounds,
+            radial_limit: app.radial_limit,
+            enable_spring: app.enable_spring,
+            spring_stiffness: app.size_spring.stiffness,
+            spring_damping: app.size_spring.damping,
 
             line_width: app.oscilloscope.settings.line_width,
             draw_lines: app.oscilloscope.settings.draw_lines,
+            line_join: app.oscilloscope.settings.line_join,
             intensity: app.oscilloscope.settings.intensity,
-            zoom: app.oscilloscope.settings.zoom,
+            zoom_x: app.oscilloscope.settings.zoom_x,
+            zoom_y: app.oscilloscope.settings.zoom_y,
+            zoom_linked: app.oscilloscope.settings.zoom_linked,
             show_graticule: app.oscilloscope.settings.show_graticule,
+            graticule_divisions: app.oscilloscope.settings.graticule_divisions,
             persistence: app.oscilloscope.settings.persistence,
+            persistence_gamma: app.oscilloscope.settings.persistence_gamma,
+            persistence_spacing: app.oscilloscope.settings.persistence_spacing,
+            frame_blend: app.oscilloscope.settings.frame_blend,
+            trigger: app.oscilloscope.settings.trigger,
+            trigger_source: app.oscilloscope.settings.trigger_source,
+            trigger_edge: app.oscilloscope.settings.trigger_edge,
+            focus_coupling: app.oscilloscope.settings.focus_coupling,
+            show_cursor_readout: app.oscilloscope.settings.show_cursor_readout,
+            show_direction: app.oscilloscope.settings.show_direction,
+            direction_spacing: app.oscilloscope.settings.direction_spacing,
+            show_velocity_field: app.oscilloscope.settings.show_velocity_field,
+            show_draw_order: app.oscilloscope.settings.show_draw_order,
+            show_beam_spot: app.oscilloscope.settings.show_beam_spot,
+            beam_spot_brightness: app.oscilloscope.settings.beam_spot_brightness,
+            idle_animation: app.idle_animation,
+            show_crossings: app.show_crossings,
+            idle_dimming: app.idle_dimmer.enabled,
+            idle_dim_delay: app.idle_dimmer.delay,
 
             color_r: app.oscilloscope.settings.color.r(),
             color_g: app.oscilloscope.settings.color.g(),
@@ -212,12 +526,17 @@ impl AppSettings {
             background_r: app.oscilloscope.settings.background.r(),
             background_g: app.oscilloscope.settings.background.g(),
             background_b: app.oscilloscope.settings.background.b(),
+            color_cycle_speed: app.oscilloscope.settings.color_cycle_speed,
 
             text_input: app.text_input.clone(),
 
             mesh_primitive: app.mesh_primitive,
 
             midi_mappings: app.midi.mappings.clone(),
+            beat_flash: app.beat_flash,
+            visualizer_sensitivity: app.visualizer_sensitivity,
+            visualizer_size: app.visualizer_size,
+            visualizer_spin: app.visualizer_spin,
         }
     }
 
@@ -235,10 +554,43 @@ impl AppSettings {
         app.shape_params.lissajous_a = self.lissajous_a;
         app.shape_params.lissajous_b = self.lissajous_b;
         app.shape_params.lissajous_delta = self.lissajous_delta;
+        app.shape_params.lissajous_points = self.lissajous_points;
+        app.shape_params.lissajous_close = self.lissajous_close;
+        app.shape_params.harmonograph = self.harmonograph.clone();
+        app.shape_params.harmonograph_points = self.harmonograph_points;
         app.shape_params.spiral_turns = self.spiral_turns;
+        app.shape_params.morph_from = self.morph_from;
+        app.shape_params.morph_to = self.morph_to;
+        app.shape_params.morph_period = self.morph_period;
+        app.shape_params.morph_easing = self.morph_easing;
 
-        app.audio.config.frequency = self.frequency;
+        app.audio.config.frequency = self.frequency.clamp(MIN_FREQUENCY, MAX_FREQUENCY);
+        app.audio.config.time_scale = self.time_scale.clamp(0.1, 2.0);
         app.audio.config.volume = self.volume;
+        app.audio.set_invert_y(self.invert_y);
+        app.audio.set_max_slew(self.max_slew);
+        app.audio.set_trace_overlap(self.trace_overlap);
+        app.audio.config.buffer_frames = self.buffer_frames;
+        app.audio.config.auto_fit = self.auto_fit;
+        app.audio.config.blank_distance = self.blank_distance;
+        app.audio.config.center_dead_zone = self.center_dead_zone;
+        app.audio.config.start_t = self.start_t;
+        app.audio.config.end_t = self.end_t;
+        app.audio.config.output_rotation = self.output_rotation;
+        app.audio.config.stereo_width = self.stereo_width;
+        app.audio.config.max_total_points = self.max_total_points;
+        app.audio.config.close_gap = self.close_gap;
+        app.audio.config.retrace_fraction = self.retrace_fraction;
+        app.audio.config.corner_dwell = self.corner_dwell;
+        app.audio.config.revolutions = self.revolutions;
+        app.audio.config.per_rev_rotation = self.per_rev_rotation;
+        app.audio.config.channel_phase_offset = self.channel_phase_offset;
+        app.audio.config.x_channel = self.x_channel;
+        app.audio.config.y_channel = self.y_channel;
+        app.audio.config.z_channel = self.z_channel;
+        app.audio.config.monitor_device = self.monitor_device.clone();
+        app.audio.config.output_format = self.output_format;
+        app.audio.config.dither = self.dither;
 
         app.enable_rotation = self.enable_rotation;
         app.rotation_speed = self.rotation_speed;
@@ -247,25 +599,149 @@ impl AppSettings {
         app.scale_lfo_min = self.scale_lfo_min;
         app.scale_lfo_max = self.scale_lfo_max;
         app.scale_lfo_waveform = self.scale_lfo_waveform;
+        app.scale_lfo_spatial = self.scale_lfo_spatial;
+        app.enable_tile = self.enable_tile;
+        app.tile_x = self.tile_x;
+        app.tile_y = self.tile_y;
+        app.tile_mirror = self.tile_mirror;
+        app.enable_ripple = self.enable_ripple;
+        app.ripple_freq = self.ripple_freq;
+        app.ripple_depth = self.ripple_depth;
+        app.enable_jitter = self.enable_jitter;
+        app.jitter_amount = self.jitter_amount;
+        app.enable_sequencer = self.enable_sequencer;
+        app.sequencer_steps.clone_from(&self.sequencer_steps);
+        app.sequencer_bpm = self.sequencer_bpm;
+        app.sequencer_rate = self.sequencer_rate;
+        app.sequencer_min = self.sequencer_min;
+        app.sequencer_max = self.sequencer_max;
+        app.sequencer_target = self.sequencer_target;
+        app.out_of_bounds = self.out_of_bounds;
+        app.radial_limit = self.radial_limit;
+        app.enable_spring = self.enable_spring;
+        for spring in [&mut app.size_spring, &mut app.rotation_spring] {
+            spring.stiffness = self.spring_stiffness;
+            spring.damping = self.spring_damping;
+        }
+        app.size_spring.snap(self.size);
+        app.rotation_spring.snap(self.rotation_speed);
 
         app.oscilloscope.settings.line_width = self.line_width;
         app.oscilloscope.settings.draw_lines = self.draw_lines;
+        app.oscilloscope.settings.line_join = self.line_join;
         app.oscilloscope.settings.intensity = self.intensity;
-        app.oscilloscope.settings.zoom = self.zoom;
+        app.oscilloscope.settings.zoom_x = self.zoom_x;
+        app.oscilloscope.settings.zoom_y = self.zoom_y;
+        app.oscilloscope.settings.zoom_linked = self.zoom_linked;
         app.oscilloscope.settings.show_graticule = self.show_graticule;
+        app.oscilloscope.settings.graticule_divisions = self.graticule_divisions;
         app.oscilloscope.settings.persistence = self.persistence;
+        app.oscilloscope.settings.persistence_gamma = self.persistence_gamma;
+        app.oscilloscope.settings.persistence_spacing = self.persistence_spacing;
+        app.oscilloscope.settings.frame_blend = self.frame_blend;
+        app.oscilloscope.settings.trigger = self.trigger;
+        app.oscilloscope.settings.trigger_source = self.trigger_source;
+        app.oscilloscope.settings.trigger_edge = self.trigger_edge;
+        app.oscilloscope.settings.focus_coupling = self.focus_coupling;
+        app.oscilloscope.settings.show_cursor_readout = self.show_cursor_readout;
+        app.oscilloscope.settings.show_direction = self.show_direction;
+        app.oscilloscope.settings.direction_spacing = self.direction_spacing;
+        app.oscilloscope.settings.show_velocity_field = self.show_velocity_field;
+        app.oscilloscope.settings.show_draw_order = self.show_draw_order;
+        app.oscilloscope.settings.show_beam_spot = self.show_beam_spot;
+        app.oscilloscope.settings.beam_spot_brightness = self.beam_spot_brightness;
+        app.idle_animation = self.idle_animation;
+        app.show_crossings = self.show_crossings;
+        app.idle_dimmer.enabled = self.idle_dimming;
+        app.idle_dimmer.delay = self.idle_dim_delay;
 
         app.oscilloscope.settings.color =
             egui::Color32::from_rgb(self.color_r, self.color_g, self.color_b);
         app.oscilloscope.settings.background =
             egui::Color32::from_rgb(self.background_r, self.background_g, self.background_b);
+        app.oscilloscope.settings.color_cycle_speed = self.color_cycle_speed;
 
         app.text_input = self.text_input.clone();
 
         app.mesh_primitive = self.mesh_primitive;
 
         app.midi.mappings = self.midi_mappings.clone();
+        app.beat_flash = self.beat_flash;
+        app.visualizer_sensitivity = self.visualizer_sensitivity;
+        app.visualizer_size = self.visualizer_size;
+        app.visualizer_spin = self.visualizer_spin;
 
         app.shape_needs_update = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_string_round_trip() {
+        let settings = AppSettings {
+            frequency: 123.5,
+            text_input: "Hi there".to_string(),
+            selected_shape: ShapeType::Harmonograph,
+            color_g: 42,
+            direction_spacing: 64,
+            ..Default::default()
+        };
+
+        let shared = settings.to_share_string();
+        assert!(shared.starts_with(SHARE_PREFIX));
+        assert!(!shared.contains(char::is_whitespace));
+        assert_eq!(AppSettings::from_share_string(&shared).unwrap(), settings);
+
+        // Pasted text often picks up stray whitespace
+        let padded = format!("  {}\n", shared);
+        assert_eq!(AppSettings::from_share_string(&padded).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_share_string_rejects_garbage() {
+        assert!(AppSettings::from_share_string("osci-rs:not base64!").is_err());
+        let not_json = format!("{}{}", SHARE_PREFIX, URL_SAFE_NO_PAD.encode("nope"));
+        assert!(AppSettings::from_share_string(&not_json).is_err());
+    }
+
+    #[test]
+    fn test_shared_settings_keep_local_output() {
+        let local = AppSettings {
+            x_channel: 2,
+            y_channel: 3,
+            ..Default::default()
+        };
+        let mut shared = AppSettings {
+            frequency: 300.0,
+            buffer_frames: Some(256),
+            z_channel: Some(4),
+            monitor_device: Some("Speakers".to_string()),
+            ..Default::default()
+        };
+
+        shared.keep_local_output(&local);
+        assert_eq!(shared.frequency, 300.0);
+        assert_eq!(shared.buffer_frames, None);
+        assert_eq!((shared.x_channel, shared.y_channel), (2, 3));
+        assert_eq!(shared.z_channel, None);
+        assert_eq!(shared.monitor_device, None);
+    }
+
+    #[test]
+    fn test_legacy_zoom_sets_both_axes() {
+        let old = br#"{"frequency": 220.0, "zoom": 2.5, "line_width": 3.0}"#;
+        let settings = AppSettings::from_json(old).unwrap();
+        assert_eq!(settings.zoom_x, 2.5);
+        assert_eq!(settings.zoom_y, 2.5);
+        assert_eq!(settings.frequency, 220.0);
+        assert_eq!(settings.line_width, 3.0);
+
+        // Newer files keep their per-axis values
+        let new = br#"{"zoom_x": 1.5, "zoom_y": 0.5}"#;
+        let settings = AppSettings::from_json(new).unwrap();
+        assert_eq!((settings.zoom_x, settings.zoom_y), (1.5, 0.5));
+    }
+}
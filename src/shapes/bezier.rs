@@ -0,0 +1,177 @@
+//! Bézier curve evaluation and flattening, shared by the SVG and text importers
+//!
+//! Curves are turned into polylines either with a fixed number of points per
+//! segment or adaptively: a curve is split in half until each piece lies
+//! within a tolerance of its chord, so flat curves cost a couple of points
+//! and tight bends get as many as they need.
+
+/// Deepest the adaptive subdivision recurses (at most 2^16 pieces per curve)
+const MAX_SUBDIVISION_DEPTH: u32 = 16;
+
+type Point = (f32, f32);
+
+/// How a curve segment is turned into points
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Flattening {
+    /// A fixed number of evenly spaced points per segment
+    Fixed(usize),
+    /// Subdivide until the curve is within this distance of each chord
+    Adaptive(f32),
+}
+
+/// Evaluate a quadratic Bézier curve at parameter t
+pub fn quadratic_bezier(p0: Point, p1: Point, p2: Point, t: f32) -> Point {
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let t2 = t * t;
+
+    let x = mt2 * p0.0 + 2.0 * mt * t * p1.0 + t2 * p2.0;
+    let y = mt2 * p0.1 + 2.0 * mt * t * p1.1 + t2 * p2.1;
+
+    (x, y)
+}
+
+/// Evaluate a cubic Bézier curve at parameter t
+pub fn cubic_bezier(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let mt3 = mt2 * mt;
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let x = mt3 * p0.0 + 3.0 * mt2 * t * p1.0 + 3.0 * mt * t2 * p2.0 + t3 * p3.0;
+    let y = mt3 * p0.1 + 3.0 * mt2 * t * p1.1 + 3.0 * mt * t2 * p2.1 + t3 * p3.1;
+
+    (x, y)
+}
+
+/// Append points along a quadratic curve to `out`, excluding the start point
+pub fn flatten_quadratic(p0: Point, p1: Point, p2: Point, mode: Flattening, out: &mut Vec<Point>) {
+    match mode {
+        Flattening::Fixed(samples) => {
+            for i in 1..=samples {
+                let t = i as f32 / samples as f32;
+                out.push(quadratic_bezier(p0, p1, p2, t));
+            }
+        }
+        Flattening::Adaptive(_) => {
+            // A quadratic is a cubic with both controls 2/3 of the way to its control point
+            let c1 = lerp(p0, p1, 2.0 / 3.0);
+            let c2 = lerp(p2, p1, 2.0 / 3.0);
+            flatten_cubic(p0, c1, c2, p2, mode, out);
+        }
+    }
+}
+
+/// Append points along a cubic curve to `out`, excluding the start point
+pub fn flatten_cubic(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    mode: Flattening,
+    out: &mut Vec<Point>,
+) {
+    match mode {
+        Flattening::Fixed(samples) => {
+            for i in 1..=samples {
+                let t = i as f32 / samples as f32;
+                out.push(cubic_bezier(p0, p1, p2, p3, t));
+            }
+        }
+        Flattening::Adaptive(tolerance) => {
+            subdivide_cubic(p0, p1, p2, p3, tolerance.max(f32::EPSILON), 0, out);
+        }
+    }
+}
+
+/// Split a cubic in half until its control points sit within `tolerance` of the chord
+fn subdivide_cubic(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    tolerance: f32,
+    depth: u32,
+    out: &mut Vec<Point>,
+) {
+    // The curve lies inside the hull of its control points, so this bounds its deviation
+    let flat = distance_to_segment(p1, p0, p3) <= tolerance
+        && distance_to_segment(p2, p0, p3) <= tolerance;
+    if flat || depth >= MAX_SUBDIVISION_DEPTH {
+        out.push(p3);
+        return;
+    }
+
+    // de Casteljau split at t = 0.5
+    let p01 = lerp(p0, p1, 0.5);
+    let p12 = lerp(p1, p2, 0.5);
+    let p23 = lerp(p2, p3, 0.5);
+    let p012 = lerp(p01, p12, 0.5);
+    let p123 = lerp(p12, p23, 0.5);
+    let mid = lerp(p012, p123, 0.5);
+
+    subdivide_cubic(p0, p01, p012, mid, tolerance, depth + 1, out);
+    subdivide_cubic(mid, p123, p23, p3, tolerance, depth + 1, out);
+}
+
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Distance from `p` to the segment `a`-`b`
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + dx * t, a.1 + dy * t);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_spends_points_on_sharp_curves() {
+        let mode = Flattening::Adaptive(0.001);
+
+        let mut flat = Vec::new();
+        flatten_cubic(
+            (0.0, 0.0),
+            (0.33, 0.001),
+            (0.66, -0.001),
+            (1.0, 0.0),
+            mode,
+            &mut flat,
+        );
+
+        let mut sharp = Vec::new();
+        flatten_cubic(
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.0),
+            mode,
+            &mut sharp,
+        );
+
+        assert!(flat.len() <= 2, "flat curve used {} points", flat.len());
+        assert!(sharp.len() >= 16, "sharp curve used {} points", sharp.len());
+        assert_eq!(*sharp.last().unwrap(), (1.0, 0.0));
+
+        // Points stay inside the curve's bounding box
+        for &(x, y) in &sharp {
+            assert!((-0.01..=1.01).contains(&x) && (-0.01..=0.76).contains(&y));
+        }
+
+        let mut quad = Vec::new();
+        flatten_quadratic((0.0, 0.0), (0.5, 1.0), (1.0, 0.0), mode, &mut quad);
+        let apex = quad.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+        assert!((apex - 0.5).abs() < 0.01);
+    }
+}
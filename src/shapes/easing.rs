@@ -0,0 +1,93 @@
+//! Easing curves for transitions between shapes
+//!
+//! A transition parameter running linearly from 0 to 1 starts and stops
+//! abruptly. Each curve here maps it onto a new 0 to 1 value that keeps
+//! the endpoints and the midpoint but eases the rate of change near them.
+
+use std::f32::consts::PI;
+
+/// How a transition's progress is shaped over time
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum Easing {
+    /// Constant rate, with abrupt starts and stops
+    Linear,
+    /// Half a cosine: a gentle ease at both ends
+    #[default]
+    Sine,
+    /// Cubic ease-in-out: slower at the ends, quicker through the middle
+    EaseInOut,
+    /// Exponential ease-in-out: lingers at each end, then sweeps across
+    Exponential,
+}
+
+impl Easing {
+    /// Get all curves
+    pub fn all() -> &'static [Easing] {
+        &[
+            Easing::Linear,
+            Easing::Sine,
+            Easing::EaseInOut,
+            Easing::Exponential,
+        ]
+    }
+
+    /// Get the name of this curve
+    pub fn name(&self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::Sine => "Sine",
+            Easing::EaseInOut => "Ease in-out",
+            Easing::Exponential => "Exponential",
+        }
+    }
+
+    /// Map progress `t` (clamped to 0..1) along the curve
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Sine => 0.5 - 0.5 * (t * PI).cos(),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - 4.0 * (1.0 - t).powi(3)
+                }
+            }
+            Easing::Exponential => {
+                // Pinned so the ends land exactly rather than 2^-10 short
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    0.5 * 2f32.powf(20.0 * t - 10.0)
+                } else {
+                    1.0 - 0.5 * 2f32.powf(10.0 - 20.0 * t)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_in_out_keeps_ends_and_midpoint() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+        for easing in Easing::all() {
+            assert!(close(easing.apply(0.0), 0.0), "{}", easing.name());
+            assert!(close(easing.apply(0.5), 0.5), "{}", easing.name());
+            assert!(close(easing.apply(1.0), 1.0), "{}", easing.name());
+        }
+
+        // Flat at the ends, steeper than linear in the middle
+        let slope = |easing: Easing, t: f32| (easing.apply(t + 1e-2) - easing.apply(t)) / 1e-2;
+        for easing in [Easing::Sine, Easing::EaseInOut, Easing::Exponential] {
+            assert!(slope(easing, 0.0) < 0.1, "{}", easing.name());
+            assert!(slope(easing, 1.0 - 1e-2) < 0.1, "{}", easing.name());
+            assert!(slope(easing, 0.5) > 1.0, "{}", easing.name());
+        }
+        assert!(close(slope(Easing::Linear, 0.0), 1.0));
+    }
+}
@@ -2,16 +2,21 @@
 //!
 //! This module handles:
 //! - Loading image files (PNG, JPEG, etc.)
-//! - Edge detection using Sobel operator
+//! - Edge detection using Sobel operator, with optional edge thinning
 //! - Tracing edges into point sequences
+//! - Halftone dithering, tracing the dots as a point cloud
 //! - Normalizing coordinates to [-1, 1] range
 
 use std::path::Path as FilePath;
 use thiserror::Error;
 
+use super::normalize::{normalize, NormalizeMode};
 use super::path::Path;
 use super::traits::Shape;
 
+/// A step this many times the typical spacing is a jump between edges
+const SMOOTH_BREAK_FACTOR: f32 = 4.0;
+
 /// Errors that can occur during image processing
 #[derive(Error, Debug)]
 pub enum ImageError {
@@ -28,8 +33,36 @@ pub enum ImageError {
     TooSmall,
 }
 
+/// How an image is turned into points
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ImageMode {
+    /// Trace the outlines found by edge detection
+    #[default]
+    EdgeTrace,
+    /// Dither to a dot pattern, so darker areas get denser dots
+    Halftone,
+}
+
+impl ImageMode {
+    /// Get all modes
+    pub fn all() -> &'static [ImageMode] {
+        &[ImageMode::EdgeTrace, ImageMode::Halftone]
+    }
+
+    /// Get the name of this mode
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageMode::EdgeTrace => "Edge trace",
+            ImageMode::Halftone => "Halftone",
+        }
+    }
+}
+
 /// Options for image tracing
+#[derive(Clone, Debug)]
 pub struct ImageOptions {
+    /// Edge tracing or halftone dots
+    pub mode: ImageMode,
     /// Edge detection threshold (0.0 to 1.0)
     pub threshold: f32,
     /// Whether to invert the image before processing
@@ -38,15 +71,33 @@ pub struct ImageOptions {
     pub max_points: usize,
     /// Minimum edge strength to consider (0.0 to 1.0)
     pub edge_min: f32,
+    /// Moving-average window over the ordered points (0 = off)
+    ///
+    /// The window is centred, so an even size is widened by one point.
+    pub smoothing: usize,
+    /// Shift the trace so its point centroid sits at the origin
+    pub recenter: bool,
+    /// Thin edges to one pixel with non-maximum suppression
+    pub thin_edges: bool,
+    /// Keep the source proportions instead of stretching to fill the square
+    pub preserve_aspect: bool,
+    /// Map the whole image to the square, or fit the traced figure inside it
+    pub normalize: NormalizeMode,
 }
 
 impl Default for ImageOptions {
     fn default() -> Self {
         Self {
+            mode: ImageMode::EdgeTrace,
             threshold: 0.3,
             invert: false,
             max_points: 5000,
             edge_min: 0.1,
+            smoothing: 0,
+            recenter: false,
+            thin_edges: false,
+            preserve_aspect: true,
+            normalize: NormalizeMode::Fill,
         }
     }
 }
@@ -93,8 +144,17 @@ impl ImageShape {
             return Err(ImageError::TooSmall);
         }
 
-        // Apply edge detection
-        let edges = sobel_edge_detection(&gray, options);
+        // Apply edge detection, or dither to a dot mask
+        let edges = match options.mode {
+            ImageMode::EdgeTrace => {
+                let (mut edges, directions) = sobel_edge_detection(&gray, options);
+                if options.thin_edges {
+                    edges = thin_edges(&edges, &directions, width, height);
+                }
+                edges
+            }
+            ImageMode::Halftone => halftone_dots(&gray, options),
+        };
 
         // Extract edge points
         let points = extract_edge_points(&edges, width, height, options);
@@ -106,11 +166,17 @@ impl ImageShape {
         // Sort points for better drawing order (nearest neighbor)
         let sorted_points = sort_points_nearest_neighbor(&points, options.max_points);
 
+        // Smooth out jagged edge jitter
+        let sorted_points = smooth_points(&sorted_points, options.smoothing);
+
         // Create path from points
-        let path = Path::with_options(sorted_points.clone(), false, name.to_string());
+        let mut path = Path::with_options(sorted_points, false, name.to_string());
+        if options.recenter {
+            path = path.recenter_centroid();
+        }
 
         Ok(Self {
-            points: sorted_points,
+            points: path.points().to_vec(),
             path,
             name: name.to_string(),
             width,
@@ -123,6 +189,11 @@ impl ImageShape {
         self.points.len()
     }
 
+    /// Get the traced points, in drawing order
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
     /// Get the original image dimensions
     pub fn dimensions(&self) -> (u32, u32) {
         (self.width, self.height)
@@ -145,10 +216,25 @@ impl Shape for ImageShape {
     fn is_closed(&self) -> bool {
         false
     }
+
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        self.path.bounds()
+    }
+
+    fn suggested_samples(&self) -> Option<usize> {
+        Some(self.points.len())
+    }
+
+    fn has_points(&self) -> bool {
+        self.path.has_points()
+    }
 }
 
 /// Apply Sobel edge detection to a grayscale image
-fn sobel_edge_detection(img: &image::GrayImage, options: &ImageOptions) -> Vec<f32> {
+///
+/// Returns the normalized gradient magnitude and the gradient direction
+/// (radians, in pixel coordinates) for each pixel.
+fn sobel_edge_detection(img: &image::GrayImage, options: &ImageOptions) -> (Vec<f32>, Vec<f32>) {
     let (width, height) = img.dimensions();
     let w = width as usize;
     let h = height as usize;
@@ -158,6 +244,7 @@ fn sobel_edge_detection(img: &image::GrayImage, options: &ImageOptions) -> Vec<f
     const GY: [[i32; 3]; 3] = [[-1, -2, -1], [0, 0, 0], [1, 2, 1]];
 
     let mut edges = vec![0.0f32; w * h];
+    let mut directions = vec![0.0f32; w * h];
 
     // Get pixel value, handling inversion
     let get_pixel = |x: u32, y: u32| -> f32 {
@@ -189,6 +276,7 @@ fn sobel_edge_detection(img: &image::GrayImage, options: &ImageOptions) -> Vec<f
             // Gradient magnitude
             let magnitude = (gx_sum * gx_sum + gy_sum * gy_sum).sqrt();
             edges[y as usize * w + x as usize] = magnitude;
+            directions[y as usize * w + x as usize] = gy_sum.atan2(gx_sum);
         }
     }
 
@@ -200,7 +288,98 @@ fn sobel_edge_detection(img: &image::GrayImage, options: &ImageOptions) -> Vec<f
         }
     }
 
-    edges
+    (edges, directions)
+}
+
+/// Floyd-Steinberg dither the image into a mask of dots (1.0) and paper (0.0)
+///
+/// Dark pixels become dots, so dot density follows the grayscale. The mask
+/// has the same layout as the edge map and is traced the same way.
+fn halftone_dots(img: &image::GrayImage, options: &ImageOptions) -> Vec<f32> {
+    let (width, height) = img.dimensions();
+    let w = width as usize;
+    let h = height as usize;
+
+    // Work in ink coverage: 1.0 is black
+    let mut ink: Vec<f32> = img
+        .pixels()
+        .map(|p| {
+            let val = p.0[0] as f32 / 255.0;
+            if options.invert {
+                val
+            } else {
+                1.0 - val
+            }
+        })
+        .collect();
+    let mut dots = vec![0.0f32; w * h];
+
+    for y in 0..h {
+        for x in 0..w {
+            let i = y * w + x;
+            let dot = if ink[i] >= 0.5 { 1.0 } else { 0.0 };
+            dots[i] = dot;
+
+            // Push the rounding error onto unvisited neighbours
+            let error = ink[i] - dot;
+            if x + 1 < w {
+                ink[i + 1] += error * 7.0 / 16.0;
+            }
+            if y + 1 < h {
+                if x > 0 {
+                    ink[i + w - 1] += error * 3.0 / 16.0;
+                }
+                ink[i + w] += error * 5.0 / 16.0;
+                if x + 1 < w {
+                    ink[i + w + 1] += error * 1.0 / 16.0;
+                }
+            }
+        }
+    }
+
+    dots
+}
+
+/// Non-maximum suppression: keep only pixels that peak along their gradient
+///
+/// A Sobel edge is several pixels wide. Comparing each pixel with its two
+/// neighbours across the edge leaves just the ridge, one pixel thick.
+fn thin_edges(edges: &[f32], directions: &[f32], width: u32, height: u32) -> Vec<f32> {
+    let w = width as usize;
+    let h = height as usize;
+    let mut thinned = vec![0.0f32; w * h];
+
+    for y in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            let i = y * w + x;
+            let magnitude = edges[i];
+            if magnitude <= 0.0 {
+                continue;
+            }
+
+            // Quantize the gradient to the nearest of four neighbour axes
+            let angle = directions[i].to_degrees().rem_euclid(180.0);
+            let (dx, dy): (isize, isize) = if !(22.5..157.5).contains(&angle) {
+                (1, 0)
+            } else if angle < 67.5 {
+                (1, 1)
+            } else if angle < 112.5 {
+                (0, 1)
+            } else {
+                (-1, 1)
+            };
+
+            let before = edges[(y as isize - dy) as usize * w + (x as isize - dx) as usize];
+            let after = edges[(y as isize + dy) as usize * w + (x as isize + dx) as usize];
+
+            // Strict on one side so a two-pixel plateau keeps exactly one
+            if magnitude > before && magnitude >= after {
+                thinned[i] = magnitude;
+            }
+        }
+    }
+
+    thinned
 }
 
 /// Extract points from edge detection result
@@ -213,10 +392,18 @@ fn extract_edge_points(
     let w = width as usize;
     let h = height as usize;
 
-    // Calculate normalization factors to map to [-1, 1]
-    let scale = width.max(height) as f32;
-    let offset_x = width as f32 / 2.0;
-    let offset_y = height as f32 / 2.0;
+    // Calculate normalization factors to map to [-1, 1]. Pixel centers run
+    // from 0 to width - 1, so that's the span to fill
+    let span_x = width.saturating_sub(1).max(1) as f32;
+    let span_y = height.saturating_sub(1).max(1) as f32;
+    let (scale_x, scale_y) = if options.preserve_aspect {
+        let scale = span_x.max(span_y);
+        (scale, scale)
+    } else {
+        (span_x, span_y)
+    };
+    let offset_x = span_x / 2.0;
+    let offset_y = span_y / 2.0;
 
     let mut points = Vec::new();
 
@@ -227,14 +414,15 @@ fn extract_edge_points(
             // Check if this pixel is above threshold
             if edge_val >= options.threshold && edge_val >= options.edge_min {
                 // Normalize coordinates to [-1, 1]
-                let nx = (x as f32 - offset_x) / (scale / 2.0);
-                let ny = -(y as f32 - offset_y) / (scale / 2.0); // Flip Y
+                let nx = (x as f32 - offset_x) / (scale_x / 2.0);
+                let ny = -(y as f32 - offset_y) / (scale_y / 2.0); // Pixel rows are Y-down
 
-                points.push((nx.clamp(-1.0, 1.0), ny.clamp(-1.0, 1.0)));
+                points.push((nx, ny));
             }
         }
     }
 
+    normalize(&mut points, options.normalize, options.preserve_aspect);
     points
 }
 
@@ -307,6 +495,57 @@ fn sort_points_nearest_neighbor(points: &[(f32, f32)], max_points: usize) -> Vec
     result
 }
 
+/// Apply a centered moving average over an ordered point sequence
+///
+/// Each point is replaced by the mean of the `window` points around it,
+/// which removes high-frequency jitter from traced edges. The window is
+/// centred on the point, so an even `window` averages `window + 1` points.
+/// A window of 0 or 1 leaves the points unchanged.
+///
+/// The nearest-neighbour chain jumps between separate edges; a step longer
+/// than `SMOOTH_BREAK_FACTOR` times the median step is such a pen-up
+/// break, and each run between breaks is smoothed on its own so the jump
+/// doesn't drag neighbouring edges towards each other.
+fn smooth_points(points: &[(f32, f32)], window: usize) -> Vec<(f32, f32)> {
+    if window <= 1 || points.len() <= 2 {
+        return points.to_vec();
+    }
+
+    let step = |a: (f32, f32), b: (f32, f32)| ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+    let mut steps: Vec<f32> = points.windows(2).map(|w| step(w[0], w[1])).collect();
+    steps.sort_by(f32::total_cmp);
+    let limit = steps[steps.len() / 2] * SMOOTH_BREAK_FACTOR;
+
+    let mut smoothed = Vec::with_capacity(points.len());
+    let mut start = 0;
+    for i in 1..=points.len() {
+        if i == points.len() || step(points[i - 1], points[i]) > limit {
+            smoothed.extend(smooth_run(&points[start..i], window / 2));
+            start = i;
+        }
+    }
+    smoothed
+}
+
+/// Moving average of `2 * half + 1` points over one unbroken run
+fn smooth_run(points: &[(f32, f32)], half: usize) -> Vec<(f32, f32)> {
+    let last = points.len() - 1;
+
+    (0..points.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half).min(last);
+            let count = (end - start + 1) as f32;
+
+            let (sum_x, sum_y) = points[start..=end]
+                .iter()
+                .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+
+            (sum_x / count, sum_y / count)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,7 +564,7 @@ mod tests {
         }
 
         let options = ImageOptions::default();
-        let edges = sobel_edge_detection(&img, &options);
+        let (edges, _) = sobel_edge_detection(&img, &options);
 
         // Should have strong edges in the middle columns
         assert!(!edges.is_empty());
@@ -336,6 +575,62 @@ mod tests {
         assert!(mid_val > corner_val);
     }
 
+    #[test]
+    fn test_thin_edges_keeps_centerline() {
+        // A soft vertical edge whose gradient peaks at column 10
+        let mut img = image::GrayImage::new(20, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                let t = 1.0 / (1.0 + (-(x as f32 - 10.0) / 1.5).exp());
+                img.put_pixel(x, y, image::Luma([(t * 255.0).round() as u8]));
+            }
+        }
+
+        let options = ImageOptions::default();
+        let (edges, directions) = sobel_edge_detection(&img, &options);
+        let thinned = thin_edges(&edges, &directions, 20, 20);
+
+        let retained = |e: &[f32]| -> Vec<(usize, usize)> {
+            (0..e.len())
+                .filter(|&i| e[i] >= options.threshold)
+                .map(|i| (i % 20, i / 20))
+                .collect()
+        };
+        let thick = retained(&edges);
+        let thin = retained(&thinned);
+
+        assert!(thin.len() < thick.len());
+        // Every interior row keeps exactly its centre pixel
+        assert_eq!(thin.len(), 18);
+        assert!(thin.iter().all(|&(x, _)| x == 10));
+    }
+
+    #[test]
+    fn test_halftone_density_follows_darkness() {
+        // Horizontal gradient from white on the left to black on the right
+        let mut img = image::GrayImage::new(60, 20);
+        for y in 0..20 {
+            for x in 0..60 {
+                let val = 255 - (x * 255 / 59) as u8;
+                img.put_pixel(x, y, image::Luma([val]));
+            }
+        }
+
+        let dots = halftone_dots(&img, &ImageOptions::default());
+        let density = |x0: usize, x1: usize| -> usize {
+            (0..20)
+                .flat_map(|y| (x0..x1).map(move |x| y * 60 + x))
+                .filter(|&i| dots[i] > 0.0)
+                .count()
+        };
+
+        let light = density(0, 20);
+        let mid = density(20, 40);
+        let dark = density(40, 60);
+        assert!(light < mid, "{light} !< {mid}");
+        assert!(mid < dark, "{mid} !< {dark}");
+    }
+
     #[test]
     fn test_nearest_neighbor_sorting() {
         let points = vec![(0.0, 0.0), (1.0, 0.0), (0.1, 0.0), (0.9, 0.0)];
@@ -364,4 +659,75 @@ mod tests {
         // Should extract 4 points (the center 2x2)
         assert_eq!(points.len(), 4);
     }
+
+    #[test]
+    fn test_preserve_aspect() {
+        // A wide 8x2 image that is edge everywhere
+        let edges = vec![1.0; 16];
+        let extent = |points: &[(f32, f32)]| -> (f32, f32) {
+            let (min_x, max_x) = points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+                (lo.min(p.0), hi.max(p.0))
+            });
+            let (min_y, max_y) = points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+                (lo.min(p.1), hi.max(p.1))
+            });
+            (max_x - min_x, max_y - min_y)
+        };
+
+        let stretched = ImageOptions {
+            preserve_aspect: false,
+            ..Default::default()
+        };
+        let points = extract_edge_points(&edges, 8, 2, &stretched);
+        // Both axes fill [-1, 1] exactly
+        for bound in [-1.0, 1.0] {
+            assert!(points.iter().any(|p| p.0 == bound));
+            assert!(points.iter().any(|p| p.1 == bound));
+        }
+        assert_eq!(extent(&points), (2.0, 2.0));
+
+        let proportional = ImageOptions::default();
+        let (w, h) = extent(&extract_edge_points(&edges, 8, 2, &proportional));
+        // Width-to-height ratio matches the pixel spacing
+        assert_eq!(w, 2.0);
+        assert!((w / h - 7.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_smoothing_reduces_variation() {
+        // Zig-zag along X with alternating Y jitter
+        let points: Vec<(f32, f32)> = (0..20)
+            .map(|i| {
+                let y = if i % 2 == 0 { 0.1 } else { -0.1 };
+                (i as f32 * 0.05, y)
+            })
+            .collect();
+
+        let total_variation =
+            |pts: &[(f32, f32)]| -> f32 { pts.windows(2).map(|w| (w[1].1 - w[0].1).abs()).sum() };
+
+        let smoothed = smooth_points(&points, 4);
+        assert_eq!(smoothed.len(), points.len());
+        assert!(total_variation(&smoothed) < total_variation(&points));
+
+        // Zero window leaves points untouched
+        assert_eq!(smooth_points(&points, 0), points);
+
+        // An even window is centred by widening it
+        assert_eq!(smooth_points(&points, 4), smooth_points(&points, 5));
+    }
+
+    #[test]
+    fn test_smoothing_stops_at_pen_up_breaks() {
+        // Two separate horizontal edges, joined by a jump
+        let points: Vec<(f32, f32)> = (0..20)
+            .map(|i| ((i % 10) as f32 * 0.01, if i < 10 { 0.0 } else { 1.0 }))
+            .collect();
+
+        let smoothed = smooth_points(&points, 5);
+        assert_eq!(smoothed.len(), points.len());
+        for (i, &(_, y)) in smoothed.iter().enumerate() {
+            assert_eq!(y, points[i].1, "point {} pulled across the jump", i);
+        }
+    }
 }
@@ -14,12 +14,14 @@
 //!
 //! The camera looks down the negative Z axis by default.
 
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
 use std::path::Path as FilePath;
 
 use nalgebra::{Matrix4, Point3, Vector3};
 use thiserror::Error;
 
+use super::optimize::optimize_edges;
 use super::path::Path;
 use super::traits::Shape;
 
@@ -44,8 +46,16 @@ pub enum MeshError {
 pub struct Mesh {
     /// Vertex positions
     pub vertices: Vec<Point3<f32>>,
+    /// Vertex normals, parallel to `vertices` (empty if the source had none)
+    pub normals: Vec<Vector3<f32>>,
     /// Edges as pairs of vertex indices
     pub edges: Vec<(usize, usize)>,
+    /// Faces as loops of vertex indices (empty for edge-only meshes)
+    pub faces: Vec<Vec<usize>>,
+    /// Material names referenced by `edge_materials`
+    pub materials: Vec<String>,
+    /// Material index per edge, parallel to `edges` (empty if untagged)
+    pub edge_materials: Vec<Option<usize>>,
     /// Name of the mesh
     pub name: String,
 }
@@ -55,7 +65,11 @@ impl Mesh {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             vertices: Vec::new(),
+            normals: Vec::new(),
             edges: Vec::new(),
+            faces: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
             name: name.into(),
         }
     }
@@ -68,15 +82,25 @@ impl Mesh {
     ) -> Self {
         Self {
             vertices,
+            normals: Vec::new(),
             edges,
+            faces: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
             name: name.into(),
         }
     }
 
+    /// Attach face loops to the mesh (builder pattern)
+    pub fn with_faces(mut self, faces: Vec<Vec<usize>>) -> Self {
+        self.faces = faces;
+        self
+    }
+
     /// Load a mesh from an OBJ file
     pub fn from_obj(path: impl AsRef<FilePath>) -> Result<Self, MeshError> {
         let path = path.as_ref();
-        let (models, _materials) = tobj::load_obj(
+        let (models, materials) = tobj::load_obj(
             path,
             &tobj::LoadOptions {
                 triangulate: false,
@@ -90,13 +114,22 @@ impl Mesh {
             return Err(MeshError::NoGeometry);
         }
 
+        // A missing or broken MTL file only loses the material names
+        let materials: Vec<String> = materials
+            .map(|m| m.into_iter().map(|m| m.name).collect())
+            .unwrap_or_default();
+
         // Combine all models into one mesh
         let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut all_normals = true;
         let mut edges = Vec::new();
+        let mut faces = Vec::new();
         let mut vertex_offset = 0;
 
         for model in &models {
             let mesh = &model.mesh;
+            let material = mesh.material_id.filter(|&id| id < materials.len());
 
             // Add vertices
             for i in (0..mesh.positions.len()).step_by(3) {
@@ -107,23 +140,43 @@ impl Mesh {
                 ));
             }
 
+            // With a single index, `vn` normals line up with the positions
+            if mesh.normals.len() == mesh.positions.len() {
+                for n in mesh.normals.chunks_exact(3) {
+                    normals.push(Vector3::new(n[0], n[1], n[2]));
+                }
+            } else {
+                all_normals = false;
+            }
+
             // Extract edges from faces
             // OBJ indices are stored in mesh.indices
             let indices = &mesh.indices;
-            let face_arities = &mesh.face_arities;
+            // tobj leaves face_arities empty when every face is a triangle
+            let face_arities = if mesh.face_arities.is_empty() {
+                vec![3; indices.len() / 3]
+            } else {
+                mesh.face_arities.clone()
+            };
 
             let mut idx = 0;
-            for &arity in face_arities {
+            for arity in face_arities {
                 let arity = arity as usize;
+                faces.push(
+                    indices[idx..idx + arity]
+                        .iter()
+                        .map(|&i| i as usize + vertex_offset)
+                        .collect(),
+                );
                 // Add edges for this face
                 for i in 0..arity {
                     let v1 = indices[idx + i] as usize + vertex_offset;
                     let v2 = indices[idx + (i + 1) % arity] as usize + vertex_offset;
                     // Avoid duplicate edges by only adding if v1 < v2
                     if v1 < v2 {
-                        edges.push((v1, v2));
+                        edges.push(((v1, v2), material));
                     } else if v1 > v2 {
-                        edges.push((v2, v1));
+                        edges.push(((v2, v1), material));
                     }
                 }
                 idx += arity;
@@ -132,14 +185,22 @@ impl Mesh {
             vertex_offset = vertices.len();
         }
 
-        // Remove duplicate edges
-        edges.sort();
-        edges.dedup();
+        // Remove duplicate edges; the sort is stable, so an edge shared
+        // between materials keeps the first one in file order
+        edges.sort_by_key(|(edge, _)| *edge);
+        edges.dedup_by_key(|(edge, _)| *edge);
 
         if edges.is_empty() {
             return Err(MeshError::NoEdges);
         }
 
+        let (edges, edge_materials) = edges.into_iter().unzip();
+
+        // Normals are all or nothing, so they stay parallel to the vertices
+        if !all_normals {
+            normals.clear();
+        }
+
         let name = path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -148,7 +209,11 @@ impl Mesh {
 
         Ok(Self {
             vertices,
+            normals,
             edges,
+            faces,
+            materials,
+            edge_materials,
             name,
         })
     }
@@ -185,7 +250,16 @@ impl Mesh {
             (3, 7),
         ];
 
-        Self::from_data(vertices, edges, "Cube")
+        let faces = vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![1, 2, 6, 5],
+            vec![2, 3, 7, 6],
+            vec![3, 0, 4, 7],
+        ];
+
+        Self::from_data(vertices, edges, "Cube").with_faces(faces)
     }
 
     /// Create a tetrahedron
@@ -200,7 +274,8 @@ impl Mesh {
 
         let edges = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
 
-        Self::from_data(vertices, edges, "Tetrahedron")
+        let faces = triangle_faces(vertices.len(), &edges);
+        Self::from_data(vertices, edges, "Tetrahedron").with_faces(faces)
     }
 
     /// Create an octahedron
@@ -229,7 +304,8 @@ impl Mesh {
             (3, 5),
         ];
 
-        Self::from_data(vertices, edges, "Octahedron")
+        let faces = triangle_faces(vertices.len(), &edges);
+        Self::from_data(vertices, edges, "Octahedron").with_faces(faces)
     }
 
     /// Create an icosahedron
@@ -286,7 +362,183 @@ impl Mesh {
             (10, 11),
         ];
 
-        Self::from_data(vertices, edges, "Icosahedron")
+        let faces = triangle_faces(vertices.len(), &edges);
+        Self::from_data(vertices, edges, "Icosahedron").with_faces(faces)
+    }
+
+    /// Get the material index of an edge, if the mesh is tagged
+    pub fn edge_material(&self, edge: usize) -> Option<usize> {
+        self.edge_materials.get(edge).copied().flatten()
+    }
+
+    /// Get the material name of an edge, if the mesh is tagged
+    pub fn edge_material_name(&self, edge: usize) -> Option<&str> {
+        self.edge_material(edge)
+            .and_then(|m| self.materials.get(m))
+            .map(String::as_str)
+    }
+
+    /// Subdivide the wireframe for denser line work
+    ///
+    /// Each level splits every edge at its midpoint and, for meshes with
+    /// faces, adds a face-center vertex connected to the midpoints of the
+    /// face's edges. Faces are replaced by the resulting quads so further
+    /// levels keep refining. Vertex positions are not smoothed.
+    ///
+    /// Every edge becomes two and every face adds one spoke per side, so a
+    /// closed triangle or quad mesh has about four times as many edges per
+    /// level (a cube goes from 12 to 48). Edge-only wireframes just double.
+    pub fn subdivide(&self, levels: usize) -> Mesh {
+        let mut mesh = self.clone();
+        for _ in 0..levels {
+            mesh = mesh.subdivide_once();
+        }
+        mesh
+    }
+
+    fn subdivide_once(&self) -> Mesh {
+        let mut vertices = self.vertices.clone();
+        let mut normals = self.normals.clone();
+        let has_normals = self.has_normals();
+        let mut edges = Vec::with_capacity(self.edges.len() * 2);
+        let mut edge_materials = Vec::new();
+        let tagged = !self.edge_materials.is_empty();
+        let mut midpoint_materials: HashMap<usize, Option<usize>> = HashMap::new();
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+
+        // New vertices get the blended normal of the ones they sit between
+        let blend = |indices: &[usize]| {
+            let sum = indices
+                .iter()
+                .fold(Vector3::zeros(), |acc, &i| acc + self.normals[i]);
+            sum.try_normalize(f32::EPSILON).unwrap_or(sum)
+        };
+
+        let mut midpoint = |a: usize,
+                            b: usize,
+                            vertices: &mut Vec<Point3<f32>>,
+                            normals: &mut Vec<Vector3<f32>>|
+         -> usize {
+            let key = (a.min(b), a.max(b));
+            *midpoints.entry(key).or_insert_with(|| {
+                vertices.push(nalgebra::center(&vertices[a], &vertices[b]));
+                if has_normals {
+                    normals.push(blend(&[a, b]));
+                }
+                vertices.len() - 1
+            })
+        };
+
+        for (i, &(a, b)) in self.edges.iter().enumerate() {
+            if a >= self.vertices.len() || b >= self.vertices.len() {
+                continue;
+            }
+            let m = midpoint(a, b, &mut vertices, &mut normals);
+            edges.push((a, m));
+            edges.push((m, b));
+            if tagged {
+                let material = self.edge_material(i);
+                edge_materials.extend([material, material]);
+                midpoint_materials.insert(m, material);
+            }
+        }
+
+        let mut faces = Vec::with_capacity(self.faces.len() * 4);
+        for face in &self.faces {
+            let n = face.len();
+            if n < 3 || face.iter().any(|&i| i >= self.vertices.len()) {
+                continue;
+            }
+
+            let sum = face
+                .iter()
+                .fold(Vector3::zeros(), |acc, &i| acc + self.vertices[i].coords);
+            vertices.push(Point3::from(sum / n as f32));
+            if has_normals {
+                normals.push(blend(face));
+            }
+            let center = vertices.len() - 1;
+
+            let mids: Vec<usize> = (0..n)
+                .map(|i| midpoint(face[i], face[(i + 1) % n], &mut vertices, &mut normals))
+                .collect();
+            for i in 0..n {
+                edges.push((center, mids[i]));
+                if tagged {
+                    // Spokes take the material of the edge they end on
+                    edge_materials.push(midpoint_materials.get(&mids[i]).copied().flatten());
+                }
+                faces.push(vec![face[i], mids[i], center, mids[(i + n - 1) % n]]);
+            }
+        }
+
+        Mesh {
+            vertices,
+            normals,
+            edges,
+            faces,
+            materials: self.materials.clone(),
+            edge_materials,
+            name: self.name.clone(),
+        }
+    }
+
+    /// Whether every vertex has a normal
+    pub fn has_normals(&self) -> bool {
+        !self.normals.is_empty() && self.normals.len() == self.vertices.len()
+    }
+
+    /// Outward normal of a face (not normalized), if it has any area
+    ///
+    /// Uses the averaged vertex normals when the mesh has them, e.g. from
+    /// OBJ `vn` lines, as they follow the modeller's intent better than the
+    /// winding of a coarse or non-planar face. Otherwise the normal comes
+    /// from the winding (Newell's method).
+    pub fn face_normal(&self, face: usize) -> Option<Vector3<f32>> {
+        let face = self.faces.get(face)?;
+        if face.len() < 3 || face.iter().any(|&i| i >= self.vertices.len()) {
+            return None;
+        }
+        let normal = if self.has_normals() {
+            face.iter()
+                .fold(Vector3::zeros(), |acc, &i| acc + self.normals[i])
+        } else {
+            (0..face.len()).fold(Vector3::zeros(), |acc, i| {
+                let a = self.vertices[face[i]].coords;
+                let b = self.vertices[face[(i + 1) % face.len()]].coords;
+                acc + a.cross(&b)
+            })
+        };
+        (normal.norm_squared() > 0.0).then_some(normal)
+    }
+
+    /// Whether a face points away from a viewer at `eye`
+    ///
+    /// Faces without area count as front-facing, so they're never culled.
+    pub fn is_back_face(&self, face: usize, eye: &Point3<f32>) -> bool {
+        match (self.face_normal(face), self.faces.get(face)) {
+            (Some(normal), Some(corners)) => normal.dot(&(eye - self.vertices[corners[0]])) < 0.0,
+            _ => false,
+        }
+    }
+
+    /// Faces bordering each edge, parallel to `edges`
+    ///
+    /// An edge borders a face when its two vertices are neighbours around
+    /// the face's loop. Edges of edge-only meshes border no faces.
+    pub fn edge_faces(&self) -> Vec<Vec<usize>> {
+        let key = |a: usize, b: usize| (a.min(b), a.max(b));
+        let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            for i in 0..face.len() {
+                let entry = by_edge.entry(key(face[i], face[(i + 1) % face.len()]));
+                entry.or_default().push(f);
+            }
+        }
+        self.edges
+            .iter()
+            .map(|&(a, b)| by_edge.get(&key(a, b)).cloned().unwrap_or_default())
+            .collect()
     }
 
     /// Get bounding box of mesh
@@ -333,6 +585,134 @@ impl Mesh {
             v.z = (v.z - center.z) * scale;
         }
     }
+
+    /// Merge vertices closer than `epsilon` and drop the edges that become
+    /// zero-length or duplicated as a result
+    ///
+    /// Redundant edges are traced twice, wasting beam time and drawing
+    /// brighter than their neighbours. Faces are remapped the same way and
+    /// dropped if they collapse below three distinct vertices or repeat
+    /// the vertex set of an earlier face. Returns the number of edges
+    /// removed.
+    pub fn clean(&mut self, epsilon: f32) -> usize {
+        let epsilon = epsilon.max(f32::MIN_POSITIVE);
+        let cell = |v: &Point3<f32>| {
+            (
+                (v.x / epsilon).floor() as i64,
+                (v.y / epsilon).floor() as i64,
+                (v.z / epsilon).floor() as i64,
+            )
+        };
+
+        // Map each vertex to the first kept vertex within epsilon, looking
+        // in the neighbouring grid cells so pairs straddling a cell edge merge
+        let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        let mut vertices: Vec<Point3<f32>> = Vec::new();
+        let mut normals = Vec::new();
+        let has_normals = self.has_normals();
+        let mut remap = Vec::with_capacity(self.vertices.len());
+        for (i, v) in self.vertices.iter().enumerate() {
+            let (cx, cy, cz) = cell(v);
+            let existing = (-1..=1)
+                .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+                .filter_map(|(dx, dy, dz)| grid.get(&(cx + dx, cy + dy, cz + dz)))
+                .flatten()
+                .copied()
+                .find(|&i| (vertices[i] - v).norm() <= epsilon);
+
+            let index = existing.unwrap_or_else(|| {
+                vertices.push(*v);
+                if has_normals {
+                    // Merged vertices keep the first one's normal
+                    normals.push(self.normals[i]);
+                }
+                grid.entry((cx, cy, cz))
+                    .or_default()
+                    .push(vertices.len() - 1);
+                vertices.len() - 1
+            });
+            remap.push(index);
+        }
+
+        let tagged = !self.edge_materials.is_empty();
+        let mut edges: Vec<((usize, usize), Option<usize>)> = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(i, &(a, b))| {
+                let (a, b) = (remap[a], remap[b]);
+                ((a.min(b), a.max(b)), self.edge_material(i))
+            })
+            .filter(|((a, b), _)| a != b)
+            .collect();
+        // Duplicates keep the first edge's material, as in `from_obj`
+        edges.sort_by_key(|(edge, _)| *edge);
+        edges.dedup_by_key(|(edge, _)| *edge);
+
+        // Duplicate faces keep the first one's winding
+        let mut seen_faces = HashSet::new();
+        let faces = self
+            .faces
+            .iter()
+            .filter_map(|face| {
+                let mut face: Vec<usize> = face.iter().map(|&i| remap[i]).collect();
+                face.dedup();
+                if face.len() > 1 && face.first() == face.last() {
+                    face.pop();
+                }
+                let mut key = face.clone();
+                key.sort_unstable();
+                (face.len() >= 3 && seen_faces.insert(key)).then_some(face)
+            })
+            .collect();
+
+        let removed = self.edges.len() - edges.len();
+        let (edges, edge_materials): (Vec<_>, Vec<_>) = edges.into_iter().unzip();
+        self.vertices = vertices;
+        self.normals = normals;
+        self.edges = edges;
+        self.edge_materials = if tagged { edge_materials } else { Vec::new() };
+        self.faces = faces;
+        removed
+    }
+
+    /// Reorder and flip edges so each one starts where the last ended
+    /// wherever the wireframe allows, cutting the stray lines between them
+    pub fn optimize_edge_order(&mut self) {
+        let vertices: Vec<[f32; 3]> = self.vertices.iter().map(|v| [v.x, v.y, v.z]).collect();
+        let order = optimize_edges(&self.edges, &vertices);
+        let tagged = !self.edge_materials.is_empty();
+        let (edges, edge_materials) = order
+            .into_iter()
+            .map(|(i, reverse)| {
+                let (a, b) = self.edges[i];
+                let edge = if reverse { (b, a) } else { (a, b) };
+                (edge, self.edge_material(i))
+            })
+            .unzip();
+        self.edges = edges;
+        self.edge_materials = if tagged { edge_materials } else { Vec::new() };
+    }
+}
+
+/// Find the triangular faces of an edge-only mesh (every 3-cycle)
+///
+/// Only meaningful for meshes built purely from triangles, like the
+/// tetrahedron, octahedron and icosahedron primitives.
+fn triangle_faces(vertex_count: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let adjacent: HashSet<(usize, usize)> =
+        edges.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+
+    let mut faces = Vec::new();
+    for &(a, b) in &adjacent {
+        for c in (b + 1)..vertex_count {
+            if adjacent.contains(&(a, c)) && adjacent.contains(&(b, c)) {
+                faces.push(vec![a, b, c]);
+            }
+        }
+    }
+    faces.sort();
+    faces
 }
 
 /// Camera for 3D viewing
@@ -429,24 +809,66 @@ impl Camera {
 /// Options for 3D mesh rendering
 #[derive(Clone, Debug)]
 pub struct Mesh3DOptions {
-    /// Points per edge for sampling
+    /// Points per edge for sampling (when not proportional)
     pub edge_samples: usize,
+    /// Sample edges in proportion to their projected length
+    pub proportional: bool,
+    /// Samples per unit of projected edge length (when proportional)
+    pub edge_density: f32,
     /// Camera rotation speed (radians per frame)
     pub auto_rotate_speed: f32,
     /// Whether to auto-rotate
     pub auto_rotate: bool,
+    /// Wireframe subdivision levels applied to the mesh (each level roughly
+    /// quadruples the edge count of a closed mesh)
+    pub subdivision: usize,
+    /// Merge near-coincident vertices and drop redundant edges
+    pub clean: bool,
+    /// Most projected points per frame (0 = no limit)
+    ///
+    /// Reprojecting a mesh with tens of thousands of edges every frame
+    /// stalls the UI; edges past the budget are left out, with a warning.
+    pub max_points: usize,
+    /// Chain edges end to end to cut the jumps between them
+    pub optimize_order: bool,
+    /// Leave out edges whose faces all point away from the camera
+    ///
+    /// Only meshes with faces are affected; bare wireframes draw in full.
+    pub hide_back_faces: bool,
 }
 
 impl Default for Mesh3DOptions {
     fn default() -> Self {
         Self {
             edge_samples: 2,
+            proportional: true,
+            edge_density: 16.0,
             auto_rotate_speed: 0.01,
             auto_rotate: true,
+            subdivision: 0,
+            clean: true,
+            max_points: DEFAULT_MAX_POINTS,
+            optimize_order: false,
+            hide_back_faces: false,
         }
     }
 }
 
+/// Default for `Mesh3DOptions::max_points`
+pub const DEFAULT_MAX_POINTS: usize = 50_000;
+
+/// Vertex merge distance for mesh cleanup, as a fraction of the mesh size
+const CLEAN_TOLERANCE: f32 = 1e-4;
+
+/// Samples needed per projected edge for it to read as a straight line
+const SAMPLES_PER_EDGE: usize = 16;
+
+/// Fewest samples a proportionally sampled edge can receive
+const MIN_EDGE_SAMPLES: usize = 1;
+
+/// Most samples a proportionally sampled edge can receive
+const MAX_EDGE_SAMPLES: usize = 64;
+
 /// A 3D mesh shape for oscilloscope rendering
 pub struct Mesh3DShape {
     /// The 3D mesh
@@ -461,12 +883,28 @@ pub struct Mesh3DShape {
     path: Path,
     /// Cached points for Shape trait
     points: Vec<(f32, f32)>,
+    /// Edges left out of the last projection to stay within `max_points`
+    skipped_edges: usize,
+    /// Faces bordering each mesh edge, for back-face culling
+    edge_faces: Vec<Vec<usize>>,
 }
 
 impl Mesh3DShape {
     /// Create a new 3D mesh shape
-    pub fn new(mesh: Mesh, options: Mesh3DOptions) -> Self {
+    pub fn new(mut mesh: Mesh, options: Mesh3DOptions) -> Self {
         let camera = Camera::default();
+        if options.clean {
+            let (min, max) = mesh.bounds();
+            let removed = mesh.clean((max - min).norm() * CLEAN_TOLERANCE);
+            if removed > 0 {
+                log::debug!("Cleaned {}: removed {} redundant edges", mesh.name, removed);
+            }
+        }
+        let mut mesh = mesh.subdivide(options.subdivision);
+        if options.optimize_order {
+            mesh.optimize_edge_order();
+        }
+        let edge_faces = mesh.edge_faces();
         let mut shape = Self {
             mesh,
             camera,
@@ -474,6 +912,8 @@ impl Mesh3DShape {
             rotation: 0.0,
             path: Path::with_options(Vec::new(), false, "mesh".to_string()),
             points: Vec::new(),
+            skipped_edges: 0,
+            edge_faces,
         };
         shape.update_projection();
         shape
@@ -567,16 +1007,43 @@ impl Mesh3DShape {
             })
             .collect();
 
-        // Build path from edges
+        // Build path from edges, up to the point budget
+        let budget = match self.options.max_points {
+            0 => usize::MAX,
+            max => max,
+        };
+        // Edges bordering only faces that point away are hidden
+        let hidden: Vec<bool> = if self.options.hide_back_faces {
+            let eye = self.camera.position;
+            let back: Vec<bool> = (0..self.mesh.faces.len())
+                .map(|f| self.mesh.is_back_face(f, &eye))
+                .collect();
+            self.edge_faces
+                .iter()
+                .map(|faces| !faces.is_empty() && faces.iter().all(|&f| back[f]))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         let mut points = Vec::new();
-        for &(i1, i2) in &self.mesh.edges {
+        let mut skipped_edges = 0;
+        for (e, &(i1, i2)) in self.mesh.edges.iter().enumerate() {
+            if hidden.get(e).copied().unwrap_or(false) {
+                continue;
+            }
             if i1 < projected.len() && i2 < projected.len() {
                 let p1 = projected[i1];
                 let p2 = projected[i2];
 
                 // Sample points along the edge
-                for i in 0..=self.options.edge_samples {
-                    let t = i as f32 / self.options.edge_samples as f32;
+                let samples = self.edge_sample_count(p1, p2);
+                if points.len() + samples + 1 > budget {
+                    skipped_edges += 1;
+                    continue;
+                }
+                for i in 0..=samples {
+                    let t = i as f32 / samples as f32;
                     let x = p1.0 + t * (p2.0 - p1.0);
                     let y = p1.1 + t * (p2.1 - p1.1);
                     // Clamp to visible range
@@ -585,10 +1052,37 @@ impl Mesh3DShape {
             }
         }
 
+        if skipped_edges > 0 && self.skipped_edges == 0 {
+            log::warn!(
+                "{}: {} edges past the {} point budget were skipped",
+                self.mesh.name,
+                skipped_edges,
+                budget
+            );
+        }
+        self.skipped_edges = skipped_edges;
         self.points = points.clone();
         self.path = Path::with_options(points, false, self.mesh.name.clone());
     }
 
+    /// Edges left out of the current projection to stay within `max_points`
+    pub fn skipped_edges(&self) -> usize {
+        self.skipped_edges
+    }
+
+    /// Number of segments to sample a projected edge with
+    ///
+    /// Proportional sampling keeps the beam speed uniform across the
+    /// wireframe, so edges that grow as the camera zooms in stay smooth.
+    fn edge_sample_count(&self, p1: (f32, f32), p2: (f32, f32)) -> usize {
+        if !self.options.proportional {
+            return self.options.edge_samples.max(1);
+        }
+        let length = ((p2.0 - p1.0).powi(2) + (p2.1 - p1.1).powi(2)).sqrt();
+        ((length * self.options.edge_density).round() as usize)
+            .clamp(MIN_EDGE_SAMPLES, MAX_EDGE_SAMPLES)
+    }
+
     /// Get the mesh name
     pub fn name(&self) -> &str {
         &self.mesh.name
@@ -621,12 +1115,44 @@ impl Shape for Mesh3DShape {
     fn is_closed(&self) -> bool {
         false
     }
+
+    fn suggested_samples(&self) -> Option<usize> {
+        let wanted = self.mesh.edges.len() * SAMPLES_PER_EDGE;
+        Some(match self.options.max_points {
+            0 => wanted,
+            max => wanted.min(max),
+        })
+    }
+
+    fn warning(&self) -> Option<String> {
+        (self.skipped_edges > 0).then(|| {
+            format!(
+                "Mesh \"{}\" is over the point budget - {} of {} edges not drawn",
+                self.mesh.name,
+                self.skipped_edges,
+                self.mesh.edges.len()
+            )
+        })
+    }
+
+    fn has_points(&self) -> bool {
+        self.path.has_points()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Load an OBJ file from tests/fixtures
+    fn load_fixture(name: &str) -> Mesh {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name);
+        Mesh::from_obj(path).unwrap()
+    }
+
     #[test]
     fn test_cube() {
         let mesh = Mesh::cube();
@@ -634,6 +1160,26 @@ mod tests {
         assert_eq!(mesh.edges.len(), 12);
     }
 
+    #[test]
+    fn test_proportional_edge_samples() {
+        let shape = Mesh3DShape::cube(Mesh3DOptions {
+            auto_rotate: false,
+            ..Default::default()
+        });
+        let short = shape.edge_sample_count((0.0, 0.0), (0.1, 0.0));
+        let long = shape.edge_sample_count((0.0, 0.0), (1.0, 0.0));
+        assert!(long > short);
+
+        let flat = Mesh3DShape::cube(Mesh3DOptions {
+            proportional: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            flat.edge_sample_count((0.0, 0.0), (0.1, 0.0)),
+            flat.edge_sample_count((0.0, 0.0), (1.0, 0.0))
+        );
+    }
+
     #[test]
     fn test_tetrahedron() {
         let mesh = Mesh::tetrahedron();
@@ -655,6 +1201,92 @@ mod tests {
         assert_eq!(mesh.edges.len(), 30);
     }
 
+    #[test]
+    fn test_subdivide_cube() {
+        let cube = Mesh::cube();
+        let sub = cube.subdivide(1);
+
+        // 12 edges split in two plus 4 spokes for each of the 6 faces
+        assert_eq!(sub.edges.len(), 2 * 12 + 4 * 6);
+        assert_eq!(sub.vertices.len(), 8 + 12 + 6);
+        assert_eq!(sub.faces.len(), 24);
+
+        // Zero levels is a no-op
+        assert_eq!(cube.subdivide(0).edges.len(), cube.edges.len());
+    }
+
+    #[test]
+    fn test_obj_edge_materials() {
+        // Two quads side by side, one red and one blue
+        let mesh = load_fixture("two_materials.obj");
+
+        assert_eq!(mesh.materials, vec!["red", "blue"]);
+        assert_eq!(mesh.edge_materials.len(), mesh.edges.len());
+
+        let tags: HashSet<usize> = (0..mesh.edges.len())
+            .filter_map(|i| mesh.edge_material(i))
+            .collect();
+        assert_eq!(tags.len(), 2);
+
+        // Tags survive subdivision
+        let sub = mesh.subdivide(1);
+        assert_eq!(sub.edge_materials.len(), sub.edges.len());
+        assert!(sub.edge_materials.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn test_obj_vertex_normals() {
+        // A quad in the XY plane whose normals say it faces +Z, wound so
+        // its geometry alone would face -Z
+        let mesh = load_fixture("quad_normals.obj");
+
+        assert_eq!(mesh.normals.len(), mesh.vertices.len());
+        assert!(mesh.has_normals());
+        let front = Point3::new(0.5, 0.5, 2.0);
+        assert!(!mesh.is_back_face(0, &front));
+        assert!(mesh.is_back_face(0, &Point3::new(0.5, 0.5, -2.0)));
+
+        // Without normals the winding decides
+        let mut bare = mesh.clone();
+        bare.normals.clear();
+        assert!(bare.is_back_face(0, &front));
+
+        // Normals stay parallel to the vertices through subdivision
+        let sub = mesh.subdivide(1);
+        assert_eq!(sub.normals.len(), sub.vertices.len());
+    }
+
+    #[test]
+    fn test_obj_all_triangles() {
+        // tobj reports no face arities when every face is a triangle
+        let mesh = load_fixture("tetrahedron.obj");
+
+        assert_eq!(mesh.faces.len(), 4);
+        assert!(mesh.faces.iter().all(|f| f.len() == 3));
+        assert_eq!(mesh.edges.len(), 6);
+    }
+
+    #[test]
+    fn test_clean_removes_degenerate_and_duplicate_edges() {
+        // Vertex 4 sits on top of vertex 3, so the last two faces repeat
+        // the first one's edges plus a zero-length edge
+        let mut mesh = load_fixture("duplicate_faces.obj");
+        assert_eq!(mesh.edges.len(), 6);
+
+        assert_eq!(mesh.clean(1e-4), 3);
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.edges, vec![(0, 1), (0, 2), (1, 2)]);
+        // The second face merges into a copy of the first; the third collapses
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_primitive_triangle_faces() {
+        assert_eq!(Mesh::tetrahedron().faces.len(), 4);
+        assert_eq!(Mesh::octahedron().faces.len(), 8);
+        assert_eq!(Mesh::icosahedron().faces.len(), 20);
+    }
+
     #[test]
     fn test_mesh_normalize() {
         let mut mesh = Mesh::cube();
@@ -684,4 +1316,86 @@ mod tests {
         let (x, y) = shape.sample(0.5);
         assert!(x.is_finite() && y.is_finite());
     }
+
+    #[test]
+    fn test_mesh3d_suggests_samples_per_edge() {
+        let mesh = Mesh3DShape::icosahedron(Mesh3DOptions::default());
+        let circle = crate::shapes::Circle::new(0.5);
+
+        // The mesh asks for points per edge; the circle leaves it to the frequency
+        let mesh_hint = mesh
+            .suggested_samples()
+            .expect("mesh should suggest a count");
+        assert_eq!(mesh_hint, mesh.edge_count() * SAMPLES_PER_EDGE);
+        assert_eq!(circle.suggested_samples(), None);
+    }
+
+    #[test]
+    fn test_dense_mesh_respects_point_budget() {
+        // A 100 x 100 grid: about 20,000 edges
+        let n = 100;
+        let mut mesh = Mesh::new("grid");
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, y) = (i as f32 / n as f32 - 0.5, j as f32 / n as f32 - 0.5);
+                mesh.vertices.push(Point3::new(x, y, 0.0));
+            }
+        }
+        let index = |i: usize, j: usize| j * (n + 1) + i;
+        for j in 0..=n {
+            for i in 0..n {
+                mesh.edges.push((index(i, j), index(i + 1, j)));
+                mesh.edges.push((index(j, i), index(j, i + 1)));
+            }
+        }
+        let options = Mesh3DOptions {
+            auto_rotate: false,
+            clean: false,
+            max_points: 2000,
+            ..Default::default()
+        };
+        let shape = Mesh3DShape::new(mesh, options);
+
+        assert!(shape.points.len() <= 2000);
+        assert!(shape.skipped_edges() > 0);
+        assert!(shape.suggested_samples().unwrap() <= 2000);
+
+        let mut engine = crate::audio::AudioEngine::new(crate::audio::SampleBuffer::new(16));
+        engine.set_shape(&shape);
+        assert!(engine.shape_warning().is_some_and(|w| w.contains("budget")));
+
+        // A small mesh fits
+        assert!(Mesh3DShape::cube(Mesh3DOptions::default())
+            .warning()
+            .is_none());
+    }
+
+    #[test]
+    fn test_hide_back_faces() {
+        let mut options = Mesh3DOptions {
+            auto_rotate: false,
+            proportional: false,
+            edge_samples: 2,
+            ..Default::default()
+        };
+        // Every cube edge borders two faces
+        let cube = Mesh::cube();
+        assert!(cube.edge_faces().iter().all(|faces| faces.len() == 2));
+        assert_eq!(
+            Mesh3DShape::new(cube.clone(), options.clone()).points.len(),
+            12 * 3
+        );
+
+        // Head on, only the front face's four edges remain
+        options.hide_back_faces = true;
+        assert_eq!(
+            Mesh3DShape::new(cube.clone(), options.clone()).points.len(),
+            4 * 3
+        );
+
+        // A bare wireframe has no faces to cull by
+        let mut bare = cube;
+        bare.faces.clear();
+        assert_eq!(Mesh3DShape::new(bare, options).points.len(), 12 * 3);
+    }
 }
@@ -3,34 +3,55 @@
 //! This module provides:
 //! - `Shape` trait for abstracting over different shape types
 //! - Primitive shapes: Circle, Line, Rectangle, etc.
-//! - Path type for arbitrary point sequences
+//! - Path type for arbitrary point sequences (including CSV import)
 //! - Scene type for composing multiple shapes
+//! - Morph type for animating between two shapes
+//! - Easing curves for shaping transitions
 //! - SVG import for loading vector graphics
 //! - Image tracing for converting raster images to paths
 //! - Text rendering for converting text to paths
+//! - Adaptive Bézier flattening shared by SVG and text import
 //! - 3D mesh rendering with wireframe projection
+//! - Beam path optimization for figures made of many subpaths
+//! - Fill or fit normalization of imported coordinates
+//! - Golden snapshots of shape geometry for regression tests
 
+mod bezier;
+mod easing;
 mod image;
 mod mesh3d;
+mod morph;
+mod normalize;
+mod optimize;
 mod path;
 mod primitives;
 mod scene;
+#[cfg(test)]
+mod snapshot;
+mod stroke_font;
 mod svg;
 mod text;
 mod traits;
 
+pub use easing::Easing;
 #[allow(unused_imports)]
-pub use image::{ImageError, ImageOptions, ImageShape};
+pub use image::{ImageError, ImageMode, ImageOptions, ImageShape};
 #[allow(unused_imports)]
 pub use mesh3d::{Camera, Mesh, Mesh3DOptions, Mesh3DShape, MeshError};
-pub use path::Path;
+pub use morph::MorphShape;
+#[allow(unused_imports)]
+pub use normalize::{Fit, NormalizeMode};
+#[allow(unused_imports)]
+pub use optimize::{jump_length, optimize_edges, optimize_order};
+#[allow(unused_imports)]
+pub use path::{CsvOptions, HarmonographParams, Path, PathError, Pendulum};
 pub use primitives::{Circle, Line, Polygon, Rectangle};
 #[allow(unused_imports)]
-pub use scene::{Scene, SceneShape};
+pub use scene::{Scene, ScenePlayback, SceneShape};
 #[allow(unused_imports)]
 pub use svg::{SvgError, SvgOptions, SvgShape};
 #[allow(unused_imports)]
-pub use text::{TextError, TextOptions, TextShape};
+pub use text::{FontCache, TextError, TextOptions, TextShape, TextStyle};
 pub use traits::Shape;
 
 use crate::audio::XYSample;
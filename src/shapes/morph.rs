@@ -0,0 +1,162 @@
+//! Morph - time-driven interpolation between two shapes
+//!
+//! Both endpoint shapes are resampled to the same number of points, so
+//! point `i` of one corresponds to point `i` of the other. The blend
+//! factor linearly interpolates each pair, letting a circle breathe into
+//! a star and back; an easing curve shapes how the blend moves over time.
+
+use super::easing::Easing;
+use super::path::Path;
+use super::traits::Shape;
+
+/// A shape that blends between two resampled endpoint shapes
+#[derive(Clone)]
+pub struct MorphShape {
+    /// Points of the starting shape
+    from: Vec<(f32, f32)>,
+    /// Points of the target shape (same count as `from`)
+    to: Vec<(f32, f32)>,
+    /// Current blend (0 = `from`, 1 = `to`)
+    blend: f32,
+    /// Curve the timed blend follows between the endpoints
+    easing: Easing,
+    /// Whether both endpoints are closed
+    closed: bool,
+    /// Blended path for rendering
+    path: Path,
+    /// Display name
+    name: String,
+}
+
+impl MorphShape {
+    /// Create a morph between two shapes, resampling both to `num_points`
+    pub fn new(from: &dyn Shape, to: &dyn Shape, num_points: usize) -> Self {
+        let num_points = num_points.max(2);
+        let name = format!("{} → {}", from.name(), to.name());
+        let closed = from.is_closed() && to.is_closed();
+
+        let mut morph = Self {
+            from: resample(from, num_points),
+            to: resample(to, num_points),
+            blend: 0.0,
+            easing: Easing::default(),
+            closed,
+            path: Path::new(Vec::new()),
+            name,
+        };
+        morph.rebuild();
+        morph
+    }
+
+    /// Current blend factor
+    pub fn blend(&self) -> f32 {
+        self.blend
+    }
+
+    /// Set the blend factor (0 = start shape, 1 = end shape)
+    pub fn set_blend(&mut self, blend: f32) {
+        self.blend = blend.clamp(0.0, 1.0);
+        self.rebuild();
+    }
+
+    /// Curve the timed blend follows
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    /// Set the curve the timed blend follows
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    /// Set the blend from elapsed time, cycling there and back every `period` seconds
+    ///
+    /// Each half of the cycle follows the easing curve, so the default sine
+    /// curve eases in and out at both endpoints.
+    pub fn set_time(&mut self, seconds: f32, period: f32) {
+        let phase = if period > 0.0 { seconds / period } else { 0.0 };
+        let there_and_back = 1.0 - (2.0 * phase.rem_euclid(1.0) - 1.0).abs();
+        self.set_blend(self.easing.apply(there_and_back));
+    }
+
+    /// The blended points
+    pub fn points(&self) -> &[(f32, f32)] {
+        self.path.points()
+    }
+
+    fn rebuild(&mut self) {
+        let b = self.blend;
+        let points = self
+            .from
+            .iter()
+            .zip(&self.to)
+            .map(|(&(x1, y1), &(x2, y2))| (x1 + (x2 - x1) * b, y1 + (y2 - y1) * b))
+            .collect();
+        self.path = Path::with_options(points, self.closed, self.name.clone());
+    }
+}
+
+/// Sample a shape at `num_points` evenly spaced parameters
+fn resample(shape: &dyn Shape, num_points: usize) -> Vec<(f32, f32)> {
+    (0..num_points)
+        .map(|i| shape.sample(i as f32 / num_points as f32))
+        .collect()
+}
+
+impl Shape for MorphShape {
+    fn sample(&self, t: f32) -> (f32, f32) {
+        self.path.sample(t)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn length(&self) -> f32 {
+        self.path.length()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn suggested_samples(&self) -> Option<usize> {
+        Some(self.from.len())
+    }
+
+    fn has_points(&self) -> bool {
+        self.path.has_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Polygon};
+
+    #[test]
+    fn test_midpoint_is_average() {
+        let circle = Circle::new(0.8);
+        let star = Polygon::star(5, 0.8, 0.3);
+        let mut morph = MorphShape::new(&circle, &star, 100);
+        morph.set_blend(0.5);
+
+        let a = resample(&circle, 100);
+        let b = resample(&star, 100);
+        for (i, &(x, y)) in morph.points().iter().enumerate() {
+            assert!((x - (a[i].0 + b[i].0) / 2.0).abs() < 1e-5);
+            assert!((y - (a[i].1 + b[i].1) / 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_time_cycles_back() {
+        let mut morph = MorphShape::new(&Circle::new(0.5), &Circle::new(1.0), 16);
+        morph.set_time(0.0, 4.0);
+        assert!(morph.blend() < 1e-5);
+        morph.set_time(2.0, 4.0);
+        assert!((morph.blend() - 1.0).abs() < 1e-5);
+        morph.set_time(4.0, 4.0);
+        assert!(morph.blend() < 1e-5);
+    }
+}
@@ -0,0 +1,98 @@
+//! Normalizing imported coordinates into the [-1, 1] drawing square
+//!
+//! Importers first map their source frame (SVG viewbox, image, font
+//! outline) onto the square. `Fill` then clamps whatever lands outside,
+//! which flattens any part of a figure that overhangs the frame against
+//! the edge. `Fit` instead scales the figure's own bounding box to sit
+//! inside the square, so no point is ever clamped.
+
+/// How imported coordinates are brought into [-1, 1]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum NormalizeMode {
+    /// Scale the source frame to the square and clamp any overhang
+    #[default]
+    Fill,
+    /// Center the figure and scale it to fit inside the square
+    Fit,
+}
+
+impl NormalizeMode {
+    /// Get all modes
+    pub fn all() -> &'static [NormalizeMode] {
+        &[NormalizeMode::Fill, NormalizeMode::Fit]
+    }
+
+    /// Get the name of this mode
+    pub fn name(&self) -> &'static str {
+        match self {
+            NormalizeMode::Fill => "Fill and clamp",
+            NormalizeMode::Fit => "Fit inside",
+        }
+    }
+}
+
+/// Mapping of a figure's bounding box onto the [-1, 1] square
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Fit {
+    center: (f32, f32),
+    /// Half the scaled extent along each axis
+    half: (f32, f32),
+}
+
+impl Fit {
+    /// Fit the bounding box of `points`, keeping proportions unless
+    /// `preserve_aspect` is off; None if the points have no extent
+    pub fn new(points: &[(f32, f32)], preserve_aspect: bool) -> Option<Self> {
+        let (min_x, max_x, min_y, max_y) = points.iter().fold(
+            (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+            |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+        );
+        let (width, height) = (max_x - min_x, max_y - min_y);
+        let scale = width.max(height);
+        if scale.is_nan() || scale <= 0.0 {
+            return None;
+        }
+
+        // A flat axis can't be stretched, so it falls back to the shared scale
+        let (scale_x, scale_y) = if preserve_aspect {
+            (scale, scale)
+        } else {
+            (
+                if width > 0.0 { width } else { scale },
+                if height > 0.0 { height } else { scale },
+            )
+        };
+        Some(Self {
+            center: ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0),
+            half: (scale_x / 2.0, scale_y / 2.0),
+        })
+    }
+
+    /// Map a point into the square
+    pub fn apply(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        // Only ever trims rounding error at the edges
+        (
+            ((x - self.center.0) / self.half.0).clamp(-1.0, 1.0),
+            ((y - self.center.1) / self.half.1).clamp(-1.0, 1.0),
+        )
+    }
+}
+
+/// Bring points already mapped from their source frame into the square
+pub fn normalize(points: &mut [(f32, f32)], mode: NormalizeMode, preserve_aspect: bool) {
+    let fit = match mode {
+        NormalizeMode::Fill => None,
+        NormalizeMode::Fit => Fit::new(points, preserve_aspect),
+    };
+    for point in points.iter_mut() {
+        *point = match fit {
+            Some(fit) => fit.apply(*point),
+            None => clamp_point(*point),
+        };
+    }
+}
+
+/// Clamp a point into the square
+pub fn clamp_point((x, y): (f32, f32)) -> (f32, f32) {
+    (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0))
+}
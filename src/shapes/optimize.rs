@@ -0,0 +1,399 @@
+//! Beam path optimization for figures made of many subpaths
+//!
+//! Each jump between subpaths costs beam time and draws a stray line unless
+//! it's blanked. Treating the subpaths as edges of a graph between their
+//! endpoints, an Euler circuit traces every edge exactly once. A closed
+//! subpath starts and ends at the same point, so it is a loop at its start
+//! vertex and can be spliced in wherever the trace passes that point. Where
+//! the graph has odd-degree vertices, they are paired greedily by distance
+//! and each pair joined by a jump: a cheap stand-in for the minimum matching
+//! of the Chinese postman problem. The separate parts are then visited
+//! nearest first.
+//!
+//! Image traces are a single nearest-neighbour point chain with no contour
+//! structure, so there is nothing here to reorder for them.
+
+use std::collections::HashMap;
+
+/// Endpoints closer than this are treated as the same vertex
+const JOIN_TOLERANCE: f32 = 1e-3;
+
+/// An edge of the traversal graph
+#[derive(Clone, Copy)]
+struct Edge {
+    from: usize,
+    to: usize,
+    /// Index of the subpath, or None for a jump added to pair odd vertices
+    subpath: Option<usize>,
+}
+
+/// A step along an edge, and whether it runs from `from` to `to`
+type Step = (usize, bool);
+
+/// Order and orient `subpaths` to keep the jumps between them short
+///
+/// Each subpath comes with whether it is closed. Returns each non-empty
+/// subpath's index once, with `true` where it should be traced in reverse;
+/// closed subpaths are never reversed, so they still start at their first
+/// point.
+pub fn optimize_order(subpaths: &[(&[(f32, f32)], bool)]) -> Vec<(usize, bool)> {
+    let mut graph = Graph::default();
+    for (i, &(points, closed)) in subpaths.iter().enumerate() {
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            let from = graph.vertex(first);
+            let to = if closed { from } else { graph.vertex(last) };
+            graph.add_edge(from, to, Some(i));
+        }
+    }
+    graph.traversal()
+}
+
+/// Order and orient the edges of a wireframe to keep the jumps short
+///
+/// `edges` index into `vertices`, which are already shared, so no endpoints
+/// are merged. Returns each edge's index once, with `true` where it should
+/// run from its second vertex to its first.
+pub fn optimize_edges(edges: &[(usize, usize)], vertices: &[[f32; 3]]) -> Vec<(usize, bool)> {
+    let mut graph = Graph {
+        positions: vertices.to_vec(),
+        adjacent: vec![Vec::new(); vertices.len()],
+        ..Default::default()
+    };
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        if a < vertices.len() && b < vertices.len() {
+            graph.add_edge(a, b, Some(i));
+        }
+    }
+    graph.traversal()
+}
+
+/// Total length of the jumps between consecutive subpaths
+pub fn jump_length(subpaths: &[Vec<(f32, f32)>]) -> f32 {
+    subpaths
+        .windows(2)
+        .filter_map(|pair| Some(distance(*pair[0].last()?, *pair[1].first()?)))
+        .sum()
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+fn distance3(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let [dx, dy, dz] = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// Undirected multigraph of subpath endpoints
+#[derive(Default)]
+struct Graph {
+    /// Vertex positions; flat figures leave z at zero
+    positions: Vec<[f32; 3]>,
+    /// Vertices by tolerance-sized grid cell, for merging endpoints
+    grid: HashMap<(i64, i64), Vec<usize>>,
+    edges: Vec<Edge>,
+    /// Edge indices at each vertex (self-loops appear twice)
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// Vertex at `point`, merging with an existing one within tolerance
+    fn vertex(&mut self, point: (f32, f32)) -> usize {
+        let position = [point.0, point.1, 0.0];
+        let cell = |v: f32| (v / JOIN_TOLERANCE).floor() as i64;
+        let (cx, cy) = (cell(point.0), cell(point.1));
+        let existing = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (cx + dx, cy + dy)))
+            .filter_map(|key| self.grid.get(&key))
+            .flatten()
+            .copied()
+            .find(|&v| distance3(self.positions[v], position) <= JOIN_TOLERANCE);
+        existing.unwrap_or_else(|| {
+            self.positions.push(position);
+            self.adjacent.push(Vec::new());
+            let v = self.positions.len() - 1;
+            self.grid.entry((cx, cy)).or_default().push(v);
+            v
+        })
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, subpath: Option<usize>) {
+        let e = self.edges.len();
+        self.edges.push(Edge { from, to, subpath });
+        self.adjacent[from].push(e);
+        self.adjacent[to].push(e);
+    }
+
+    fn edge_length(&self, e: usize) -> f32 {
+        let edge = self.edges[e];
+        distance3(self.positions[edge.from], self.positions[edge.to])
+    }
+
+    fn step_start(&self, (e, forward): Step) -> usize {
+        let edge = self.edges[e];
+        if forward {
+            edge.from
+        } else {
+            edge.to
+        }
+    }
+
+    fn step_end(&self, (e, forward): Step) -> usize {
+        self.step_start((e, !forward))
+    }
+
+    /// Trace every edge once, returning the subpaths in order with `true`
+    /// for those traced against their direction (never for loops)
+    fn traversal(mut self) -> Vec<(usize, bool)> {
+        self.pair_odd_vertices();
+
+        // One trail per connected part; dropping its longest jump opens it up
+        let mut parts: Vec<(Vec<Step>, bool)> = Vec::new();
+        let mut used = vec![false; self.edges.len()];
+        for start in 0..self.positions.len() {
+            if self.adjacent[start].iter().all(|&e| used[e]) {
+                continue;
+            }
+            let circuit = self.euler_circuit(start, &mut used);
+            let longest_jump = circuit
+                .iter()
+                .enumerate()
+                .filter(|(_, &(e, _))| self.edges[e].subpath.is_none())
+                .max_by(|(_, &(a, _)), (_, &(b, _))| {
+                    self.edge_length(a).total_cmp(&self.edge_length(b))
+                })
+                .map(|(k, _)| k);
+            match longest_jump {
+                Some(k) => {
+                    let trail = circuit[k + 1..].iter().chain(&circuit[..k]).copied();
+                    parts.push((trail.collect(), false));
+                }
+                None => parts.push((circuit, true)),
+            }
+        }
+
+        // Visit the parts nearest first: trails from either end, circuits
+        // from any of their vertices
+        let mut order = Vec::new();
+        let mut position = parts
+            .first()
+            .and_then(|(steps, _)| steps.first())
+            .map(|&step| self.positions[self.step_start(step)]);
+        while let Some(here) = position {
+            let mut best = (0, 0, false, f32::INFINITY);
+            for (p, (steps, circuit)) in parts.iter().enumerate() {
+                let entries: Vec<(usize, bool)> = if *circuit {
+                    (0..steps.len()).map(|k| (k, false)).collect()
+                } else {
+                    vec![(0, false), (steps.len().saturating_sub(1), true)]
+                };
+                for (k, reverse) in entries {
+                    let Some(&step) = steps.get(k) else {
+                        continue;
+                    };
+                    let vertex = if reverse {
+                        self.step_end(step)
+                    } else {
+                        self.step_start(step)
+                    };
+                    let d = distance3(here, self.positions[vertex]);
+                    if d < best.3 {
+                        best = (p, k, reverse, d);
+                    }
+                }
+            }
+
+            let (p, k, reverse, _) = best;
+            let (mut steps, circuit) = parts.swap_remove(p);
+            if circuit {
+                steps.rotate_left(k);
+            } else if reverse {
+                steps = steps.into_iter().rev().map(|(e, f)| (e, !f)).collect();
+            }
+            position = steps
+                .last()
+                .filter(|_| !parts.is_empty())
+                .map(|&step| self.positions[self.step_end(step)]);
+            order.extend(steps.into_iter().filter_map(|(e, forward)| {
+                let edge = self.edges[e];
+                let reverse = !forward && edge.from != edge.to;
+                edge.subpath.map(|i| (i, reverse))
+            }));
+        }
+        order
+    }
+
+    /// Join odd-degree vertices in nearest pairs with jumps, so every
+    /// vertex has even degree
+    fn pair_odd_vertices(&mut self) {
+        let mut odd: Vec<usize> = (0..self.positions.len())
+            .filter(|&v| self.adjacent[v].len() % 2 == 1)
+            .collect();
+        while let Some(v) = odd.pop() {
+            let Some((k, _)) = odd.iter().enumerate().min_by(|(_, &a), (_, &b)| {
+                let da = distance3(self.positions[v], self.positions[a]);
+                let db = distance3(self.positions[v], self.positions[b]);
+                da.total_cmp(&db)
+            }) else {
+                break;
+            };
+            let w = odd.swap_remove(k);
+            self.add_edge(v, w, None);
+        }
+    }
+
+    /// Euler circuit through the unused edges reachable from `start`
+    /// (Hierholzer's algorithm), marking them used
+    fn euler_circuit(&self, start: usize, used: &mut [bool]) -> Vec<Step> {
+        let mut next = vec![0; self.positions.len()];
+        let mut stack: Vec<(usize, Option<Step>)> = vec![(start, None)];
+        let mut circuit = Vec::new();
+        while let Some(&(v, _)) = stack.last() {
+            while next[v] < self.adjacent[v].len() && used[self.adjacent[v][next[v]]] {
+                next[v] += 1;
+            }
+            if let Some(&e) = self.adjacent[v].get(next[v]) {
+                used[e] = true;
+                let edge = self.edges[e];
+                let forward = edge.from == v;
+                let to = if forward { edge.to } else { edge.from };
+                stack.push((to, Some((e, forward))));
+            } else if let Some((_, Some(step))) = stack.pop() {
+                circuit.push(step);
+            }
+        }
+        circuit.reverse();
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimized_order_jumps_less_than_naive() {
+        // The sides of two squares a unit apart, alternating between them
+        // and pointing every which way
+        let segments = vec![
+            vec![(-1.0, 0.0), (-0.5, 0.0)],
+            vec![(1.0, 0.5), (0.5, 0.5)],
+            vec![(-1.0, 0.5), (-0.5, 0.5)],
+            vec![(1.0, 0.0), (1.0, 0.5)],
+            vec![(-0.5, 0.0), (-0.5, 0.5)],
+            vec![(0.5, 0.0), (1.0, 0.0)],
+            vec![(-1.0, 0.5), (-1.0, 0.0)],
+            vec![(0.5, 0.0), (0.5, 0.5)],
+        ];
+        let naive = jump_length(&segments);
+
+        let slices: Vec<_> = segments.iter().map(|s| (s.as_slice(), false)).collect();
+        let order = optimize_order(&slices);
+        let mut indices: Vec<usize> = order.iter().map(|&(i, _)| i).collect();
+        indices.sort();
+        assert_eq!(indices, (0..segments.len()).collect::<Vec<_>>());
+
+        let traced: Vec<Vec<(f32, f32)>> = order
+            .iter()
+            .map(|&(i, reverse)| {
+                let mut points = segments[i].clone();
+                if reverse {
+                    points.reverse();
+                }
+                points
+            })
+            .collect();
+        let optimized = jump_length(&traced);
+        assert!(optimized < naive, "{} >= {}", optimized, naive);
+
+        // Each square is one loop, so the only jump is between them
+        let jumps = traced
+            .windows(2)
+            .filter(|pair| pair[0].last() != pair[1].first())
+            .count();
+        assert_eq!(jumps, 1);
+    }
+
+    #[test]
+    fn test_closed_subpath_is_a_loop_at_its_start() {
+        // A square starting and ending at the origin, between two segments
+        // that meet there; the square's last listed point is elsewhere
+        let square = vec![(0.0, 0.0), (0.5, 0.0), (0.5, 0.5), (0.0, 0.5)];
+        let left = vec![(-1.0, 0.0), (0.0, 0.0)];
+        let right = vec![(1.0, 0.0), (0.0, 0.0)];
+        let subpaths = [
+            (square.as_slice(), true),
+            (left.as_slice(), false),
+            (right.as_slice(), false),
+        ];
+
+        let order = optimize_order(&subpaths);
+        assert_eq!(order.len(), 3);
+        assert!(order.contains(&(0, false)), "loops are never reversed");
+
+        // Traced with the square closed back to its start, nothing jumps
+        let traced: Vec<Vec<(f32, f32)>> = order
+            .iter()
+            .map(|&(i, reverse)| {
+                let (points, closed) = subpaths[i];
+                let mut points = points.to_vec();
+                if closed {
+                    points.push(points[0]);
+                }
+                if reverse {
+                    points.reverse();
+                }
+                points
+            })
+            .collect();
+        assert_eq!(jump_length(&traced), 0.0);
+    }
+
+    #[test]
+    fn test_optimized_edges_trace_a_cube_with_few_jumps() {
+        let vertices: Vec<[f32; 3]> = (0..8)
+            .map(|i| [(i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32])
+            .collect();
+        // Listed axis by axis, so every edge in the naive order jumps
+        let edges = vec![
+            (0, 1),
+            (2, 3),
+            (4, 5),
+            (6, 7),
+            (0, 2),
+            (1, 3),
+            (4, 6),
+            (5, 7),
+            (0, 4),
+            (1, 5),
+            (2, 6),
+            (3, 7),
+        ];
+        let jumps = |edges: &[(usize, usize)]| {
+            edges
+                .windows(2)
+                .filter(|pair| pair[0].1 != pair[1].0)
+                .count()
+        };
+
+        let order = optimize_edges(&edges, &vertices);
+        let mut indices: Vec<usize> = order.iter().map(|&(i, _)| i).collect();
+        indices.sort();
+        assert_eq!(indices, (0..edges.len()).collect::<Vec<_>>());
+
+        let traced: Vec<(usize, usize)> = order
+            .iter()
+            .map(|&(i, reverse)| {
+                let (a, b) = edges[i];
+                if reverse {
+                    (b, a)
+                } else {
+                    (a, b)
+                }
+            })
+            .collect();
+        // All eight corners are odd, so four pairings leave three jumps
+        assert_eq!(jumps(&edges), 11);
+        assert_eq!(jumps(&traced), 3);
+    }
+}
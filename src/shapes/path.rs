@@ -6,8 +6,108 @@
 //! - Imported SVG paths (future)
 //! - Traced image edges (future)
 //! - Text glyphs (future)
+//! - Coordinate lists loaded from CSV files or stereo WAV recordings
 
-use super::traits::Shape;
+use std::path::Path as FilePath;
+use thiserror::Error;
+
+use super::normalize::{clamp_point, Fit};
+use super::traits::{bounds_of, Shape};
+
+/// Errors that can occur when loading a path from a coordinate file
+#[derive(Error, Debug)]
+pub enum PathError {
+    #[error("Failed to read file: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Line {line}: {message}")]
+    ParseError { line: usize, message: String },
+
+    #[error("File contains no points")]
+    NoPoints,
+
+    #[error("Failed to read WAV file: {0}")]
+    WavError(#[from] hound::Error),
+
+    #[error("WAV file is mono; X and Y need two channels")]
+    Mono,
+}
+
+/// Most points kept from a WAV file; longer recordings are thinned evenly
+const MAX_WAV_POINTS: usize = 20_000;
+
+/// Options for CSV coordinate import
+pub struct CsvOptions {
+    /// Center and scale points to fit [-1, 1] (false = assume pre-normalized)
+    pub normalize: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { normalize: true }
+    }
+}
+
+/// One damped pendulum contributing to a harmonograph axis
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Pendulum {
+    /// Angular frequency (radians per unit of `t`)
+    pub frequency: f32,
+    /// Phase offset (radians)
+    pub phase: f32,
+    /// Swing amplitude
+    pub amplitude: f32,
+    /// Exponential damping rate (0 = swings forever)
+    pub decay: f32,
+}
+
+impl Pendulum {
+    /// Create a pendulum
+    pub fn new(frequency: f32, phase: f32, amplitude: f32, decay: f32) -> Self {
+        Self {
+            frequency,
+            phase,
+            amplitude,
+            decay,
+        }
+    }
+
+    /// Displacement at time `t`
+    fn position(&self, t: f32) -> f32 {
+        self.amplitude * (self.frequency * t + self.phase).sin() * (-self.decay * t).exp()
+    }
+}
+
+/// Parameters for `Path::harmonograph`
+///
+/// Each axis is driven by the sum of two damped pendulums, as in the
+/// classic lateral harmonograph.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct HarmonographParams {
+    /// Pendulums driving X
+    pub x: [Pendulum; 2],
+    /// Pendulums driving Y
+    pub y: [Pendulum; 2],
+    /// Length of the simulation in full cycles of `t` (each cycle is 2π)
+    pub cycles: f32,
+}
+
+impl Default for HarmonographParams {
+    fn default() -> Self {
+        use std::f32::consts::FRAC_PI_2;
+        Self {
+            x: [
+                Pendulum::new(2.0, FRAC_PI_2, 0.5, 0.02),
+                Pendulum::new(3.01, 0.0, 0.5, 0.01),
+            ],
+            y: [
+                Pendulum::new(3.0, 0.0, 0.5, 0.015),
+                Pendulum::new(2.0, FRAC_PI_2, 0.5, 0.01),
+            ],
+            cycles: 20.0,
+        }
+    }
+}
 
 /// A path defined by a sequence of points
 ///
@@ -25,6 +125,8 @@ pub struct Path {
     closed: bool,
     /// Optional name for this path
     name: String,
+    /// Segments traced with the beam off, sorted
+    pen_up: Vec<usize>,
 }
 
 impl Path {
@@ -65,9 +167,52 @@ impl Path {
             total_length,
             closed,
             name,
+            pen_up: Vec::new(),
         }
     }
 
+    /// Mark segments as pen-up moves between strokes
+    ///
+    /// Segment `i` runs from point `i` to point `i + 1`. The beam still
+    /// travels along a pen-up segment but is blanked while it does.
+    pub fn with_pen_up(mut self, mut segments: Vec<usize>) -> Self {
+        segments.sort_unstable();
+        segments.dedup();
+        segments.retain(|&i| i < self.segment_lengths.len());
+        self.pen_up = segments;
+        self
+    }
+
+    /// Segments marked as pen-up moves
+    pub fn pen_up(&self) -> &[usize] {
+        &self.pen_up
+    }
+
+    /// Segment at parameter t and the position along it, in [0, 1]
+    fn locate(&self, t: f32) -> Option<(usize, f32)> {
+        if self.points.len() < 2 || self.total_length == 0.0 {
+            return None;
+        }
+
+        let target_dist = t * self.total_length;
+        let mut accumulated = 0.0;
+        let last = self.segment_lengths.len() - 1;
+
+        for (i, &seg_len) in self.segment_lengths.iter().enumerate() {
+            if accumulated + seg_len >= target_dist || i == last {
+                let local_t = if seg_len > 0.0 {
+                    (target_dist - accumulated) / seg_len
+                } else {
+                    0.0
+                };
+                return Some((i, local_t));
+            }
+            accumulated += seg_len;
+        }
+
+        None
+    }
+
     /// Get the number of points in the path
     pub fn len(&self) -> usize {
         self.points.len()
@@ -83,6 +228,275 @@ impl Path {
         &self.points
     }
 
+    /// Mean position of the points (the trace's DC offset)
+    pub fn centroid(&self) -> (f32, f32) {
+        if self.points.is_empty() {
+            return (0.0, 0.0);
+        }
+        let n = self.points.len() as f32;
+        let (sx, sy) = self
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+        (sx / n, sy / n)
+    }
+
+    /// Points where the path crosses itself
+    ///
+    /// Returns `(segment, point)` pairs, where segment `i` runs from point
+    /// `i` to point `i + 1` and is the earlier of the two crossing segments.
+    /// Neighbouring segments, which always share an endpoint, don't count.
+    /// Segments are half-open, owning their start point but not their end,
+    /// so a crossing that lands on a vertex is counted once.
+    /// Uses a sweep over X so only segments whose X ranges overlap are tested.
+    pub fn self_intersections(&self) -> Vec<(usize, (f32, f32))> {
+        let n = self.segment_lengths.len();
+        let segment = |i: usize| (self.points[i], self.points[(i + 1) % self.points.len()]);
+
+        // Segments ordered by their left end
+        let mut order: Vec<usize> = (0..n).filter(|&i| self.segment_lengths[i] > 0.0).collect();
+        let min_x = |i: usize| {
+            let (a, b) = segment(i);
+            a.0.min(b.0)
+        };
+        order.sort_by(|&a, &b| min_x(a).total_cmp(&min_x(b)));
+
+        let adjacent = |a: usize, b: usize| {
+            let d = a.abs_diff(b);
+            d == 1 || (self.closed && d == n - 1)
+        };
+
+        let mut crossings = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+        for &i in &order {
+            let left = min_x(i);
+            // Drop segments that end before this one starts
+            active.retain(|&j| {
+                let (a, b) = segment(j);
+                a.0.max(b.0) >= left
+            });
+            for &j in &active {
+                if adjacent(i, j) {
+                    continue;
+                }
+                if let Some(p) = segment_intersection(segment(i), segment(j)) {
+                    crossings.push((i.min(j), p));
+                }
+            }
+            active.push(i);
+        }
+
+        crossings.sort_by_key(|&(i, _)| i);
+        crossings
+    }
+
+    /// Shift every point by the given offset
+    pub fn translated(&self, dx: f32, dy: f32) -> Path {
+        let points = self.points.iter().map(|&(x, y)| (x + dx, y + dy)).collect();
+        Self::with_options(points, self.closed, self.name.clone()).with_pen_up(self.pen_up.clone())
+    }
+
+    /// Shift every point by the given offset, then scale it about the origin
+    pub fn transformed(&self, dx: f32, dy: f32, scale: f32) -> Path {
+        let points = self
+            .points
+            .iter()
+            .map(|&(x, y)| ((x + dx) * scale, (y + dy) * scale))
+            .collect();
+        Self::with_options(points, self.closed, self.name.clone()).with_pen_up(self.pen_up.clone())
+    }
+
+    /// Offset and scale for `transformed` that put the mean of the points
+    /// at the origin while keeping every point inside [-1, 1]
+    pub fn recentering(&self) -> (f32, f32, f32) {
+        let (cx, cy) = self.centroid();
+        let peak = self
+            .points
+            .iter()
+            .map(|&(x, y)| (x - cx).abs().max((y - cy).abs()))
+            .fold(0.0f32, f32::max);
+        let scale = if peak > 1.0 { 1.0 / peak } else { 1.0 };
+        (-cx, -cy, scale)
+    }
+
+    /// Shift points so their mean sits at the origin
+    ///
+    /// Bounding-box normalization centers the extent, but a trace whose
+    /// points cluster on one side still carries a DC offset that AC-coupled
+    /// scope inputs will drift away from. Shifting a lopsided figure pushes
+    /// its far side past the edge, so it's then scaled back inside [-1, 1].
+    pub fn recenter_centroid(&self) -> Path {
+        let (dx, dy, scale) = self.recentering();
+        self.transformed(dx, dy, scale)
+    }
+
+    /// Load a path from a CSV/plain-text coordinate file
+    ///
+    /// Each line holds `x,y` or `x,y,z`, separated by commas, semicolons or
+    /// whitespace. As on the Z output, `z = 0` marks a blanked (pen-up)
+    /// point and any other value a lit one. Blanked points are dropped and
+    /// the move from the last lit point to the next is marked pen-up.
+    /// Blank lines, `#` comments and a leading header row are ignored.
+    pub fn from_csv(path: impl AsRef<FilePath>, options: &CsvOptions) -> Result<Self, PathError> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)?;
+
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("CSV")
+            .to_string();
+
+        Self::from_csv_str(&data, &name, options)
+    }
+
+    /// Load a path from a coordinate file, picking the format by extension
+    ///
+    /// `.wav` files are read with `from_wav`, anything else as CSV.
+    pub fn from_file(path: impl AsRef<FilePath>, options: &CsvOptions) -> Result<Self, PathError> {
+        let path = path.as_ref();
+        let is_wav = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+        if is_wav {
+            Self::from_wav(path, options)
+        } else {
+            Self::from_csv(path, options)
+        }
+    }
+
+    /// Load a path from a stereo WAV recording
+    ///
+    /// Each frame is one point, with the left channel as X and the right
+    /// as Y, the way an XY scope would draw the recording. Further
+    /// channels are ignored. Recordings longer than `MAX_WAV_POINTS`
+    /// frames are thinned evenly, so keep the file to a few cycles of
+    /// the figure.
+    pub fn from_wav(path: impl AsRef<FilePath>, options: &CsvOptions) -> Result<Self, PathError> {
+        let path = path.as_ref();
+        let mut reader = hound::WavReader::open(path)?;
+        let spec = reader.spec();
+        if spec.channels < 2 {
+            return Err(PathError::Mono);
+        }
+
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|s| s.map(|v| v as f32 * scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        let frames = samples.chunks_exact(usize::from(spec.channels));
+        let step = frames.len().div_ceil(MAX_WAV_POINTS).max(1);
+        let points = frames.step_by(step).map(|f| (f[0], f[1])).collect();
+
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("WAV")
+            .to_string();
+
+        Self::from_coordinates(points, Vec::new(), &name, options)
+    }
+
+    /// Parse a path from CSV text
+    pub fn from_csv_str(data: &str, name: &str, options: &CsvOptions) -> Result<Self, PathError> {
+        let mut points = Vec::new();
+        let mut pen_up = Vec::new();
+        let mut pen_lifted = false;
+        let mut seen_row = false;
+
+        for (i, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|f| !f.is_empty())
+                .collect();
+
+            let values: Result<Vec<f32>, _> = fields.iter().map(|f| f.parse::<f32>()).collect();
+            let values = match values {
+                Ok(v) => v,
+                // Allow a single header row before any data
+                Err(_) if !seen_row => {
+                    seen_row = true;
+                    continue;
+                }
+                Err(e) => {
+                    return Err(PathError::ParseError {
+                        line: i + 1,
+                        message: e.to_string(),
+                    })
+                }
+            };
+            seen_row = true;
+
+            if values.len() < 2 {
+                return Err(PathError::ParseError {
+                    line: i + 1,
+                    message: "expected at least two columns".to_string(),
+                });
+            }
+
+            let blanked = values.get(2).is_some_and(|&z| z == 0.0);
+            if blanked {
+                pen_lifted = true;
+                continue;
+            }
+            if pen_lifted && !points.is_empty() {
+                pen_up.push(points.len() - 1);
+            }
+            pen_lifted = false;
+            points.push((values[0], values[1]));
+        }
+
+        Self::from_coordinates(points, pen_up, name, options)
+    }
+
+    /// Build a path from imported coordinates
+    ///
+    /// Fits or clamps them to [-1, 1] and closes the path if it returns
+    /// to its start.
+    fn from_coordinates(
+        points: Vec<(f32, f32)>,
+        pen_up: Vec<usize>,
+        name: &str,
+        options: &CsvOptions,
+    ) -> Result<Self, PathError> {
+        if points.is_empty() {
+            return Err(PathError::NoPoints);
+        }
+
+        let points = if options.normalize {
+            // A file with no extent has nothing to scale, so it collapses to the origin
+            match Fit::new(&points, true) {
+                Some(fit) => points.iter().map(|&p| fit.apply(p)).collect(),
+                None => vec![(0.0, 0.0); points.len()],
+            }
+        } else {
+            points.into_iter().map(clamp_point).collect()
+        };
+
+        // A file that returns to its start describes a closed outline
+        let closed = points.len() > 2 && points.first() == points.last();
+        let points = if closed {
+            points[..points.len() - 1].to_vec()
+        } else {
+            points
+        };
+
+        Ok(Self::with_options(points, closed, name.to_string()).with_pen_up(pen_up))
+    }
+
     /// Create a path that traces a sine wave
     pub fn sine_wave(amplitude: f32, periods: f32, num_points: usize) -> Self {
         let points: Vec<(f32, f32)> = (0..num_points)
@@ -108,17 +522,82 @@ impl Path {
     /// * `b` - Frequency ratio for Y
     /// * `delta` - Phase offset for X (in radians)
     /// * `num_points` - Number of points to generate
-    pub fn lissajous(a: f32, b: f32, delta: f32, num_points: usize) -> Self {
-        let points: Vec<(f32, f32)> = (0..num_points)
+    /// * `close` - Force the curve shut when the ratio doesn't close on its own
+    ///
+    /// Integer ratios return to the start after one period and are always
+    /// closed. Other ratios leave the end short of the start; with `close`
+    /// the start point is appended so the beam returns, otherwise the path
+    /// is left open.
+    pub fn lissajous(a: f32, b: f32, delta: f32, num_points: usize, close: bool) -> Self {
+        /// End-to-start distance below which the curve counts as closed
+        const CLOSE_EPSILON: f32 = 0.01;
+
+        let num_points = num_points.max(2);
+        let mut points: Vec<(f32, f32)> = (0..num_points)
             .map(|i| {
-                let t = i as f32 / num_points as f32 * std::f32::consts::TAU;
+                let t = i as f32 / (num_points - 1) as f32 * std::f32::consts::TAU;
                 let x = (a * t + delta).sin();
                 let y = (b * t).sin();
                 (x, y)
             })
             .collect();
 
-        Self::with_options(points, true, "Lissajous".to_string())
+        let start = points[0];
+        let end = points[num_points - 1];
+        let gap = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+
+        let closed = if gap < CLOSE_EPSILON {
+            // Snap the final point onto the start to remove rounding drift
+            points[num_points - 1] = start;
+            true
+        } else if close {
+            points.push(start);
+            true
+        } else {
+            false
+        };
+
+        Self::with_options(points, closed, "Lissajous".to_string())
+    }
+
+    /// Build the classic Lissajous ratio table
+    ///
+    /// Returns `size × size` figures in row-major order: row `b - 1`,
+    /// column `a - 1`, for integer ratios `a:b` from 1 to `size`.
+    pub fn lissajous_grid(size: u32, delta: f32, num_points: usize) -> Vec<((u32, u32), Path)> {
+        (1..=size)
+            .flat_map(|b| (1..=size).map(move |a| (a, b)))
+            .map(|(a, b)| {
+                let path = Self::lissajous(a as f32, b as f32, delta, num_points, true);
+                ((a, b), path)
+            })
+            .collect()
+    }
+
+    /// Create a harmonograph figure
+    ///
+    /// Each axis is `Σ A·sin(f·t + p)·e^(-d·t)` over its pendulums, with `t`
+    /// running over `params.cycles` full turns. The result is scaled down
+    /// if the summed amplitudes would leave [-1, 1]. With zero decay and a
+    /// single pendulum per axis this is a Lissajous figure.
+    pub fn harmonograph(params: &HarmonographParams, num_points: usize) -> Self {
+        let num_points = num_points.max(2);
+        let t_end = params.cycles.max(0.0) * std::f32::consts::TAU;
+
+        let reach =
+            |pendulums: &[Pendulum]| -> f32 { pendulums.iter().map(|p| p.amplitude.abs()).sum() };
+        let scale = 1.0 / reach(&params.x).max(reach(&params.y)).max(1.0);
+
+        let points: Vec<(f32, f32)> = (0..num_points)
+            .map(|i| {
+                let t = i as f32 / (num_points - 1) as f32 * t_end;
+                let x: f32 = params.x.iter().map(|p| p.position(t)).sum();
+                let y: f32 = params.y.iter().map(|p| p.position(t)).sum();
+                (x * scale, y * scale)
+            })
+            .collect();
+
+        Self::with_options(points, false, "Harmonograph".to_string())
     }
 
     /// Create a spiral
@@ -169,39 +648,26 @@ impl Shape for Path {
             return (0.0, 0.0);
         }
 
-        if self.points.len() == 1 {
+        // Find which segment we're on
+        let Some((i, local_t)) = self.locate(t) else {
             return self.points[0];
-        }
+        };
 
-        if self.total_length == 0.0 {
-            return self.points[0];
-        }
+        let (x1, y1) = self.points[i];
+        let (x2, y2) = self.points[(i + 1) % self.points.len()];
 
-        // Find which segment we're on
-        let target_dist = t * self.total_length;
-        let mut accumulated = 0.0;
+        let x = x1 + local_t * (x2 - x1);
+        let y = y1 + local_t * (y2 - y1);
 
-        for (i, &seg_len) in self.segment_lengths.iter().enumerate() {
-            if accumulated + seg_len >= target_dist || i == self.segment_lengths.len() - 1 {
-                // We're on this segment
-                let local_t = if seg_len > 0.0 {
-                    (target_dist - accumulated) / seg_len
-                } else {
-                    0.0
-                };
-
-                let (x1, y1) = self.points[i];
-                let (x2, y2) = self.points[(i + 1) % self.points.len()];
-
-                let x = x1 + local_t * (x2 - x1);
-                let y = y1 + local_t * (y2 - y1);
+        (x, y)
+    }
 
-                return (x, y);
-            }
-            accumulated += seg_len;
+    fn is_blanked(&self, t: f32) -> bool {
+        if self.pen_up.is_empty() {
+            return false;
         }
-
-        self.points[0]
+        self.locate(t)
+            .is_some_and(|(i, _)| self.pen_up.binary_search(&i).is_ok())
     }
 
     fn name(&self) -> &str {
@@ -212,15 +678,51 @@ impl Shape for Path {
         self.total_length
     }
 
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        bounds_of(self.points.iter().copied())
+    }
+
     fn is_closed(&self) -> bool {
         self.closed
     }
+
+    fn has_points(&self) -> bool {
+        !self.points.is_empty()
+    }
+}
+
+/// Crossing point of two line segments, if they properly intersect
+///
+/// Parallel and collinear segments report no crossing.
+fn segment_intersection(
+    (p1, p2): ((f32, f32), (f32, f32)),
+    (q1, q2): ((f32, f32), (f32, f32)),
+) -> Option<(f32, f32)> {
+    let r = (p2.0 - p1.0, p2.1 - p1.1);
+    let s = (q2.0 - q1.0, q2.1 - q1.1);
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() < f32::EPSILON {
+        return None;
+    }
+    let qp = (q1.0 - p1.0, q1.1 - p1.1);
+    let t = (qp.0 * s.1 - qp.1 * s.0) / denom;
+    let u = (qp.0 * r.1 - qp.1 * r.0) / denom;
+    // Half-open, so a vertex belongs only to the segment it starts
+    ((0.0..1.0).contains(&t) && (0.0..1.0).contains(&u)).then_some((p1.0 + t * r.0, p1.1 + t * r.1))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Location of a file in tests/fixtures
+    fn fixture(name: &str) -> std::path::PathBuf {
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
+
     #[test]
     fn test_path_basic() {
         let path = Path::new(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
@@ -230,14 +732,209 @@ mod tests {
 
     #[test]
     fn test_lissajous() {
-        let lissajous = Path::lissajous(3.0, 2.0, 0.0, 100);
+        let lissajous = Path::lissajous(3.0, 2.0, 0.0, 100, false);
         assert_eq!(lissajous.len(), 100);
         assert!(lissajous.is_closed());
     }
 
+    #[test]
+    fn test_harmonograph_undamped_is_lissajous() {
+        let delta = std::f32::consts::FRAC_PI_2;
+        let silent = Pendulum::new(1.0, 0.0, 0.0, 0.0);
+        let params = HarmonographParams {
+            x: [Pendulum::new(3.0, delta, 1.0, 0.0), silent],
+            y: [Pendulum::new(2.0, 0.0, 1.0, 0.0), silent],
+            cycles: 1.0,
+        };
+
+        let harmonograph = Path::harmonograph(&params, 500);
+        let lissajous = Path::lissajous(3.0, 2.0, delta, 500, false);
+        for (h, l) in harmonograph.points().iter().zip(lissajous.points()) {
+            assert!((h.0 - l.0).abs() < 1e-4 && (h.1 - l.1).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_harmonograph_decays() {
+        let path = Path::harmonograph(&HarmonographParams::default(), 4000);
+        let points = path.points();
+        let peak = |pts: &[(f32, f32)]| {
+            pts.iter()
+                .map(|&(x, y)| x.abs().max(y.abs()))
+                .fold(0.0f32, f32::max)
+        };
+
+        let tenth = points.len() / 10;
+        let early = peak(&points[..tenth]);
+        let late = peak(&points[points.len() - tenth..]);
+        assert!(late < early * 0.5);
+        assert!(early <= 1.0);
+    }
+
+    #[test]
+    fn test_lissajous_grid_ratios() {
+        let grid = Path::lissajous_grid(3, std::f32::consts::FRAC_PI_2, 100);
+        let ratios: Vec<(u32, u32)> = grid.iter().map(|(ratio, _)| *ratio).collect();
+        assert_eq!(
+            ratios,
+            vec![
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (1, 3),
+                (2, 3),
+                (3, 3)
+            ]
+        );
+
+        // Each cell is the matching figure
+        let (_, cell) = &grid[5];
+        let expected = Path::lissajous(3.0, 2.0, std::f32::consts::FRAC_PI_2, 100, true);
+        assert_eq!(cell.points(), expected.points());
+    }
+
+    #[test]
+    fn test_lissajous_force_close() {
+        // A non-integer ratio doesn't return to its start by itself
+        let open = Path::lissajous(2.5, 1.7, 0.3, 200, false);
+        assert!(!open.is_closed());
+        assert_ne!(open.points().first(), open.points().last());
+
+        let closed = Path::lissajous(2.5, 1.7, 0.3, 200, true);
+        assert!(closed.is_closed());
+        assert_eq!(closed.points().first(), closed.points().last());
+    }
+
     #[test]
     fn test_heart() {
         let heart = Path::heart(0.8, 100);
         assert_eq!(heart.len(), 100);
     }
+
+    #[test]
+    fn test_figure_eight_crosses_once_at_center() {
+        use std::f32::consts::TAU;
+
+        // Lemniscate: two lobes meeting at the origin. 63 points keeps
+        // the crossing off the vertices.
+        let points = (0..63)
+            .map(|i| {
+                let a = i as f32 / 63.0 * TAU;
+                (a.cos(), (2.0 * a).sin() / 2.0)
+            })
+            .collect();
+        let crossings = Path::closed(points).self_intersections();
+
+        assert_eq!(crossings.len(), 1);
+        let (_, (x, y)) = crossings[0];
+        assert!(x.abs() < 0.01 && y.abs() < 0.01, "crossing at ({x}, {y})");
+
+        // A circle never crosses itself
+        let circle = (0..63)
+            .map(|i| {
+                let a = i as f32 / 63.0 * TAU;
+                (a.cos(), a.sin())
+            })
+            .collect();
+        assert!(Path::closed(circle).self_intersections().is_empty());
+    }
+
+    #[test]
+    fn test_crossing_at_shared_vertex_counts_once() {
+        // A bow tie whose two strokes cross exactly on the middle vertex
+        let bow_tie = vec![
+            (-1.0, -1.0),
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (1.0, -1.0),
+            (0.0, 0.0),
+            (-1.0, 1.0),
+        ];
+        let crossings = Path::closed(bow_tie).self_intersections();
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].1, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_recenter_centroid() {
+        // Most points bunched up in the top-right corner
+        let mut points = vec![(-1.0, -1.0)];
+        points.extend((0..20).map(|i| (0.8 + i as f32 * 0.01, 0.9)));
+        let path = Path::new(points);
+        assert!(path.centroid().0 > 0.5);
+
+        let centered = path.recenter_centroid();
+        let (cx, cy) = centered.centroid();
+        assert!(cx.abs() < 1e-5 && cy.abs() < 1e-5);
+        assert_eq!(centered.len(), path.len());
+
+        // The lone far point would land near -1.8; it's scaled back in
+        let (min_x, min_y, max_x, max_y) = centered.bounds();
+        for v in [min_x, min_y, max_x, max_y] {
+            assert!((-1.0..=1.0).contains(&v), "{} outside [-1, 1]", v);
+        }
+        assert!((min_x.min(min_y) + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "x,y,z\n\
+                   # square with a pen-up move\n\
+                   0, 0, 1\n\
+                   10, 0, 1\n\
+                   5, 5, 0\n\
+                   10, 10, 1\n\
+                   0, 10, 1\n";
+
+        let path = Path::from_csv_str(csv, "square", &CsvOptions::default()).unwrap();
+        assert_eq!(
+            path.points(),
+            &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        );
+        assert!(!path.is_closed());
+        assert_eq!(path.pen_up(), &[1]);
+
+        // Pre-normalized input is kept as-is
+        let options = CsvOptions { normalize: false };
+        let path = Path::from_csv_str("0.5 0.25\n-0.5 0.25\n", "raw", &options).unwrap();
+        assert_eq!(path.points(), &[(0.5, 0.25), (-0.5, 0.25)]);
+
+        assert!(Path::from_csv_str("1,2\nfoo,bar\n", "bad", &options).is_err());
+    }
+
+    #[test]
+    fn test_from_wav() {
+        // Four corners at half scale, left channel X and right channel Y
+        let options = CsvOptions { normalize: false };
+        let path = Path::from_file(fixture("square.wav"), &options).unwrap();
+        assert_eq!(path.name(), "square");
+        assert_eq!(
+            path.points(),
+            &[(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+        );
+
+        let result = Path::from_wav(fixture("mono.wav"), &options);
+        assert!(matches!(result, Err(PathError::Mono)));
+    }
+
+    #[test]
+    fn test_from_csv_file_blanks_z_zero() {
+        let path = Path::from_csv(fixture("pen_up.csv"), &CsvOptions::default()).unwrap();
+
+        assert_eq!(path.name(), "pen_up");
+        // The blanked point is dropped and the file returns to its start
+        assert_eq!(
+            path.points(),
+            &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        );
+        assert!(path.is_closed());
+        // Only the move across the blanked point is drawn dark
+        assert_eq!(path.pen_up(), &[1]);
+        assert!(path.is_blanked(0.375));
+        assert!(!path.is_blanked(0.125));
+        assert!(!path.is_blanked(0.625));
+    }
 }
@@ -369,6 +369,14 @@ mod tests {
         assert!((y - 0.5).abs() < 0.001);
     }
 
+    #[test]
+    fn test_circle_bounds() {
+        let (min_x, min_y, max_x, max_y) = Circle::new(0.5).bounds();
+        for (v, expected) in [(min_x, -0.5), (min_y, -0.5), (max_x, 0.5), (max_y, 0.5)] {
+            assert!((v - expected).abs() < 0.001, "{v} != {expected}");
+        }
+    }
+
     #[test]
     fn test_line() {
         let line = Line::new(-1.0, 0.0, 1.0, 0.0);
@@ -5,14 +5,21 @@
 
 use super::traits::Shape;
 
+/// Smallest length used when sharing time by length
+const MIN_SHARE_LENGTH: f32 = 1e-3;
+
 /// A shape entry in the scene with its configuration
 pub struct SceneShape {
     /// The shape (boxed for dynamic dispatch)
     shape: Box<dyn Shape>,
     /// Weight for time allocation (higher = more time)
     weight: f32,
-    /// Whether this shape is enabled
+    /// Scale applied to the shape's coordinates (1.0 = as drawn)
+    amplitude: f32,
+    /// Whether this shape is enabled (unmuted)
     enabled: bool,
+    /// Whether this shape is soloed
+    soloed: bool,
 }
 
 impl SceneShape {
@@ -21,7 +28,9 @@ impl SceneShape {
         Self {
             shape: Box::new(shape),
             weight: 1.0,
+            amplitude: 1.0,
             enabled: true,
+            soloed: false,
         }
     }
 
@@ -30,7 +39,9 @@ impl SceneShape {
         Self {
             shape: Box::new(shape),
             weight,
+            amplitude: 1.0,
             enabled: true,
+            soloed: false,
         }
     }
 
@@ -49,6 +60,27 @@ impl SceneShape {
         self.weight = weight.max(0.1); // Minimum weight
     }
 
+    /// Get the amplitude
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    /// Set the amplitude, shrinking or enlarging the shape about the origin
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude.max(0.0);
+    }
+
+    /// Sample the shape with its amplitude applied
+    fn sample(&self, t: f32) -> (f32, f32) {
+        let (x, y) = self.shape.sample(t);
+        (x * self.amplitude, y * self.amplitude)
+    }
+
+    /// Length of the shape as drawn, after amplitude
+    fn length(&self) -> f32 {
+        self.shape.length() * self.amplitude
+    }
+
     /// Check if enabled
     pub fn is_enabled(&self) -> bool {
         self.enabled
@@ -58,6 +90,62 @@ impl SceneShape {
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
     }
+
+    /// Check if soloed
+    pub fn is_soloed(&self) -> bool {
+        self.soloed
+    }
+
+    /// Toggle solo state
+    pub fn set_soloed(&mut self, soloed: bool) {
+        self.soloed = soloed;
+    }
+}
+
+/// Order in which a scene traces its shapes
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ScenePlayback {
+    /// First shape to last
+    #[default]
+    Forward,
+    /// Last shape to first, each one traced backwards
+    Reverse,
+    /// Forward, then straight back, within a single trace
+    ///
+    /// Both passes share one trace, so each gets half the time and the beam
+    /// never jumps from the last shape back to the first. The direction
+    /// doesn't change from one trace to the next.
+    PingPong,
+}
+
+impl ScenePlayback {
+    /// Get all playback orders
+    pub fn all() -> &'static [ScenePlayback] {
+        &[
+            ScenePlayback::Forward,
+            ScenePlayback::Reverse,
+            ScenePlayback::PingPong,
+        ]
+    }
+
+    /// Get the name of this playback order
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScenePlayback::Forward => "Forward",
+            ScenePlayback::Reverse => "Reverse",
+            ScenePlayback::PingPong => "Ping-Pong",
+        }
+    }
+
+    /// Position in the forward composition to draw at `t`
+    fn map(&self, t: f32) -> f32 {
+        match self {
+            ScenePlayback::Forward => t,
+            ScenePlayback::Reverse => 1.0 - t,
+            ScenePlayback::PingPong if t < 0.5 => t * 2.0,
+            ScenePlayback::PingPong => 2.0 - t * 2.0,
+        }
+    }
 }
 
 /// A scene containing multiple shapes
@@ -65,9 +153,16 @@ impl SceneShape {
 /// Shapes are drawn in sequence, with time allocated based on their weights.
 /// For example, with two shapes of weight 1.0 each, the first shape is drawn
 /// for t in [0, 0.5) and the second for t in [0.5, 1.0).
+///
+/// With `proportional_to_length` enabled, each shape's share is also scaled
+/// by its `length()`, so every shape is drawn at a similar beam speed.
 pub struct Scene {
     /// Shapes in the scene
     shapes: Vec<SceneShape>,
+    /// Whether time is allocated by weight × length instead of weight alone
+    proportional_to_length: bool,
+    /// Order the shapes are traced in
+    playback: ScenePlayback,
     /// Cached time boundaries for each shape (computed from weights)
     /// Each entry is (start_t, end_t, shape_index)
     boundaries: Vec<(f32, f32, usize)>,
@@ -80,6 +175,8 @@ impl Scene {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             shapes: Vec::new(),
+            proportional_to_length: false,
+            playback: ScenePlayback::Forward,
             boundaries: Vec::new(),
             name: name.into(),
         }
@@ -99,6 +196,26 @@ impl Scene {
         self
     }
 
+    /// Whether a shape with these flags is drawn
+    ///
+    /// While any shape in the scene is soloed only soloed shapes are drawn,
+    /// and solo wins over mute: a muted shape that is soloed is drawn. With
+    /// nothing soloed, every enabled shape is drawn.
+    pub fn is_drawn(enabled: bool, soloed: bool, any_soloed: bool) -> bool {
+        if any_soloed {
+            soloed
+        } else {
+            enabled
+        }
+    }
+
+    /// Add several configured shape entries, recomputing boundaries once
+    pub fn add_entries(&mut self, entries: impl IntoIterator<Item = SceneShape>) -> &mut Self {
+        self.shapes.extend(entries);
+        self.recompute_boundaries();
+        self
+    }
+
     /// Remove a shape by index
     pub fn remove(&mut self, index: usize) -> Option<SceneShape> {
         if index < self.shapes.len() {
@@ -151,6 +268,39 @@ impl Scene {
         }
     }
 
+    /// Mute or unmute a shape and recompute boundaries
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(shape) = self.shapes.get_mut(index) {
+            shape.set_enabled(enabled);
+            self.recompute_boundaries();
+        }
+    }
+
+    /// Solo or unsolo a shape and recompute boundaries
+    ///
+    /// While any shape is soloed, only soloed shapes are drawn, even if
+    /// they are muted (see `Scene::is_drawn`).
+    pub fn set_soloed(&mut self, index: usize, soloed: bool) {
+        if let Some(shape) = self.shapes.get_mut(index) {
+            shape.set_soloed(soloed);
+            self.recompute_boundaries();
+        }
+    }
+
+    /// Whether a shape is drawn: soloed shapes win, otherwise enabled ones
+    fn is_active(&self, shape: &SceneShape) -> bool {
+        Self::is_drawn(
+            shape.enabled,
+            shape.soloed,
+            self.shapes.iter().any(|s| s.soloed),
+        )
+    }
+
+    /// Number of shapes currently drawn
+    pub fn active_count(&self) -> usize {
+        self.shapes.iter().filter(|s| self.is_active(s)).count()
+    }
+
     /// Update a shape's weight and recompute boundaries
     pub fn set_weight(&mut self, index: usize, weight: f32) {
         if let Some(shape) = self.shapes.get_mut(index) {
@@ -159,6 +309,47 @@ impl Scene {
         }
     }
 
+    /// Update a shape's amplitude
+    ///
+    /// Boundaries are recomputed since the drawn length changes.
+    pub fn set_amplitude(&mut self, index: usize, amplitude: f32) {
+        if let Some(shape) = self.shapes.get_mut(index) {
+            shape.set_amplitude(amplitude);
+            self.recompute_boundaries();
+        }
+    }
+
+    /// Whether time is allocated in proportion to shape length
+    pub fn proportional_to_length(&self) -> bool {
+        self.proportional_to_length
+    }
+
+    /// Allocate time by weight × length so all shapes draw at a similar beam speed
+    pub fn set_proportional_to_length(&mut self, enabled: bool) {
+        self.proportional_to_length = enabled;
+        self.recompute_boundaries();
+    }
+
+    /// Order the shapes are traced in
+    pub fn playback(&self) -> ScenePlayback {
+        self.playback
+    }
+
+    /// Trace the shapes forwards, backwards, or there and back
+    pub fn set_playback(&mut self, playback: ScenePlayback) {
+        self.playback = playback;
+    }
+
+    /// Effective time share of a shape before normalization
+    fn share(&self, shape: &SceneShape) -> f32 {
+        if self.proportional_to_length {
+            // Keep zero-length shapes (dots) from vanishing entirely
+            shape.weight * shape.length().max(MIN_SHARE_LENGTH)
+        } else {
+            shape.weight
+        }
+    }
+
     /// Recompute time boundaries based on current weights
     fn recompute_boundaries(&mut self) {
         self.boundaries.clear();
@@ -167,8 +358,8 @@ impl Scene {
         let total_weight: f32 = self
             .shapes
             .iter()
-            .filter(|s| s.enabled)
-            .map(|s| s.weight)
+            .filter(|s| self.is_active(s))
+            .map(|s| self.share(s))
             .sum();
 
         if total_weight <= 0.0 {
@@ -178,8 +369,8 @@ impl Scene {
         // Compute boundaries
         let mut current_t = 0.0;
         for (i, shape) in self.shapes.iter().enumerate() {
-            if shape.enabled {
-                let duration = shape.weight / total_weight;
+            if self.is_active(shape) {
+                let duration = self.share(shape) / total_weight;
                 self.boundaries.push((current_t, current_t + duration, i));
                 current_t += duration;
             }
@@ -207,35 +398,67 @@ impl Scene {
 
 impl Shape for Scene {
     fn sample(&self, t: f32) -> (f32, f32) {
+        let t = self.playback.map(t);
         if let Some((idx, local_t)) = self.find_shape_at(t) {
-            self.shapes[idx].shape.sample(local_t)
+            self.shapes[idx].sample(local_t)
         } else if !self.shapes.is_empty() {
             // Fallback to first shape
-            self.shapes[0].shape.sample(t)
+            self.shapes[0].sample(t)
         } else {
             (0.0, 0.0)
         }
     }
 
+    fn is_blanked(&self, t: f32) -> bool {
+        let t = self.playback.map(t);
+        self.find_shape_at(t)
+            .is_some_and(|(idx, local_t)| self.shapes[idx].shape.is_blanked(local_t))
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 
     fn length(&self) -> f32 {
-        // Sum of all shape lengths
-        self.shapes
+        // Sum of all shape lengths, traced twice when ping-ponging
+        let length: f32 = self
+            .shapes
             .iter()
-            .filter(|s| s.enabled)
-            .map(|s| s.shape.length() * s.weight)
-            .sum()
+            .filter(|s| self.is_active(s))
+            .map(|s| s.length() * s.weight)
+            .sum();
+        if self.playback == ScenePlayback::PingPong {
+            length * 2.0
+        } else {
+            length
+        }
     }
 
     fn is_closed(&self) -> bool {
-        // Scene is closed if all shapes are closed
+        // Scene is closed if all shapes are closed; ping-pong always
+        // comes back to where it started
+        self.playback == ScenePlayback::PingPong
+            || self
+                .shapes
+                .iter()
+                .filter(|s| self.is_active(s))
+                .all(|s| s.shape.is_closed())
+    }
+
+    fn suggested_samples(&self) -> Option<usize> {
+        // Each shape only gets a slice of the trace, so hints add up
+        self.shapes
+            .iter()
+            .filter(|s| self.is_active(s))
+            .filter_map(|s| s.shape.suggested_samples())
+            .reduce(|a, b| a + b)
+    }
+
+    fn has_points(&self) -> bool {
         self.shapes
             .iter()
-            .filter(|s| s.enabled)
-            .all(|s| s.shape.is_closed())
+            .filter(|s| self.is_active(s))
+            .any(|s| s.shape.has_points())
     }
 }
 
@@ -281,6 +504,59 @@ mod tests {
         assert!((x2 - 0.3).abs() < 0.01);
     }
 
+    #[test]
+    fn test_solo_isolates_shape() {
+        let mut scene = Scene::new("Solo");
+        scene.add(Circle::new(0.2));
+        scene.add(Circle::new(0.5));
+        scene.add(Circle::new(0.8));
+        assert_eq!(scene.active_count(), 3);
+
+        scene.set_soloed(1, true);
+        assert_eq!(scene.active_count(), 1);
+        assert_eq!(scene.boundaries, vec![(0.0, 1.0, 1)]);
+        for t in [0.0, 0.4, 0.9] {
+            let (x, y) = scene.sample(t);
+            assert!(((x * x + y * y).sqrt() - 0.5).abs() < 0.01);
+        }
+
+        // Solo overrides mute, and unsoloing restores the enabled set
+        scene.set_enabled(1, false);
+        assert_eq!(scene.active_count(), 1);
+        scene.set_soloed(1, false);
+        assert_eq!(scene.active_count(), 2);
+        assert!(Scene::is_drawn(false, true, true));
+        assert!(!Scene::is_drawn(true, false, true));
+    }
+
+    #[test]
+    fn test_add_entries() {
+        let mut scene = Scene::new("Batch");
+        let mut small = SceneShape::with_weight(Circle::new(0.2), 3.0);
+        small.set_amplitude(0.5);
+        scene.add_entries([small, SceneShape::new(Circle::new(0.8))]);
+        assert_eq!(scene.len(), 2);
+        assert_eq!(scene.boundaries, vec![(0.0, 0.75, 0), (0.75, 1.0, 1)]);
+    }
+
+    #[test]
+    fn test_proportional_to_length() {
+        let mut scene = Scene::new("Lengths");
+        scene.add(Circle::new(0.2));
+        scene.add(Circle::new(0.6));
+
+        // Weight alone splits evenly
+        assert!((scene.boundaries[0].1 - 0.5).abs() < 1e-5);
+
+        scene.set_proportional_to_length(true);
+        let short = Circle::new(0.2).length();
+        let long = Circle::new(0.6).length();
+        let (s0, e0, _) = scene.boundaries[0];
+        let (s1, e1, _) = scene.boundaries[1];
+        let ratio = (e1 - s1) / (e0 - s0);
+        assert!((ratio - long / short).abs() < 1e-3);
+    }
+
     #[test]
     fn test_weighted_shapes() {
         let mut scene = Scene::new("Weighted");
@@ -301,4 +577,48 @@ mod tests {
         let dist = (x * x + y * y).sqrt();
         assert!((dist - 0.3).abs() < 0.1);
     }
+
+    #[test]
+    fn test_reverse_playback_mirrors_t() {
+        let mut scene = Scene::new("Test");
+        scene.add(Circle::new(0.5));
+        scene.add_weighted(crate::shapes::Line::new(-0.5, -0.5, 0.5, 0.2), 2.0);
+        scene.add(crate::shapes::Rectangle::new(0.6, 0.4));
+        let forward: Vec<(f32, f32)> = (0..=40).map(|i| scene.sample(i as f32 / 40.0)).collect();
+
+        let close =
+            |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4;
+
+        scene.set_playback(ScenePlayback::Reverse);
+        for (i, &expected) in forward.iter().enumerate() {
+            let t = 1.0 - i as f32 / 40.0;
+            assert!(close(scene.sample(t), expected), "t = {}", t);
+        }
+
+        // Ping-pong goes through the forward composition and back in one trace
+        scene.set_playback(ScenePlayback::PingPong);
+        assert!(close(scene.sample(0.25), forward[20]));
+        assert!(close(scene.sample(0.75), forward[20]));
+        assert!(close(scene.sample(0.9), forward[8]));
+    }
+
+    #[test]
+    fn test_amplitude_scales_entry() {
+        let mut scene = Scene::new("Trim");
+        scene.add(Circle::new(0.5));
+        scene.add(Circle::new(0.5));
+        scene.set_amplitude(1, 0.5);
+
+        let circle = Circle::new(0.5);
+        for i in 0..10 {
+            let local_t = i as f32 / 10.0;
+            let (cx, cy) = circle.sample(local_t);
+
+            // First half untouched, second half at half size
+            let (x, y) = scene.sample(local_t * 0.5);
+            assert!((x - cx).abs() < 1e-5 && (y - cy).abs() < 1e-5);
+            let (x, y) = scene.sample(0.5 + local_t * 0.5);
+            assert!((x - cx * 0.5).abs() < 1e-5 && (y - cy * 0.5).abs() < 1e-5);
+        }
+    }
 }
@@ -0,0 +1,122 @@
+//! Golden snapshots of shape geometry for regression tests
+//!
+//! `assert_shape_snapshot` samples a shape and compares the points with a
+//! golden list stored under `tests/snapshots/`, one `x,y` pair per line.
+//! A missing golden is written on the first run and the test passes; after
+//! a deliberate change in geometry, delete the file to record it again.
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::traits::Shape;
+
+/// Largest difference in either coordinate still treated as a match
+const TOLERANCE: f32 = 1e-4;
+
+/// Location of the golden file for `name`
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.csv", name))
+}
+
+/// Sample `shape` at `n` evenly spaced points and compare them with the
+/// golden snapshot `name`, writing it if it doesn't exist yet
+pub fn assert_shape_snapshot<S: Shape + ?Sized>(shape: &S, n: usize, name: &str) {
+    let points: Vec<(f32, f32)> = (0..n).map(|i| shape.sample(i as f32 / n as f32)).collect();
+    let path = snapshot_path(name);
+
+    let Ok(golden) = fs::read_to_string(&path) else {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("create snapshot directory");
+        }
+        fs::write(&path, format_points(&points)).expect("write snapshot");
+        eprintln!("Wrote new snapshot {}", path.display());
+        return;
+    };
+
+    let expected = parse_points(&golden)
+        .unwrap_or_else(|line| panic!("{}: malformed snapshot line {:?}", name, line));
+    assert_eq!(
+        points.len(),
+        expected.len(),
+        "{}: sampled {} points, snapshot has {}",
+        name,
+        points.len(),
+        expected.len()
+    );
+    for (i, (&got, &want)) in points.iter().zip(&expected).enumerate() {
+        assert!(
+            (got.0 - want.0).abs() <= TOLERANCE && (got.1 - want.1).abs() <= TOLERANCE,
+            "{}: point {} is {:?}, snapshot has {:?}",
+            name,
+            i,
+            got,
+            want
+        );
+    }
+}
+
+fn format_points(points: &[(f32, f32)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{:.6},{:.6}\n", x, y))
+        .collect()
+}
+
+/// Parse `x,y` lines, returning the first line that isn't one
+fn parse_points(text: &str) -> Result<Vec<(f32, f32)>, &str> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (x, y) = line.split_once(',').ok_or(line)?;
+            Ok((
+                x.trim().parse().map_err(|_| line)?,
+                y.trim().parse().map_err(|_| line)?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Camera, Circle, Mesh3DOptions, Mesh3DShape, Polygon, Rectangle};
+    use nalgebra::Point3;
+
+    #[test]
+    fn test_snapshot_format_round_trips() {
+        let points = vec![(0.5, -0.25), (-1.0, 0.125)];
+        assert_eq!(parse_points(&format_points(&points)), Ok(points));
+        assert_eq!(parse_points("0.1,0.2\nnope\n"), Err("nope"));
+    }
+
+    #[test]
+    fn test_circle_snapshot() {
+        assert_shape_snapshot(&Circle::new(0.8), 64, "circle");
+    }
+
+    #[test]
+    fn test_rectangle_snapshot() {
+        assert_shape_snapshot(&Rectangle::new(1.2, 0.6), 64, "rectangle");
+    }
+
+    #[test]
+    fn test_polygon_snapshot() {
+        assert_shape_snapshot(&Polygon::regular(5, 0.7), 64, "pentagon");
+    }
+
+    #[test]
+    fn test_mesh_projection_snapshots() {
+        let options = || Mesh3DOptions {
+            auto_rotate: false,
+            ..Default::default()
+        };
+        assert_shape_snapshot(&Mesh3DShape::cube(options()), 128, "cube");
+
+        let camera = Camera::new(Point3::new(2.0, 1.5, 3.0), Point3::origin());
+        let tetrahedron = Mesh3DShape::tetrahedron(options()).with_camera(camera);
+        assert_shape_snapshot(&tetrahedron, 128, "tetrahedron");
+    }
+}
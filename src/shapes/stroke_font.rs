@@ -0,0 +1,496 @@
+//! Built-in single-stroke vector font
+//!
+//! Outline fonts describe the edge of each filled letter, so the beam
+//! traces every stroke twice, once along each side. A single-stroke font
+//! describes the skeleton instead, the way an engraving machine or pen
+//! plotter draws letters, which reads much more cleanly on a scope.
+//!
+//! The glyphs are drawn for this crate on a small grid rather than taken
+//! from an existing font. The baseline is at y = 0, capitals and
+//! ascenders reach y = 6, lowercase letters have an x-height of 4 and
+//! descenders drop to y = -2.
+
+/// Gap between letters, in grid units
+pub const LETTER_GAP: f32 = 2.0;
+
+/// Advance of a space, in grid units
+pub const SPACE_ADVANCE: f32 = 4.0;
+
+/// A polyline the pen draws without lifting
+type Stroke = &'static [(f32, f32)];
+
+/// Strokes for a character, or `None` if the font doesn't cover it
+///
+/// A space has no strokes but is covered.
+pub fn glyph(ch: char) -> Option<&'static [Stroke]> {
+    let strokes: &'static [Stroke] = match ch {
+        ' ' => &[],
+        'A' => &[
+            &[(0.0, 0.0), (2.0, 6.0), (4.0, 0.0)],
+            &[(1.0, 3.0), (3.0, 3.0)],
+        ],
+        'B' => &[
+            &[
+                (0.0, 0.0),
+                (0.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 4.0),
+                (3.0, 3.0),
+                (0.0, 3.0),
+            ],
+            &[(3.0, 3.0), (4.0, 2.0), (4.0, 1.0), (3.0, 0.0), (0.0, 0.0)],
+        ],
+        'C' => &[&[
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+        ]],
+        'D' => &[&[
+            (0.0, 0.0),
+            (0.0, 6.0),
+            (2.0, 6.0),
+            (4.0, 4.0),
+            (4.0, 2.0),
+            (2.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        'E' => &[
+            &[(4.0, 6.0), (0.0, 6.0), (0.0, 0.0), (4.0, 0.0)],
+            &[(0.0, 3.0), (3.0, 3.0)],
+        ],
+        'F' => &[
+            &[(4.0, 6.0), (0.0, 6.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (3.0, 3.0)],
+        ],
+        'G' => &[&[
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 3.0),
+            (2.0, 3.0),
+        ]],
+        'H' => &[
+            &[(0.0, 0.0), (0.0, 6.0)],
+            &[(4.0, 0.0), (4.0, 6.0)],
+            &[(0.0, 3.0), (4.0, 3.0)],
+        ],
+        'I' => &[&[(0.0, 0.0), (0.0, 6.0)]],
+        'J' => &[&[(4.0, 6.0), (4.0, 1.0), (3.0, 0.0), (1.0, 0.0), (0.0, 1.0)]],
+        'K' => &[
+            &[(0.0, 0.0), (0.0, 6.0)],
+            &[(4.0, 6.0), (0.0, 2.0)],
+            &[(1.0, 3.0), (4.0, 0.0)],
+        ],
+        'L' => &[&[(0.0, 6.0), (0.0, 0.0), (4.0, 0.0)]],
+        'M' => &[&[(0.0, 0.0), (0.0, 6.0), (2.0, 3.0), (4.0, 6.0), (4.0, 0.0)]],
+        'N' => &[&[(0.0, 0.0), (0.0, 6.0), (4.0, 0.0), (4.0, 6.0)]],
+        'O' => &[&[
+            (1.0, 0.0),
+            (0.0, 1.0),
+            (0.0, 5.0),
+            (1.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 1.0),
+            (3.0, 0.0),
+            (1.0, 0.0),
+        ]],
+        'P' => &[&[
+            (0.0, 0.0),
+            (0.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 4.0),
+            (3.0, 3.0),
+            (0.0, 3.0),
+        ]],
+        'Q' => &[
+            &[
+                (1.0, 0.0),
+                (0.0, 1.0),
+                (0.0, 5.0),
+                (1.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 1.0),
+                (3.0, 0.0),
+                (1.0, 0.0),
+            ],
+            &[(2.0, 2.0), (4.0, 0.0)],
+        ],
+        'R' => &[
+            &[
+                (0.0, 0.0),
+                (0.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 4.0),
+                (3.0, 3.0),
+                (0.0, 3.0),
+            ],
+            &[(2.0, 3.0), (4.0, 0.0)],
+        ],
+        'S' => &[&[
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 4.0),
+            (1.0, 3.0),
+            (3.0, 3.0),
+            (4.0, 2.0),
+            (4.0, 1.0),
+            (3.0, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+        ]],
+        'T' => &[&[(0.0, 6.0), (4.0, 6.0)], &[(2.0, 6.0), (2.0, 0.0)]],
+        'U' => &[&[
+            (0.0, 6.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 6.0),
+        ]],
+        'V' => &[&[(0.0, 6.0), (2.0, 0.0), (4.0, 6.0)]],
+        'W' => &[&[(0.0, 6.0), (1.0, 0.0), (2.0, 4.0), (3.0, 0.0), (4.0, 6.0)]],
+        'X' => &[&[(0.0, 0.0), (4.0, 6.0)], &[(0.0, 6.0), (4.0, 0.0)]],
+        'Y' => &[
+            &[(0.0, 6.0), (2.0, 3.0), (4.0, 6.0)],
+            &[(2.0, 3.0), (2.0, 0.0)],
+        ],
+        'Z' => &[&[(0.0, 6.0), (4.0, 6.0), (0.0, 0.0), (4.0, 0.0)]],
+        'a' => &[
+            &[(3.0, 4.0), (3.0, 0.0)],
+            &[
+                (3.0, 3.0),
+                (2.0, 4.0),
+                (1.0, 4.0),
+                (0.0, 3.0),
+                (0.0, 1.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 1.0),
+            ],
+        ],
+        'b' => &[
+            &[(0.0, 6.0), (0.0, 0.0)],
+            &[
+                (0.0, 3.0),
+                (1.0, 4.0),
+                (2.0, 4.0),
+                (3.0, 3.0),
+                (3.0, 1.0),
+                (2.0, 0.0),
+                (1.0, 0.0),
+                (0.0, 1.0),
+            ],
+        ],
+        'c' => &[&[
+            (3.0, 3.0),
+            (2.0, 4.0),
+            (1.0, 4.0),
+            (0.0, 3.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (3.0, 1.0),
+        ]],
+        'd' => &[
+            &[(3.0, 6.0), (3.0, 0.0)],
+            &[
+                (3.0, 3.0),
+                (2.0, 4.0),
+                (1.0, 4.0),
+                (0.0, 3.0),
+                (0.0, 1.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 1.0),
+            ],
+        ],
+        'e' => &[&[
+            (0.0, 2.0),
+            (3.0, 2.0),
+            (3.0, 3.0),
+            (2.0, 4.0),
+            (1.0, 4.0),
+            (0.0, 3.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+        ]],
+        'f' => &[
+            &[(3.0, 6.0), (2.0, 6.0), (1.0, 5.0), (1.0, 0.0)],
+            &[(0.0, 4.0), (2.0, 4.0)],
+        ],
+        'g' => &[
+            &[
+                (3.0, 4.0),
+                (3.0, -1.0),
+                (2.0, -2.0),
+                (1.0, -2.0),
+                (0.0, -1.0),
+            ],
+            &[
+                (3.0, 3.0),
+                (2.0, 4.0),
+                (1.0, 4.0),
+                (0.0, 3.0),
+                (0.0, 1.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 1.0),
+            ],
+        ],
+        'h' => &[
+            &[(0.0, 6.0), (0.0, 0.0)],
+            &[(0.0, 3.0), (1.0, 4.0), (2.0, 4.0), (3.0, 3.0), (3.0, 0.0)],
+        ],
+        'i' => &[&[(0.0, 4.0), (0.0, 0.0)], &[(0.0, 5.5), (0.0, 6.0)]],
+        'j' => &[
+            &[(1.0, 4.0), (1.0, -1.0), (0.0, -2.0)],
+            &[(1.0, 5.5), (1.0, 6.0)],
+        ],
+        'k' => &[
+            &[(0.0, 6.0), (0.0, 0.0)],
+            &[(3.0, 4.0), (0.0, 1.0)],
+            &[(1.0, 2.0), (3.0, 0.0)],
+        ],
+        'l' => &[&[(0.0, 6.0), (0.0, 0.0)]],
+        'm' => &[
+            &[(0.0, 0.0), (0.0, 4.0)],
+            &[
+                (0.0, 3.0),
+                (1.0, 4.0),
+                (2.0, 3.0),
+                (3.0, 4.0),
+                (4.0, 3.0),
+                (4.0, 0.0),
+            ],
+            &[(2.0, 3.0), (2.0, 0.0)],
+        ],
+        'n' => &[
+            &[(0.0, 0.0), (0.0, 4.0)],
+            &[(0.0, 3.0), (1.0, 4.0), (2.0, 4.0), (3.0, 3.0), (3.0, 0.0)],
+        ],
+        'o' => &[&[
+            (1.0, 0.0),
+            (0.0, 1.0),
+            (0.0, 3.0),
+            (1.0, 4.0),
+            (2.0, 4.0),
+            (3.0, 3.0),
+            (3.0, 1.0),
+            (2.0, 0.0),
+            (1.0, 0.0),
+        ]],
+        'p' => &[
+            &[(0.0, 4.0), (0.0, -2.0)],
+            &[
+                (0.0, 3.0),
+                (1.0, 4.0),
+                (2.0, 4.0),
+                (3.0, 3.0),
+                (3.0, 1.0),
+                (2.0, 0.0),
+                (1.0, 0.0),
+                (0.0, 1.0),
+            ],
+        ],
+        'q' => &[
+            &[(3.0, 4.0), (3.0, -2.0)],
+            &[
+                (3.0, 3.0),
+                (2.0, 4.0),
+                (1.0, 4.0),
+                (0.0, 3.0),
+                (0.0, 1.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 1.0),
+            ],
+        ],
+        'r' => &[
+            &[(0.0, 0.0), (0.0, 4.0)],
+            &[(0.0, 3.0), (1.0, 4.0), (3.0, 4.0)],
+        ],
+        's' => &[&[
+            (3.0, 3.0),
+            (2.0, 4.0),
+            (1.0, 4.0),
+            (0.0, 3.0),
+            (1.0, 2.0),
+            (2.0, 2.0),
+            (3.0, 1.0),
+            (2.0, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+        ]],
+        't' => &[
+            &[(1.0, 6.0), (1.0, 1.0), (2.0, 0.0), (3.0, 0.0)],
+            &[(0.0, 4.0), (2.0, 4.0)],
+        ],
+        'u' => &[
+            &[(0.0, 4.0), (0.0, 1.0), (1.0, 0.0), (2.0, 0.0), (3.0, 1.0)],
+            &[(3.0, 4.0), (3.0, 0.0)],
+        ],
+        'v' => &[&[(0.0, 4.0), (1.5, 0.0), (3.0, 4.0)]],
+        'w' => &[&[(0.0, 4.0), (1.0, 0.0), (2.0, 3.0), (3.0, 0.0), (4.0, 4.0)]],
+        'x' => &[&[(0.0, 0.0), (3.0, 4.0)], &[(0.0, 4.0), (3.0, 0.0)]],
+        'y' => &[
+            &[(0.0, 4.0), (1.5, 0.0)],
+            &[(3.0, 4.0), (1.0, -2.0), (0.0, -2.0)],
+        ],
+        'z' => &[&[(0.0, 4.0), (3.0, 4.0), (0.0, 0.0), (3.0, 0.0)]],
+        '0' => &[
+            &[
+                (1.0, 0.0),
+                (0.0, 1.0),
+                (0.0, 5.0),
+                (1.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 1.0),
+                (3.0, 0.0),
+                (1.0, 0.0),
+            ],
+            &[(1.0, 1.0), (3.0, 5.0)],
+        ],
+        '1' => &[
+            &[(1.0, 5.0), (2.0, 6.0), (2.0, 0.0)],
+            &[(1.0, 0.0), (3.0, 0.0)],
+        ],
+        '2' => &[&[
+            (0.0, 5.0),
+            (1.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 4.0),
+            (0.0, 0.0),
+            (4.0, 0.0),
+        ]],
+        '3' => &[
+            &[
+                (0.0, 5.0),
+                (1.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 4.0),
+                (3.0, 3.0),
+                (1.0, 3.0),
+            ],
+            &[
+                (3.0, 3.0),
+                (4.0, 2.0),
+                (4.0, 1.0),
+                (3.0, 0.0),
+                (1.0, 0.0),
+                (0.0, 1.0),
+            ],
+        ],
+        '4' => &[&[(3.0, 0.0), (3.0, 6.0), (0.0, 2.0), (4.0, 2.0)]],
+        '5' => &[&[
+            (4.0, 6.0),
+            (0.0, 6.0),
+            (0.0, 3.0),
+            (3.0, 3.0),
+            (4.0, 2.0),
+            (4.0, 1.0),
+            (3.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        '6' => &[&[
+            (4.0, 5.0),
+            (3.0, 6.0),
+            (1.0, 6.0),
+            (0.0, 5.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 2.0),
+            (3.0, 3.0),
+            (0.0, 3.0),
+        ]],
+        '7' => &[&[(0.0, 6.0), (4.0, 6.0), (1.0, 0.0)]],
+        '8' => &[&[
+            (1.0, 3.0),
+            (0.0, 4.0),
+            (0.0, 5.0),
+            (1.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 4.0),
+            (3.0, 3.0),
+            (1.0, 3.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 1.0),
+            (4.0, 2.0),
+            (3.0, 3.0),
+        ]],
+        '9' => &[&[
+            (4.0, 3.0),
+            (1.0, 3.0),
+            (0.0, 4.0),
+            (0.0, 5.0),
+            (1.0, 6.0),
+            (3.0, 6.0),
+            (4.0, 5.0),
+            (4.0, 1.0),
+            (3.0, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+        ]],
+        '.' => &[&[(0.0, 0.0), (0.0, 0.5)]],
+        ',' => &[&[(0.5, 0.5), (0.0, -1.0)]],
+        '-' => &[&[(0.0, 3.0), (3.0, 3.0)]],
+        '+' => &[&[(2.0, 1.0), (2.0, 5.0)], &[(0.0, 3.0), (4.0, 3.0)]],
+        '=' => &[&[(0.0, 2.0), (4.0, 2.0)], &[(0.0, 4.0), (4.0, 4.0)]],
+        '/' => &[&[(0.0, 0.0), (4.0, 6.0)]],
+        '\'' => &[&[(0.0, 6.0), (0.0, 4.5)]],
+        ':' => &[&[(0.0, 4.0), (0.0, 4.5)], &[(0.0, 0.0), (0.0, 0.5)]],
+        '!' => &[&[(0.0, 6.0), (0.0, 2.0)], &[(0.0, 0.0), (0.0, 0.5)]],
+        '?' => &[
+            &[
+                (0.0, 5.0),
+                (1.0, 6.0),
+                (3.0, 6.0),
+                (4.0, 5.0),
+                (4.0, 4.0),
+                (2.0, 3.0),
+                (2.0, 2.0),
+            ],
+            &[(2.0, 0.0), (2.0, 0.5)],
+        ],
+        _ => return None,
+    };
+    Some(strokes)
+}
+
+/// Horizontal advance for a glyph's strokes: its width plus the letter gap
+pub fn advance(strokes: &[Stroke]) -> f32 {
+    if strokes.is_empty() {
+        return SPACE_ADVANCE;
+    }
+    let width = strokes
+        .iter()
+        .flat_map(|s| s.iter())
+        .fold(0.0f32, |w, &(x, _)| w.max(x));
+    width + LETTER_GAP
+}
@@ -7,11 +7,21 @@
 //! - Normalizing coordinates to [-1, 1] range
 
 use std::path::Path as FilePath;
+use std::sync::Arc;
 use thiserror::Error;
 
+use super::bezier::{flatten_cubic, flatten_quadratic, Flattening};
+use super::normalize::{clamp_point, Fit, NormalizeMode};
+use super::optimize::optimize_order;
 use super::path::Path;
 use super::traits::Shape;
 
+/// Deepest group nesting accepted before import gives up
+pub const MAX_GROUP_DEPTH: usize = 64;
+
+/// Most points an import may produce, across all paths
+pub const MAX_POINTS: usize = 2_000_000;
+
 /// Errors that can occur during SVG import
 #[derive(Error, Debug)]
 pub enum SvgError {
@@ -23,24 +33,63 @@ pub enum SvgError {
 
     #[error("SVG contains no paths")]
     NoPaths,
+
+    #[error("SVG is too complex: {0}")]
+    TooComplex(String),
 }
 
 /// Options for SVG import
+#[derive(Clone, Debug)]
 pub struct SvgOptions {
     /// Number of points to sample per curve segment
     pub curve_samples: usize,
-    /// Whether to close open paths
+    /// Subdivide curves by flatness instead of using `curve_samples`
+    pub adaptive: bool,
+    /// Largest distance an adaptive curve may stray from its chord, in normalized units
+    pub curve_tolerance: f32,
+    /// Whether to also close subpaths that don't end with `Z`
     pub close_paths: bool,
     /// Simplification tolerance (0 = no simplification)
     pub simplify_tolerance: f32,
+    /// Shift the trace so its point centroid sits at the origin
+    pub recenter: bool,
+    /// Keep the source proportions instead of stretching to fill the square
+    pub preserve_aspect: bool,
+    /// Map the viewbox to the square, or fit the drawing itself inside it
+    pub normalize: NormalizeMode,
+    /// Treat each top-level element (usually a `<g>`) as one animation frame
+    pub frames_from_groups: bool,
+    /// Animation frames per second when `frames_from_groups` is on
+    pub frame_rate: f32,
+    /// Reorder and flip subpaths so the trace jumps as little as possible
+    pub optimize_order: bool,
 }
 
 impl Default for SvgOptions {
     fn default() -> Self {
         Self {
             curve_samples: 8,
+            adaptive: false,
+            curve_tolerance: 0.002,
             close_paths: false,
             simplify_tolerance: 0.0,
+            recenter: false,
+            preserve_aspect: true,
+            normalize: NormalizeMode::Fill,
+            frames_from_groups: false,
+            frame_rate: 12.0,
+            optimize_order: false,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// How curve segments are turned into points
+    pub fn flattening(&self) -> Flattening {
+        if self.adaptive {
+            Flattening::Adaptive(self.curve_tolerance)
+        } else {
+            Flattening::Fixed(self.curve_samples)
         }
     }
 }
@@ -52,8 +101,14 @@ pub struct SvgShape {
     paths: Vec<Path>,
     /// Combined path for rendering
     combined: Path,
+    /// Animation frames, one per top-level element (empty = static)
+    frames: Vec<Path>,
+    /// Index of the frame being shown
+    frame: usize,
     /// Original filename
     name: String,
+    /// Parsed document, kept so option changes don't re-parse the file
+    tree: Arc<usvg::Tree>,
 }
 
 impl SvgShape {
@@ -77,6 +132,27 @@ impl SvgShape {
         let tree = usvg::Tree::from_data(data, &usvg::Options::default())
             .map_err(|e| SvgError::ParseError(e.to_string()))?;
 
+        Self::from_tree(Arc::new(tree), name, options)
+    }
+
+    /// Rebuild the paths from the already parsed document with new options
+    ///
+    /// The current animation frame is kept when it still exists.
+    pub fn set_options(&mut self, options: &SvgOptions) -> Result<(), SvgError> {
+        let frame = self.frame;
+        *self = Self::from_tree(Arc::clone(&self.tree), &self.name, options)?;
+        if frame < self.frames.len() {
+            self.frame = frame;
+        }
+        Ok(())
+    }
+
+    /// Extract paths from a parsed document
+    fn from_tree(
+        tree: Arc<usvg::Tree>,
+        name: &str,
+        options: &SvgOptions,
+    ) -> Result<Self, SvgError> {
         let mut all_points: Vec<(f32, f32)> = Vec::new();
         let mut paths: Vec<Path> = Vec::new();
 
@@ -84,15 +160,24 @@ impl SvgShape {
         let view_box = tree.size();
         let width = view_box.width();
         let height = view_box.height();
-        let scale = width.max(height);
+        let (scale_x, scale_y) = if options.preserve_aspect {
+            let scale = width.max(height);
+            (scale, scale)
+        } else {
+            (width, height)
+        };
         let offset_x = width / 2.0;
         let offset_y = height / 2.0;
 
-        // Helper to normalize coordinates to [-1, 1]
+        // Helper to normalize coordinates to [-1, 1]; when fitting, the
+        // drawing is rescaled once all of it is known
         let normalize = |x: f32, y: f32| -> (f32, f32) {
-            let nx = (x - offset_x) / (scale / 2.0);
-            let ny = -(y - offset_y) / (scale / 2.0); // Flip Y axis
-            (nx.clamp(-1.0, 1.0), ny.clamp(-1.0, 1.0))
+            let nx = (x - offset_x) / (scale_x / 2.0);
+            let ny = -(y - offset_y) / (scale_y / 2.0); // SVG is Y-down, shapes are Y-up
+            match options.normalize {
+                NormalizeMode::Fill => clamp_point((nx, ny)),
+                NormalizeMode::Fit => (nx, ny),
+            }
         };
 
         // Process a path node
@@ -102,26 +187,21 @@ impl SvgShape {
             options: &SvgOptions,
             all_points: &mut Vec<(f32, f32)>,
             paths: &mut Vec<Path>,
-        ) {
+        ) -> Result<(), SvgError> {
             let mut path_points = Vec::new();
 
             for segment in path.data().segments() {
+                if all_points.len() + path_points.len() > MAX_POINTS {
+                    return Err(SvgError::TooComplex(format!(
+                        "more than {} points",
+                        MAX_POINTS
+                    )));
+                }
+
                 match segment {
                     usvg::tiny_skia_path::PathSegment::MoveTo(p) => {
-                        // Start a new subpath
-                        if !path_points.is_empty() {
-                            // Save the current path
-                            if path_points.len() >= 2 {
-                                let p = Path::with_options(
-                                    path_points.clone(),
-                                    options.close_paths,
-                                    "SVG Path".to_string(),
-                                );
-                                paths.push(p);
-                                all_points.extend(&path_points);
-                            }
-                            path_points.clear();
-                        }
+                        // Start a new subpath, saving the open one before it
+                        finish_subpath(&mut path_points, options.close_paths, all_points, paths);
                         path_points.push(normalize(p.x, p.y));
                     }
                     usvg::tiny_skia_path::PathSegment::LineTo(p) => {
@@ -132,11 +212,13 @@ impl SvgShape {
                         if let Some(&start) = path_points.last() {
                             let ctrl = normalize(p1.x, p1.y);
                             let end = normalize(p2.x, p2.y);
-                            for i in 1..=options.curve_samples {
-                                let t = i as f32 / options.curve_samples as f32;
-                                let point = quadratic_bezier(start, ctrl, end, t);
-                                path_points.push(point);
-                            }
+                            flatten_quadratic(
+                                start,
+                                ctrl,
+                                end,
+                                options.flattening(),
+                                &mut path_points,
+                            );
                         }
                     }
                     usvg::tiny_skia_path::PathSegment::CubicTo(p1, p2, p3) => {
@@ -145,85 +227,197 @@ impl SvgShape {
                             let ctrl1 = normalize(p1.x, p1.y);
                             let ctrl2 = normalize(p2.x, p2.y);
                             let end = normalize(p3.x, p3.y);
-                            for i in 1..=options.curve_samples {
-                                let t = i as f32 / options.curve_samples as f32;
-                                let point = cubic_bezier(start, ctrl1, ctrl2, end, t);
-                                path_points.push(point);
-                            }
+                            flatten_cubic(
+                                start,
+                                ctrl1,
+                                ctrl2,
+                                end,
+                                options.flattening(),
+                                &mut path_points,
+                            );
                         }
                     }
                     usvg::tiny_skia_path::PathSegment::Close => {
-                        // Close the path by connecting to the start
-                        if path_points.len() >= 2 {
-                            let p = Path::with_options(
-                                path_points.clone(),
-                                true, // closed
-                                "SVG Path".to_string(),
-                            );
-                            paths.push(p);
-                            all_points.extend(&path_points);
-                        }
-                        path_points.clear();
+                        // `Z` always closes, whatever the option says
+                        finish_subpath(&mut path_points, true, all_points, paths);
                     }
                 }
             }
 
-            // Save any remaining path
-            if path_points.len() >= 2 {
-                let p = Path::with_options(
-                    path_points.clone(),
-                    options.close_paths,
-                    "SVG Path".to_string(),
-                );
-                paths.push(p);
-                all_points.extend(&path_points);
+            // Save any remaining open subpath
+            finish_subpath(&mut path_points, options.close_paths, all_points, paths);
+            Ok(())
+        }
+
+        // Save a finished subpath as its own path and add it to the trace
+        fn finish_subpath(
+            points: &mut Vec<(f32, f32)>,
+            closed: bool,
+            all_points: &mut Vec<(f32, f32)>,
+            paths: &mut Vec<Path>,
+        ) {
+            // Drawing back to the start before `Z` would repeat a point
+            if closed && points.len() > 2 && points.first() == points.last() {
+                points.pop();
+            }
+            if points.len() >= 2 {
+                let path = Path::with_options(std::mem::take(points), closed, "SVG Path".into());
+                all_points.extend(traced_points(&path));
+                paths.push(path);
             }
+            points.clear();
         }
 
-        // Recursively process all nodes in a group
+        // Recursively process all nodes in a group, `depth` levels down
         fn process_group(
             group: &usvg::Group,
+            depth: usize,
             normalize: &impl Fn(f32, f32) -> (f32, f32),
             options: &SvgOptions,
             all_points: &mut Vec<(f32, f32)>,
             paths: &mut Vec<Path>,
-        ) {
+        ) -> Result<(), SvgError> {
+            if depth > MAX_GROUP_DEPTH {
+                return Err(SvgError::TooComplex(format!(
+                    "groups nested more than {} deep",
+                    MAX_GROUP_DEPTH
+                )));
+            }
             for child in group.children() {
                 match child {
                     usvg::Node::Path(ref path) => {
-                        process_path(path, normalize, options, all_points, paths);
+                        process_path(path, normalize, options, all_points, paths)?;
                     }
                     usvg::Node::Group(ref subgroup) => {
-                        process_group(subgroup, normalize, options, all_points, paths);
+                        process_group(subgroup, depth + 1, normalize, options, all_points, paths)?;
                     }
                     _ => {}
                 }
             }
+            Ok(())
         }
 
-        // Process the root group
-        process_group(
-            tree.root(),
-            &normalize,
-            options,
-            &mut all_points,
-            &mut paths,
-        );
+        // Process the root group, keeping each top-level element's points
+        // apart when they are to become frames
+        let mut frame_points: Vec<Vec<(f32, f32)>> = Vec::new();
+        for child in tree.root().children() {
+            let start = all_points.len();
+            match child {
+                usvg::Node::Path(ref path) => {
+                    process_path(path, &normalize, options, &mut all_points, &mut paths)?;
+                }
+                usvg::Node::Group(ref group) => {
+                    process_group(group, 1, &normalize, options, &mut all_points, &mut paths)?;
+                }
+                _ => {}
+            }
+            if options.frames_from_groups && all_points.len() > start {
+                frame_points.push(all_points[start..].to_vec());
+            }
+        }
 
         if all_points.is_empty() {
             return Err(SvgError::NoPaths);
         }
 
+        // Fit the whole drawing, so the subpaths and frames stay in register
+        if options.normalize == NormalizeMode::Fit {
+            if let Some(fit) = Fit::new(&all_points, options.preserve_aspect) {
+                let apply = |points: &mut Vec<(f32, f32)>| {
+                    points.iter_mut().for_each(|p| *p = fit.apply(*p));
+                };
+                apply(&mut all_points);
+                frame_points.iter_mut().for_each(apply);
+                paths = paths
+                    .iter()
+                    .map(|p| {
+                        let mut points = p.points().to_vec();
+                        apply(&mut points);
+                        Path::with_options(points, p.is_closed(), "SVG Path".to_string())
+                    })
+                    .collect();
+            }
+        }
+
+        // Trace the subpaths in the order that needs the fewest jumps
+        if options.optimize_order {
+            let subpaths: Vec<_> = paths.iter().map(|p| (p.points(), p.is_closed())).collect();
+            let order = optimize_order(&subpaths);
+            paths = order
+                .into_iter()
+                .map(|(i, reverse)| {
+                    let mut points = paths[i].points().to_vec();
+                    if reverse {
+                        points.reverse();
+                    }
+                    Path::with_options(points, paths[i].is_closed(), "SVG Path".to_string())
+                })
+                .collect();
+            all_points = paths.iter().flat_map(traced_points).collect();
+        }
+
+        // A single element is just a still image
+        let mut frames: Vec<Path> = if frame_points.len() > 1 {
+            frame_points
+                .into_iter()
+                .map(|points| Path::with_options(points, false, name.to_string()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         // Create combined path
-        let combined = Path::with_options(all_points, false, name.to_string());
+        let mut combined = Path::with_options(all_points, false, name.to_string());
+
+        // Move the sub-paths by the same offset and scale so they stay in register
+        if options.recenter {
+            let (dx, dy, scale) = combined.recentering();
+            combined = combined.transformed(dx, dy, scale);
+            paths = paths.iter().map(|p| p.transformed(dx, dy, scale)).collect();
+            frames = frames
+                .iter()
+                .map(|p| p.transformed(dx, dy, scale))
+                .collect();
+        }
 
         Ok(Self {
             paths,
             combined,
+            frames,
+            frame: 0,
             name: name.to_string(),
+            tree,
         })
     }
 
+    /// Number of animation frames (0 for a still SVG)
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Index of the frame being shown
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Individual animation frames
+    pub fn frames(&self) -> &[Path] {
+        &self.frames
+    }
+
+    /// Show the frame due at `seconds` when playing at `frame_rate` frames per second
+    pub fn set_time(&mut self, seconds: f32, frame_rate: f32) {
+        if !self.frames.is_empty() {
+            let index = (seconds * frame_rate.max(0.0)) as usize;
+            self.frame = index % self.frames.len();
+        }
+    }
+
+    /// The path currently being drawn: the active frame, or the whole SVG
+    fn current(&self) -> &Path {
+        self.frames.get(self.frame).unwrap_or(&self.combined)
+    }
+
     /// Get the number of paths
     pub fn path_count(&self) -> usize {
         self.paths.len()
@@ -242,7 +436,7 @@ impl SvgShape {
 
 impl Shape for SvgShape {
     fn sample(&self, t: f32) -> (f32, f32) {
-        self.combined.sample(t)
+        self.current().sample(t)
     }
 
     fn name(&self) -> &str {
@@ -250,49 +444,161 @@ impl Shape for SvgShape {
     }
 
     fn length(&self) -> f32 {
-        self.combined.length()
+        self.current().length()
     }
 
     fn is_closed(&self) -> bool {
-        self.combined.is_closed()
+        self.current().is_closed()
     }
-}
 
-/// Evaluate a quadratic Bézier curve at parameter t
-fn quadratic_bezier(p0: (f32, f32), p1: (f32, f32), p2: (f32, f32), t: f32) -> (f32, f32) {
-    let t2 = t * t;
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        self.current().bounds()
+    }
 
-    let x = mt2 * p0.0 + 2.0 * mt * t * p1.0 + t2 * p2.0;
-    let y = mt2 * p0.1 + 2.0 * mt * t * p1.1 + t2 * p2.1;
+    fn suggested_samples(&self) -> Option<usize> {
+        Some(self.current().len())
+    }
 
-    (x, y)
+    fn has_points(&self) -> bool {
+        self.current().has_points()
+    }
 }
 
-/// Evaluate a cubic Bézier curve at parameter t
-fn cubic_bezier(
-    p0: (f32, f32),
-    p1: (f32, f32),
-    p2: (f32, f32),
-    p3: (f32, f32),
-    t: f32,
-) -> (f32, f32) {
-    let t2 = t * t;
-    let t3 = t2 * t;
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
-    let mt3 = mt2 * mt;
-
-    let x = mt3 * p0.0 + 3.0 * mt2 * t * p1.0 + 3.0 * mt * t2 * p2.0 + t3 * p3.0;
-    let y = mt3 * p0.1 + 3.0 * mt2 * t * p1.1 + 3.0 * mt * t2 * p2.1 + t3 * p3.1;
-
-    (x, y)
+/// A subpath's points as the beam traces them, returning to the start if closed
+fn traced_points(path: &Path) -> impl Iterator<Item = (f32, f32)> + '_ {
+    let back = path.points().first().copied().filter(|_| path.is_closed());
+    path.points().iter().copied().chain(back)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shapes::bezier::{cubic_bezier, quadratic_bezier};
+
+    #[test]
+    fn test_top_level_groups_become_frames() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+            <g><path d="M 10 10 L 90 10" stroke="black"/></g>
+            <g><path d="M 10 50 L 90 50" stroke="black"/></g>
+            <g><path d="M 10 90 L 90 90" stroke="black"/></g>
+        </svg>"#;
+        let options = SvgOptions {
+            frames_from_groups: true,
+            ..Default::default()
+        };
+        let mut shape = SvgShape::from_data(svg, "frames", &options).unwrap();
+
+        assert_eq!(shape.frame_count(), 3);
+        let heights: Vec<f32> = shape.frames().iter().map(|f| f.sample(0.0).1).collect();
+        assert!(heights[0] > heights[1] && heights[1] > heights[2]);
+
+        // One frame per 1 / frame_rate seconds, looping
+        shape.set_time(0.25, 4.0);
+        assert_eq!(shape.sample(0.0), shape.frames()[1].sample(0.0));
+        shape.set_time(0.75, 4.0);
+        assert_eq!(shape.sample(0.0), shape.frames()[0].sample(0.0));
+
+        // Without the option the groups draw together
+        let still = SvgShape::from_data(svg, "still", &SvgOptions::default()).unwrap();
+        assert_eq!(still.frame_count(), 0);
+    }
+
+    #[test]
+    fn test_deep_nesting_is_too_complex() {
+        let nested = |depth: usize| {
+            let open = r#"<g transform="translate(0.1 0)">"#.repeat(depth);
+            let close = "</g>".repeat(depth);
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">{}<path d="M 10 10 L 90 90" stroke="black"/>{}</svg>"#,
+                open, close
+            )
+        };
+
+        assert!(SvgShape::from_data(nested(10).as_bytes(), "ok", &SvgOptions::default()).is_ok());
+        let result = SvgShape::from_data(
+            nested(MAX_GROUP_DEPTH + 10).as_bytes(),
+            "deep",
+            &SvgOptions::default(),
+        );
+        assert!(matches!(result, Err(SvgError::TooComplex(_))));
+    }
+
+    #[test]
+    fn test_fit_mode_keeps_overhang_unclamped() {
+        // A wave twice as wide as the viewbox
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+            <path d="M -50 50 L -25 40 L 0 60 L 25 40 L 50 60 L 75 40 L 100 60 L 125 40 L 150 50" stroke="black"/>
+        </svg>"#;
+        let at_edge = |shape: &SvgShape| {
+            shape
+                .combined
+                .points()
+                .iter()
+                .filter(|p| p.0.abs() == 1.0)
+                .count()
+        };
+
+        // Filling clamps the overhang flat against the sides
+        let filled = SvgShape::from_data(svg, "wide", &SvgOptions::default()).unwrap();
+        assert!(at_edge(&filled) > 2);
+
+        let options = SvgOptions {
+            normalize: NormalizeMode::Fit,
+            ..Default::default()
+        };
+        let fitted = SvgShape::from_data(svg, "wide", &options).unwrap();
+        let points = fitted.combined.points();
+        assert!(points.iter().all(|p| p.0.abs() <= 1.0 && p.1.abs() <= 1.0));
+        assert!(at_edge(&fitted) <= 2);
+        for pair in points.windows(2) {
+            assert!(pair[1].0 > pair[0].0, "flattened at {:?}", pair);
+        }
+    }
+
+    #[test]
+    fn test_subpath_closure_follows_z() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+            <path d="M 10 10 L 50 10 L 50 50 Z M 60 60 L 90 60 L 90 90" stroke="black"/>
+        </svg>"#;
+        let shape = SvgShape::from_data(svg, "mixed", &SvgOptions::default()).unwrap();
+        let closed: Vec<bool> = shape.paths().iter().map(|p| p.is_closed()).collect();
+        assert_eq!(closed, vec![true, false]);
+
+        // The trace draws the closing side before jumping to the open one
+        let square = shape.paths()[0].points();
+        let trace = shape.combined.points();
+        assert_eq!(trace[square.len()], square[0]);
+        assert_eq!(
+            shape.point_count(),
+            square.len() + 1 + shape.paths()[1].len()
+        );
+
+        // The option closes the open subpath too
+        let options = SvgOptions {
+            close_paths: true,
+            ..Default::default()
+        };
+        let shape = SvgShape::from_data(svg, "mixed", &options).unwrap();
+        assert!(shape.paths().iter().all(|p| p.is_closed()));
+    }
+
+    #[test]
+    fn test_set_options_rebuilds_from_parsed_tree() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+            <path d="M 10 10 L 90 10 L 90 90" stroke="black"/>
+        </svg>"#;
+        let mut shape = SvgShape::from_data(svg, "open", &SvgOptions::default()).unwrap();
+        assert!(!shape.paths()[0].is_closed());
+
+        shape
+            .set_options(&SvgOptions {
+                close_paths: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(shape.paths()[0].is_closed());
+        assert_eq!(shape.name(), "open");
+    }
 
     #[test]
     fn test_quadratic_bezier() {
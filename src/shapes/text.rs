@@ -5,12 +5,17 @@
 //! - Extracting glyph outlines
 //! - Converting Bézier curves to point sequences
 //! - Text layout and positioning
+//! - Single-stroke lettering from a built-in vector font
 
-use ab_glyph::{Font, FontRef, OutlineCurve, ScaleFont};
+use ab_glyph::{Font, FontArc, GlyphId, OutlineCurve, ScaleFont};
+use std::collections::HashMap;
 use std::path::Path as FilePath;
 use thiserror::Error;
 
+use super::bezier::{flatten_cubic, flatten_quadratic, Flattening};
+use super::normalize::Fit;
 use super::path::Path;
+use super::stroke_font;
 use super::traits::Shape;
 
 /// Errors that can occur during text rendering
@@ -29,26 +34,126 @@ pub enum TextError {
     NoGlyphs,
 }
 
+/// How letters are drawn
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum TextStyle {
+    /// Trace the glyph outlines of the selected font
+    #[default]
+    Outline,
+    /// Draw each letter's skeleton with the built-in single-stroke font
+    SingleStroke,
+}
+
+impl TextStyle {
+    /// Get all styles
+    pub fn all() -> &'static [TextStyle] {
+        &[TextStyle::Outline, TextStyle::SingleStroke]
+    }
+
+    /// Get the name of this style
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextStyle::Outline => "Outline",
+            TextStyle::SingleStroke => "Single stroke",
+        }
+    }
+}
+
 /// Options for text rendering
 pub struct TextOptions {
+    /// Outline font or single-stroke lettering
+    pub style: TextStyle,
     /// Font size in pixels (before normalization)
     pub size: f32,
     /// Number of points per curve segment
     pub curve_samples: usize,
+    /// Subdivide curves by flatness instead of using `curve_samples`
+    pub adaptive: bool,
+    /// Largest distance an adaptive curve may stray from its chord, in font pixels
+    pub curve_tolerance: f32,
     /// Letter spacing multiplier (1.0 = normal)
     pub letter_spacing: f32,
+    /// Shift the trace so its point centroid sits at the origin
+    pub recenter: bool,
+    /// Keep the source proportions instead of stretching to fill the square
+    pub preserve_aspect: bool,
 }
 
 impl Default for TextOptions {
     fn default() -> Self {
         Self {
+            style: TextStyle::Outline,
             size: 64.0,
             curve_samples: 8,
+            adaptive: false,
+            curve_tolerance: 0.1,
             letter_spacing: 1.0,
+            recenter: false,
+            preserve_aspect: true,
+        }
+    }
+}
+
+impl TextOptions {
+    /// How outline curves are turned into points
+    pub fn flattening(&self) -> Flattening {
+        if self.adaptive {
+            Flattening::Adaptive(self.curve_tolerance)
+        } else {
+            Flattening::Fixed(self.curve_samples)
         }
     }
 }
 
+/// Embedded Roboto Mono font
+const EMBEDDED_FONT: &[u8] = include_bytes!("../../assets/fonts/RobotoMono-Regular.ttf");
+
+/// Outline of one character, in unscaled font units
+struct CachedGlyph {
+    id: GlyphId,
+    curves: Vec<OutlineCurve>,
+}
+
+/// A parsed font plus the glyph outlines extracted from it so far
+///
+/// Keeping one of these around means a re-layout (new spacing, size or
+/// curve detail) only re-flattens the cached outlines instead of re-parsing
+/// the font and re-extracting every glyph.
+pub struct FontCache {
+    font: FontArc,
+    glyphs: HashMap<char, CachedGlyph>,
+    /// Number of glyph outlines extracted from the font
+    glyph_loads: usize,
+}
+
+impl FontCache {
+    /// Cache for the embedded default font
+    pub fn embedded() -> Self {
+        let font = FontArc::try_from_slice(EMBEDDED_FONT).expect("embedded font is valid");
+        Self::with_font(font)
+    }
+
+    /// Parse font data bytes
+    pub fn from_data(font_data: Vec<u8>) -> Result<Self, TextError> {
+        let font =
+            FontArc::try_from_vec(font_data).map_err(|e| TextError::FontError(e.to_string()))?;
+        Ok(Self::with_font(font))
+    }
+
+    fn with_font(font: FontArc) -> Self {
+        Self {
+            font,
+            glyphs: HashMap::new(),
+            glyph_loads: 0,
+        }
+    }
+
+    /// Number of glyph outlines extracted from the font so far
+    pub fn glyph_loads(&self) -> usize {
+        self.glyph_loads
+    }
+}
+
 /// A text string converted to drawable paths
 #[derive(Clone)]
 pub struct TextShape {
@@ -63,9 +168,7 @@ pub struct TextShape {
 impl TextShape {
     /// Create text shape from a string using the embedded default font
     pub fn new(text: &str, options: &TextOptions) -> Result<Self, TextError> {
-        // Use embedded Roboto Mono font
-        let font_data = include_bytes!("../../assets/fonts/RobotoMono-Regular.ttf");
-        Self::with_font_data(text, font_data, options)
+        Self::with_font(text, &mut FontCache::embedded(), options)
     }
 
     /// Create text shape from a string using a font file
@@ -78,6 +181,23 @@ impl TextShape {
         Self::with_font_data(text, &font_data, options)
     }
 
+    /// Create text shape from a string using an already parsed font
+    pub fn with_font(
+        text: &str,
+        font: &mut FontCache,
+        options: &TextOptions,
+    ) -> Result<Self, TextError> {
+        if text.is_empty() {
+            return Err(TextError::EmptyText);
+        }
+
+        if options.style == TextStyle::SingleStroke {
+            return Self::render_single_stroke(text, options);
+        }
+
+        Self::render_text(text, font, options)
+    }
+
     /// Create text shape from font data bytes
     pub fn with_font_data(
         text: &str,
@@ -88,18 +208,26 @@ impl TextShape {
             return Err(TextError::EmptyText);
         }
 
-        let font =
-            FontRef::try_from_slice(font_data).map_err(|e| TextError::FontError(e.to_string()))?;
+        // Single-stroke lettering has its own font, so the outline font is unused
+        if options.style == TextStyle::SingleStroke {
+            return Self::render_single_stroke(text, options);
+        }
 
-        Self::render_text(text, &font, options)
+        let mut font = FontCache::from_data(font_data.to_vec())?;
+        Self::render_text(text, &mut font, options)
     }
 
-    /// Render text using a font
-    fn render_text<F: Font>(
+    /// Lay out text using a font's cached glyph outlines
+    fn render_text(
         text: &str,
-        font: &F,
+        cache: &mut FontCache,
         options: &TextOptions,
     ) -> Result<Self, TextError> {
+        let FontCache {
+            font,
+            glyphs,
+            glyph_loads,
+        } = cache;
         let scaled_font = font.as_scaled(options.size);
 
         let mut all_points: Vec<(f32, f32)> = Vec::new();
@@ -107,37 +235,76 @@ impl TextShape {
 
         // Process each character
         for ch in text.chars() {
-            let glyph_id = font.glyph_id(ch);
-
-            // Get outline for this glyph
-            if let Some(outline) = font.outline(glyph_id) {
-                let glyph_points = extract_outline_points(
-                    &outline.curves,
-                    cursor_x,
-                    0.0,
-                    options.size,
-                    options.curve_samples,
-                );
-                all_points.extend(glyph_points);
-            }
+            let glyph = glyphs.entry(ch).or_insert_with(|| {
+                *glyph_loads += 1;
+                let id = font.glyph_id(ch);
+                let curves = font.outline(id).map(|o| o.curves).unwrap_or_default();
+                CachedGlyph { id, curves }
+            });
+
+            let glyph_points = extract_outline_points(
+                &glyph.curves,
+                cursor_x,
+                0.0,
+                options.size,
+                options.flattening(),
+            );
+            all_points.extend(glyph_points);
 
             // Advance cursor
-            let h_advance = scaled_font.h_advance(glyph_id);
+            let h_advance = scaled_font.h_advance(glyph.id);
             cursor_x += h_advance * options.letter_spacing;
         }
 
+        Self::from_points(&all_points, Vec::new(), text, options)
+    }
+
+    /// Render text with the built-in single-stroke font
+    fn render_single_stroke(text: &str, options: &TextOptions) -> Result<Self, TextError> {
+        let mut all_points: Vec<(f32, f32)> = Vec::new();
+        let mut pen_up = Vec::new();
+        let mut cursor_x = 0.0f32;
+
+        for ch in text.chars() {
+            // Characters the font lacks are skipped like a space
+            let strokes = stroke_font::glyph(ch).unwrap_or(&[]);
+            for stroke in strokes {
+                // The move from the previous stroke is drawn blanked
+                if !all_points.is_empty() {
+                    pen_up.push(all_points.len() - 1);
+                }
+                all_points.extend(stroke.iter().map(|&(x, y)| (cursor_x + x, y)));
+            }
+            cursor_x += stroke_font::advance(strokes) * options.letter_spacing;
+        }
+
+        Self::from_points(&all_points, pen_up, text, options)
+    }
+
+    /// Normalize laid-out points and build the shape
+    ///
+    /// `pen_up` lists the segments that move between strokes.
+    fn from_points(
+        all_points: &[(f32, f32)],
+        pen_up: Vec<usize>,
+        text: &str,
+        options: &TextOptions,
+    ) -> Result<Self, TextError> {
         if all_points.is_empty() {
             return Err(TextError::NoGlyphs);
         }
 
         // Normalize points to [-1, 1]
-        let normalized = normalize_points(&all_points);
+        let normalized = normalize_points(all_points, options.preserve_aspect);
 
         // Create path
-        let path = Path::with_options(normalized.clone(), false, text.to_string());
+        let mut path = Path::with_options(normalized, false, text.to_string()).with_pen_up(pen_up);
+        if options.recenter {
+            path = path.recenter_centroid();
+        }
 
         Ok(Self {
-            points: normalized,
+            points: path.points().to_vec(),
             path,
             text: text.to_string(),
         })
@@ -159,6 +326,10 @@ impl Shape for TextShape {
         self.path.sample(t)
     }
 
+    fn is_blanked(&self, t: f32) -> bool {
+        self.path.is_blanked(t)
+    }
+
     fn name(&self) -> &str {
         &self.text
     }
@@ -170,6 +341,18 @@ impl Shape for TextShape {
     fn is_closed(&self) -> bool {
         false
     }
+
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        self.path.bounds()
+    }
+
+    fn suggested_samples(&self) -> Option<usize> {
+        Some(self.points.len())
+    }
+
+    fn has_points(&self) -> bool {
+        self.path.has_points()
+    }
 }
 
 /// Extract points from outline curves
@@ -178,7 +361,7 @@ fn extract_outline_points(
     offset_x: f32,
     offset_y: f32,
     scale: f32,
-    curve_samples: usize,
+    flattening: Flattening,
 ) -> Vec<(f32, f32)> {
     let mut points = Vec::new();
 
@@ -198,11 +381,7 @@ fn extract_outline_points(
                 let end = (p2.x * scale + offset_x, p2.y * scale + offset_y);
 
                 points.push(start);
-                for i in 1..=curve_samples {
-                    let t = i as f32 / curve_samples as f32;
-                    let point = quadratic_bezier(start, ctrl, end, t);
-                    points.push(point);
-                }
+                flatten_quadratic(start, ctrl, end, flattening, &mut points);
             }
             OutlineCurve::Cubic(p0, p1, p2, p3) => {
                 // Sample cubic Bézier
@@ -212,11 +391,7 @@ fn extract_outline_points(
                 let end = (p3.x * scale + offset_x, p3.y * scale + offset_y);
 
                 points.push(start);
-                for i in 1..=curve_samples {
-                    let t = i as f32 / curve_samples as f32;
-                    let point = cubic_bezier(start, ctrl1, ctrl2, end, t);
-                    points.push(point);
-                }
+                flatten_cubic(start, ctrl1, ctrl2, end, flattening, &mut points);
             }
         }
     }
@@ -224,81 +399,22 @@ fn extract_outline_points(
     points
 }
 
-/// Evaluate a quadratic Bézier curve at parameter t
-fn quadratic_bezier(p0: (f32, f32), p1: (f32, f32), p2: (f32, f32), t: f32) -> (f32, f32) {
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
-    let t2 = t * t;
-
-    let x = mt2 * p0.0 + 2.0 * mt * t * p1.0 + t2 * p2.0;
-    let y = mt2 * p0.1 + 2.0 * mt * t * p1.1 + t2 * p2.1;
-
-    (x, y)
-}
-
-/// Evaluate a cubic Bézier curve at parameter t
-fn cubic_bezier(
-    p0: (f32, f32),
-    p1: (f32, f32),
-    p2: (f32, f32),
-    p3: (f32, f32),
-    t: f32,
-) -> (f32, f32) {
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
-    let mt3 = mt2 * mt;
-    let t2 = t * t;
-    let t3 = t2 * t;
-
-    let x = mt3 * p0.0 + 3.0 * mt2 * t * p1.0 + 3.0 * mt * t2 * p2.0 + t3 * p3.0;
-    let y = mt3 * p0.1 + 3.0 * mt2 * t * p1.1 + 3.0 * mt * t2 * p2.1 + t3 * p3.1;
-
-    (x, y)
-}
-
 /// Normalize points to [-1, 1] range, centered
-fn normalize_points(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
-    if points.is_empty() {
-        return Vec::new();
-    }
-
-    // Find bounding box
-    let mut min_x = f32::MAX;
-    let mut max_x = f32::MIN;
-    let mut min_y = f32::MAX;
-    let mut max_y = f32::MIN;
-
-    for &(x, y) in points {
-        min_x = min_x.min(x);
-        max_x = max_x.max(x);
-        min_y = min_y.min(y);
-        max_y = max_y.max(y);
-    }
-
-    let width = max_x - min_x;
-    let height = max_y - min_y;
-    let scale = width.max(height);
-
-    if scale <= 0.0 {
-        return points.to_vec();
+///
+/// Text has no frame of its own, so the outline always fits the square.
+/// With `preserve_aspect` off, each axis is stretched to fill [-1, 1].
+/// Font outlines are already Y-up, so no flip is needed here.
+fn normalize_points(points: &[(f32, f32)], preserve_aspect: bool) -> Vec<(f32, f32)> {
+    match Fit::new(points, preserve_aspect) {
+        Some(fit) => points.iter().map(|&p| fit.apply(p)).collect(),
+        None => points.to_vec(),
     }
-
-    let center_x = (min_x + max_x) / 2.0;
-    let center_y = (min_y + max_y) / 2.0;
-
-    points
-        .iter()
-        .map(|&(x, y)| {
-            let nx = (x - center_x) / (scale / 2.0);
-            let ny = -(y - center_y) / (scale / 2.0); // Flip Y for screen coords
-            (nx.clamp(-1.0, 1.0), ny.clamp(-1.0, 1.0))
-        })
-        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shapes::bezier::{cubic_bezier, quadratic_bezier};
 
     #[test]
     fn test_quadratic_bezier() {
@@ -334,13 +450,16 @@ mod tests {
     #[test]
     fn test_normalize_points() {
         let points = vec![(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
-        let normalized = normalize_points(&points);
+        let normalized = normalize_points(&points, true);
 
         // All points should be within [-1, 1]
         for &(x, y) in &normalized {
-            assert!(x >= -1.0 && x <= 1.0);
-            assert!(y >= -1.0 && y <= 1.0);
+            assert!((-1.0..=1.0).contains(&x));
+            assert!((-1.0..=1.0).contains(&y));
         }
+
+        // Y-up input stays Y-up
+        assert!(normalized[2].1 > normalized[0].1);
     }
 
     #[test]
@@ -357,4 +476,83 @@ mod tests {
         assert_eq!(text_shape.text(), "Hi");
         assert!(text_shape.point_count() > 0);
     }
+
+    #[test]
+    fn test_letter_spacing_reuses_cached_glyphs() {
+        let mut font = FontCache::embedded();
+        let mut options = TextOptions::default();
+
+        let narrow = TextShape::with_font("Hello", &mut font, &options).unwrap();
+        // Four distinct letters, each extracted once
+        assert_eq!(font.glyph_loads(), 4);
+
+        options.letter_spacing = 2.0;
+        let wide = TextShape::with_font("Hello", &mut font, &options).unwrap();
+        assert_eq!(font.glyph_loads(), 4);
+        assert_eq!(narrow.point_count(), wide.point_count());
+        assert_ne!(narrow.path.points(), wide.path.points());
+    }
+
+    #[test]
+    fn test_single_stroke_i_is_one_line() {
+        let xs = |shape: &TextShape| -> (f32, f32) {
+            shape
+                .path
+                .points()
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+                    (lo.min(p.0), hi.max(p.0))
+                })
+        };
+
+        let stroke = TextShape::new(
+            "I",
+            &TextOptions {
+                style: TextStyle::SingleStroke,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // One vertical stroke: two points sharing an X, spanning the height
+        assert_eq!(stroke.point_count(), 2);
+        let (min_x, max_x) = xs(&stroke);
+        assert_eq!(min_x, max_x);
+        let ys: Vec<f32> = stroke.path.points().iter().map(|p| p.1).collect();
+        assert!((ys[0] - ys[1]).abs() > 1.5);
+
+        // The outline version encloses an area instead
+        let outline = TextShape::new("I", &TextOptions::default()).unwrap();
+        let (min_x, max_x) = xs(&outline);
+        assert!(max_x - min_x > 0.1);
+    }
+
+    #[test]
+    fn test_single_stroke_blanks_moves_between_strokes() {
+        let options = TextOptions {
+            style: TextStyle::SingleStroke,
+            ..Default::default()
+        };
+        // E is an open bracket then a separate middle bar
+        let shape = TextShape::new("E", &options).unwrap();
+        assert_eq!(shape.point_count(), 6);
+        assert_eq!(shape.path.pen_up(), &[3]);
+
+        // Parameter at the middle of each segment
+        let points = shape.path.points();
+        let lengths: Vec<f32> = points
+            .windows(2)
+            .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
+            .collect();
+        let total: f32 = lengths.iter().sum();
+        let mut start = 0.0;
+        for (i, len) in lengths.iter().enumerate() {
+            let t = (start + len / 2.0) / total;
+            assert_eq!(shape.is_blanked(t), i == 3, "segment {i}");
+            start += len;
+        }
+
+        // Lowercase has its own glyphs
+        let lower = TextShape::new("e", &options).unwrap();
+        assert_ne!(lower.path.points(), shape.path.points());
+    }
 }
@@ -23,6 +23,9 @@
 //! }
 //! ```
 
+/// Number of points the default `Shape::bounds` samples
+pub const BOUNDS_SAMPLES: usize = 256;
+
 /// A shape that can be drawn on an oscilloscope
 ///
 /// Shapes are defined parametrically - the `sample` method takes a parameter
@@ -41,6 +44,11 @@
 /// - (0, 0) is the center
 /// - (-1, -1) is bottom-left, (1, 1) is top-right
 ///
+/// Shapes are always Y-up. Importers whose source data is Y-down (SVG,
+/// raster images) flip once while normalizing; nothing downstream flips
+/// again. Output polarity for scopes wired the other way round is handled
+/// by the global `invert_y` switch on the audio engine, not per shape.
+///
 /// ## Thread Safety
 ///
 /// Shapes must be `Send + Sync` so they can be shared with the audio thread.
@@ -75,6 +83,70 @@ pub trait Shape: Send + Sync {
     fn is_closed(&self) -> bool {
         true
     }
+
+    /// Whether the beam should be off at parameter t
+    ///
+    /// Shapes made of separate strokes, like single-stroke lettering,
+    /// travel between strokes with the pen up. The audio engine blanks
+    /// those samples on the Z output.
+    ///
+    /// Default implementation returns false (always drawn).
+    fn is_blanked(&self, _t: f32) -> bool {
+        false
+    }
+
+    /// Suggested number of samples per trace for this shape
+    ///
+    /// Dense geometry (meshes, traced images, long text) needs more points
+    /// than a circle to avoid under-sampling. The audio engine uses this
+    /// as a lower bound, capped to keep the trace rate reasonable.
+    ///
+    /// Default implementation returns None (no preference).
+    fn suggested_samples(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the shape has anything to draw
+    ///
+    /// Shapes built from a point list (files, text, traced images) can end
+    /// up with none; they sample as the origin, but the audio engine can
+    /// refuse them instead.
+    ///
+    /// Default implementation returns true.
+    fn has_points(&self) -> bool {
+        true
+    }
+
+    /// A problem with the shape worth showing the user, if any
+    ///
+    /// The audio engine reports it alongside its own warnings.
+    ///
+    /// Default implementation returns None.
+    fn warning(&self) -> Option<String> {
+        None
+    }
+
+    /// Bounding box as (min_x, min_y, max_x, max_y)
+    ///
+    /// The default samples the shape at `BOUNDS_SAMPLES` points, so sharp
+    /// extremes between samples can be missed. Shapes backed by a point
+    /// list override this with exact bounds.
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        bounds_of((0..BOUNDS_SAMPLES).map(|i| self.sample(i as f32 / BOUNDS_SAMPLES as f32)))
+    }
+}
+
+/// Bounding box of a set of points as (min_x, min_y, max_x, max_y)
+///
+/// Returns all zeros for an empty set.
+pub fn bounds_of(points: impl IntoIterator<Item = (f32, f32)>) -> (f32, f32, f32, f32) {
+    let mut points = points.into_iter();
+    let Some((x, y)) = points.next() else {
+        return (0.0, 0.0, 0.0, 0.0);
+    };
+    points.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), (x, y)| {
+        (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+    })
 }
 
 /// A boxed shape for dynamic dispatch
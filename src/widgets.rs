@@ -0,0 +1,94 @@
+//! Shared UI widgets
+//!
+//! egui sliders nudge by about a pixel's worth of range when arrow keys are
+//! pressed, so the step differs from slider to slider and with window size.
+//! `nudge_slider` gives every slider in the app the same keyboard steps: a
+//! fine step per arrow press, and a coarse one with Shift held.
+
+use std::ops::RangeInclusive;
+
+use eframe::egui::{self, emath::Numeric, Key};
+
+/// Arrow key steps across a slider's full range
+const FINE_STEPS: f64 = 1000.0;
+
+/// Shift+arrow key steps across a slider's full range
+const COARSE_STEPS: f64 = 20.0;
+
+/// Add a slider over `value` whose arrow keys nudge by consistent steps
+///
+/// `build` configures the slider (text, logarithmic, suffix...) as usual.
+pub fn nudge_slider<Num: Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut Num,
+    range: RangeInclusive<Num>,
+    build: impl for<'s> FnOnce(egui::Slider<'s>) -> egui::Slider<'s>,
+) -> egui::Response {
+    let before = value.to_f64();
+    let mut response = ui.add(build(egui::Slider::new(value, range.clone())));
+
+    if response.has_focus() {
+        let (presses, coarse) = ui.input(|input| {
+            let presses = input.num_presses(Key::ArrowRight) as i32
+                + input.num_presses(Key::ArrowUp) as i32
+                - input.num_presses(Key::ArrowLeft) as i32
+                - input.num_presses(Key::ArrowDown) as i32;
+            (presses, input.modifiers.shift)
+        });
+        if presses != 0 {
+            // Replace egui's own pixel-sized step with ours
+            let range = range.start().to_f64()..=range.end().to_f64();
+            let step = nudge_step(&range, Num::INTEGRAL, coarse);
+            *value = Num::from_f64(nudge(before, presses, step, &range));
+            response.mark_changed();
+        }
+    }
+
+    response
+}
+
+/// Size of one keyboard step over `range`
+fn nudge_step(range: &RangeInclusive<f64>, integral: bool, coarse: bool) -> f64 {
+    let steps = if coarse { COARSE_STEPS } else { FINE_STEPS };
+    let step = (range.end() - range.start()).abs() / steps;
+    if integral {
+        step.round().max(1.0)
+    } else {
+        step
+    }
+}
+
+/// Move `value` by `presses` steps, staying inside `range`
+fn nudge(value: f64, presses: i32, step: f64, range: &RangeInclusive<f64>) -> f64 {
+    let (lo, hi) = if range.start() <= range.end() {
+        (*range.start(), *range.end())
+    } else {
+        (*range.end(), *range.start())
+    };
+    (value + presses as f64 * step).clamp(lo, hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nudge_moves_by_exact_step() {
+        let range = 0.0..=2.0;
+        let fine = nudge_step(&range, false, false);
+        let coarse = nudge_step(&range, false, true);
+        assert_eq!(fine, 0.002);
+        assert_eq!(coarse, 0.1);
+
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+        assert!(close(nudge(1.0, 1, fine, &range), 1.002));
+        assert!(close(nudge(1.0, -3, coarse, &range), 0.7));
+
+        // Held inside the range
+        assert_eq!(nudge(1.95, 1, coarse, &range), 2.0);
+
+        // Integer sliders always move by at least one
+        assert_eq!(nudge_step(&(2.0..=20.0), true, false), 1.0);
+        assert_eq!(nudge_step(&(0.0..=200.0), true, true), 10.0);
+    }
+}
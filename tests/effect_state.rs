@@ -0,0 +1,65 @@
+//! Stateful effects must never allocate on the audio thread
+//!
+//! Counting allocations needs a `#[global_allocator]`, which applies to the
+//! whole test binary, so this lives apart from the unit tests. The effect
+//! modules are pulled in by path since the crate has no library target.
+
+#![allow(dead_code)]
+
+#[path = "../src/effects/state.rs"]
+mod state;
+#[path = "../src/effects/traits.rs"]
+mod traits;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::Arc;
+
+use state::{AtomicRng, Jitter};
+use traits::Effect;
+
+/// Counts allocations made on the current thread
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+#[test]
+fn test_jitter_is_deterministic_and_allocation_free() {
+    let run = |seed| {
+        let jitter = Jitter::new(0.1, Arc::new(AtomicRng::new(seed)));
+        let before = allocations();
+        let mut out = [(0.0, 0.0); 64];
+        for (i, point) in out.iter_mut().enumerate() {
+            *point = jitter.apply(0.5, -0.5, i as f32);
+        }
+        assert_eq!(allocations(), before, "apply allocated");
+        out
+    };
+
+    let a = run(42);
+    assert_eq!(a, run(42));
+    assert_ne!(a, run(7));
+    assert!(a
+        .iter()
+        .all(|&(x, y)| (x - 0.5).abs() <= 0.1 && (y + 0.5).abs() <= 0.1));
+}